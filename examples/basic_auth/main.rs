@@ -19,7 +19,7 @@ fn home(auth: Option<GetHeader<BasicAuth>>) -> Result<HttpResponse, ErrorRespons
     match auth {
         Some(GetHeader(auth)) => {
             if !authenticate(&auth) {
-                return Ok(HttpResponse::from_response(www_auth.clone()).finish());
+                return Ok(HttpResponse::from_response(www_auth.clone()));
             }
 
             let html = html::html(|| {
@@ -33,9 +33,9 @@ fn home(auth: Option<GetHeader<BasicAuth>>) -> Result<HttpResponse, ErrorRespons
                 });
             });
 
-            Ok(HttpResponse::from_response(html).finish())
+            Ok(HttpResponse::from_response(html))
         }
-        None => Ok(HttpResponse::from_response(www_auth.clone()).finish()),
+        None => Ok(HttpResponse::from_response(www_auth.clone())),
     }
 }
 
@@ -1,5 +1,4 @@
 use app::db::KeyValueDatabase;
-use datetime::DateTime;
 use http1::common::uuid::Uuid;
 use http1::{
     body::Body, common::broadcast::Broadcast, request::Request, response::Response, server::Server,
@@ -11,12 +10,12 @@ use http1_web::{
 };
 use http1_web::{
     app::Scope,
-    cookies::Cookie,
+    cookies::{Cookie, CookieJar},
     forms::form::Form,
     json::Json,
     state::State,
     ws::{Message, WebSocketUpgrade},
-    ErrorResponse, HttpResponse,
+    ErrorResponse,
 };
 use models::{ChatMessage, ChatUser};
 use serde::impl_serde_struct;
@@ -84,26 +83,21 @@ impl_serde_struct!(LoginInput => {
     username: String
 });
 
-fn login(Form(input): Form<LoginInput>) -> Result<HttpResponse, ErrorResponse> {
+fn login(mut jar: CookieJar, Form(input): Form<LoginInput>) -> Result<Redirect, ErrorResponse> {
     let session_id = http1::common::base64::encode(input.username);
-    let cookie = Cookie::new(crate::constants::COOKIE_AUTH_SESSION, session_id)
-        .path("/")
-        .http_only(true)
-        .max_age(crate::constants::SESSION_DURATION_SECS);
-
-    let res = HttpResponse::see_other("/").set_cookie(cookie).finish();
-
-    Ok(res)
+    jar.set(
+        Cookie::new(crate::constants::COOKIE_AUTH_SESSION, session_id)
+            .path("/")
+            .http_only(true)
+            .max_age(crate::constants::SESSION_DURATION_SECS),
+    );
+
+    Ok(Redirect::see_other("/"))
 }
 
-fn logout(_user: ChatUser) -> HttpResponse {
-    let cookie = Cookie::new(crate::constants::COOKIE_AUTH_SESSION, "")
-        .path("/")
-        .expires(DateTime::UNIX_EPOCH);
-
-    HttpResponse::see_other("/login")
-        .set_cookie(cookie)
-        .finish()
+fn logout(_user: ChatUser, mut jar: CookieJar) -> Redirect {
+    jar.remove(crate::constants::COOKIE_AUTH_SESSION);
+    Redirect::see_other("/login")
 }
 
 fn me(user: ChatUser) -> Json<ChatUser> {
@@ -167,7 +161,7 @@ fn join_chat(
 
                 let message_json = serde::json::to_string(&msg).unwrap();
                 log::info!("broadcasting message from user: {}", user.username);
-                tx.send(message_json).expect("failed to send message");
+                tx.send_text(message_json).expect("failed to send message");
             })
             .unwrap()
     };
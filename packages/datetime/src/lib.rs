@@ -61,6 +61,23 @@ impl Month {
             Month::December => "December",
         }
     }
+
+    pub fn as_short_str(&self) -> &'static str {
+        match self {
+            Month::January => "Jan",
+            Month::February => "Feb",
+            Month::March => "Mar",
+            Month::April => "Apr",
+            Month::May => "May",
+            Month::June => "Jun",
+            Month::July => "Jul",
+            Month::August => "Aug",
+            Month::September => "Sep",
+            Month::October => "Oct",
+            Month::November => "Nov",
+            Month::December => "Dec",
+        }
+    }
 }
 
 impl Display for Month {
@@ -337,7 +354,7 @@ impl DateTime {
     pub fn to_rfc_1123_string(&self) -> String {
         let day_of_week = self.day_of_week().as_short_str(); // e.g. "Wed"
         let day = self.day_of_month(); // e.g. "09"
-        let month = self.month().as_str(); // e.g. "Jun"
+        let month = self.month().as_short_str(); // e.g. "Jun"
         let year = self.year(); // e.g. 2021
         let hours = self.hours(); // e.g. 10
         let minutes = self.minutes(); // e.g. 18
@@ -364,62 +381,125 @@ impl DateTime {
 pub struct DateTimeParseError;
 
 impl DateTime {
+    /// Parses a date in the `RFC 1123` format, for example: `Wed, 09 Jun 2021 10:18:14 GMT`.
     pub fn parse_rfc_1123(s: &str) -> Result<Self, DateTimeParseError> {
-        // Example: "Wed, 09 Jun 2021 10:18:14 GMT"
         let parts: Vec<&str> = s.split_whitespace().collect();
-        if parts.len() != 6 {
+        if parts.len() != 6 || parts[5] != "GMT" {
             return Err(DateTimeParseError);
         }
 
-        // Parse day of month
-        let day = parts[1].parse::<u8>().map_err(|_| DateTimeParseError)?;
-
-        // Parse month
-        let month_idx = match parts[2].to_lowercase().as_str() {
-            "jan" => 0,
-            "feb" => 1,
-            "mar" => 2,
-            "apr" => 3,
-            "may" => 4,
-            "jun" => 5,
-            "jul" => 6,
-            "aug" => 7,
-            "sep" => 8,
-            "oct" => 9,
-            "nov" => 10,
-            "dec" => 11,
-            _ => return Err(DateTimeParseError),
-        };
-        let month = Month::try_from(month_idx).map_err(|_| DateTimeParseError)?;
+        let day = parse_number(parts[1])?;
+        let month = parse_short_month(parts[2])?;
+        let year = parse_number(parts[3])?;
+        build_date(year, month, day, parts[4])
+    }
 
-        // Parse year
-        let year = parts[3].parse::<u32>().map_err(|_| DateTimeParseError)?;
+    /// Parses a date in any of the formats allowed in http headers, `RFC 1123`, `RFC 850` or `asctime`:
+    ///
+    /// - `Sun, 06 Nov 1994 08:49:37 GMT`
+    /// - `Sunday, 06-Nov-94 08:49:37 GMT`
+    /// - `Sun Nov  6 08:49:37 1994`
+    ///
+    /// Use [`DateTime::to_rfc_1123_string`] for formatting dates in headers.
+    pub fn parse_http_date(s: &str) -> Result<Self, DateTimeParseError> {
+        Self::parse_rfc_1123(s)
+            .or_else(|_| parse_rfc_850(s))
+            .or_else(|_| parse_asctime(s))
+    }
+}
 
-        // Parse time (HH:MM:SS)
-        let time_parts: Vec<&str> = parts[4].split(':').collect();
-        if time_parts.len() != 3 {
-            return Err(DateTimeParseError);
-        }
+fn parse_rfc_850(s: &str) -> Result<DateTime, DateTimeParseError> {
+    // Example: "Sunday, 06-Nov-94 08:49:37 GMT"
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    if parts.len() != 4 || parts[3] != "GMT" {
+        return Err(DateTimeParseError);
+    }
 
-        let hours = time_parts[0]
-            .parse::<u8>()
-            .map_err(|_| DateTimeParseError)?;
-        let minutes = time_parts[1]
-            .parse::<u8>()
-            .map_err(|_| DateTimeParseError)?;
-        let secs = time_parts[2]
-            .parse::<u8>()
-            .map_err(|_| DateTimeParseError)?;
-
-        Ok(DateTime::builder()
-            .day(day)
-            .month(month)
-            .year(year)
-            .hours(hours)
-            .minutes(minutes)
-            .secs(secs)
-            .build())
+    let date_parts: Vec<&str> = parts[1].split('-').collect();
+    if date_parts.len() != 3 || date_parts[2].len() != 2 {
+        return Err(DateTimeParseError);
     }
+
+    let day = parse_number(date_parts[0])?;
+    let month = parse_short_month(date_parts[1])?;
+
+    // Two digits years before 70 are in the 2000s
+    let year: u32 = parse_number(date_parts[2])?;
+    let year = if year < 70 { 2000 + year } else { 1900 + year };
+
+    build_date(year, month, day, parts[2])
+}
+
+fn parse_asctime(s: &str) -> Result<DateTime, DateTimeParseError> {
+    // Example: "Sun Nov  6 08:49:37 1994"
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    if parts.len() != 5 {
+        return Err(DateTimeParseError);
+    }
+
+    let month = parse_short_month(parts[1])?;
+    let day = parse_number(parts[2])?;
+    let year = parse_number(parts[4])?;
+    build_date(year, month, day, parts[3])
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, DateTimeParseError> {
+    s.parse::<T>().map_err(|_| DateTimeParseError)
+}
+
+fn parse_short_month(s: &str) -> Result<Month, DateTimeParseError> {
+    let month_idx = match s.to_lowercase().as_str() {
+        "jan" => 0,
+        "feb" => 1,
+        "mar" => 2,
+        "apr" => 3,
+        "may" => 4,
+        "jun" => 5,
+        "jul" => 6,
+        "aug" => 7,
+        "sep" => 8,
+        "oct" => 9,
+        "nov" => 10,
+        "dec" => 11,
+        _ => return Err(DateTimeParseError),
+    };
+
+    Month::try_from(month_idx).map_err(|_| DateTimeParseError)
+}
+
+/// Builds the date from the parsed parts and the time (HH:MM:SS).
+fn build_date(
+    year: u32,
+    month: Month,
+    day: u8,
+    time: &str,
+) -> Result<DateTime, DateTimeParseError> {
+    let time_parts: Vec<&str> = time.split(':').collect();
+    if time_parts.len() != 3 {
+        return Err(DateTimeParseError);
+    }
+
+    let hours = parse_number::<u8>(time_parts[0])?;
+    let minutes = parse_number::<u8>(time_parts[1])?;
+    let secs = parse_number::<u8>(time_parts[2])?;
+
+    if year < YEAR_EPOCH
+        || !(1..=31).contains(&day)
+        || hours > 23
+        || minutes > 59
+        || secs > 59
+    {
+        return Err(DateTimeParseError);
+    }
+
+    Ok(DateTime::builder()
+        .day(day)
+        .month(month)
+        .year(year)
+        .hours(hours)
+        .minutes(minutes)
+        .secs(secs)
+        .build())
 }
 
 impl Display for DateTime {
@@ -638,6 +718,46 @@ mod tests {
         assert_eq!(dt.to_iso_8601_string(), "2025-01-03T16:15:42.555Z")
     }
 
+    #[test]
+    fn should_format_and_parse_rfc_1123() {
+        let dt = DateTime::builder()
+            .year(2024)
+            .month(Month::September)
+            .day(30)
+            .hours(8)
+            .minutes(5)
+            .secs(9)
+            .build();
+
+        let s = dt.to_rfc_1123_string();
+        assert_eq!(s, "Mon, 30 Sep 2024 08:05:09 GMT");
+        assert_eq!(DateTime::parse_rfc_1123(&s).unwrap(), dt);
+    }
+
+    #[test]
+    fn should_parse_all_http_date_formats() {
+        let dt = DateTime::builder()
+            .year(1994)
+            .month(Month::November)
+            .day(6)
+            .hours(8)
+            .minutes(49)
+            .secs(37)
+            .build();
+
+        for s in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            assert_eq!(DateTime::parse_http_date(s).unwrap(), dt, "{s}");
+        }
+
+        assert_eq!(dt.to_rfc_1123_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert!(DateTime::parse_http_date("Sun, 00 Nov 1994 08:49:37 GMT").is_err());
+        assert!(DateTime::parse_http_date("1994-11-06T08:49:37Z").is_err());
+    }
+
     #[test]
     fn should_get_day_of_week() {
         let dt = DateTime::with_yymmdd(2024, Month::September, 30);
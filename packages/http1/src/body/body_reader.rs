@@ -33,23 +33,37 @@ impl FixedLengthBodyReader<()> {
     }
 }
 
+impl<R> FixedLengthBodyReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
 impl<R: Read> HttpBody for FixedLengthBodyReader<R> {
     type Err = std::io::Error;
     type Data = Vec<u8>;
 
     fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
-        if let Some(max_body_size) = self.max_body_size {
-            if self.read_bytes >= max_body_size {
-                return Err(body_limit_error(self.read_bytes, max_body_size));
+        if let Some(content_length) = self.content_length {
+            // No need to read the body if we know is too large
+            if let Some(max_body_size) = self.max_body_size {
+                if content_length > max_body_size {
+                    return Err(body_limit_error(content_length, max_body_size));
+                }
             }
-        }
 
-        if let Some(content_length) = self.content_length {
             if self.read_bytes >= content_length {
                 return Ok(None);
             }
         }
 
+        if let Some(max_body_size) = self.max_body_size {
+            if self.read_bytes >= max_body_size {
+                return Err(body_limit_error(self.read_bytes, max_body_size));
+            }
+        }
+
         let expected_len = match self.content_length {
             Some(content_length) => match content_length.checked_sub(self.read_bytes) {
                 Some(n) => n.min(self.buffer.len()),
@@ -93,71 +107,178 @@ impl<R: Read> HttpBody for FixedLengthBodyReader<R> {
     }
 }
 
+/// Decodes a body using `Transfer-Encoding: chunked`.
+///
+/// Large chunks are returned in pieces of at most 4kb, so the chunk size sent by the client is never allocated at once.
 pub struct ChunkedBodyReader<R> {
     reader: BufReader<R>,
     read_bytes: usize,
+    chunk_remaining: usize,
     max_body_size: Option<usize>,
+    is_done: bool,
 }
 
 impl ChunkedBodyReader<()> {
     pub fn new<R>(reader: R, max_body_size: Option<usize>) -> ChunkedBodyReader<R>
+    where
+        R: Read,
+    {
+        Self::with_buf_reader(BufReader::new(reader), max_body_size)
+    }
+
+    /// Constructs a reader from a `BufReader` that may already contain buffered data.
+    pub fn with_buf_reader<R>(
+        reader: BufReader<R>,
+        max_body_size: Option<usize>,
+    ) -> ChunkedBodyReader<R>
     where
         R: Read,
     {
         ChunkedBodyReader {
-            reader: BufReader::new(reader),
+            reader,
             read_bytes: 0,
+            chunk_remaining: 0,
             max_body_size,
+            is_done: false,
         }
     }
 }
 
+impl<R> ChunkedBodyReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> BufReader<R> {
+        self.reader
+    }
+}
+
 impl<R: Read> HttpBody for ChunkedBodyReader<R> {
     type Err = std::io::Error;
     type Data = Vec<u8>;
 
     fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
-        if let Some(max_body_size) = self.max_body_size {
-            if self.read_bytes >= max_body_size {
-                return Err(body_limit_error(self.read_bytes, max_body_size));
+        if self.is_done {
+            return Ok(None);
+        }
+
+        if self.chunk_remaining == 0 {
+            match self.read_chunk_size()? {
+                0 => {
+                    self.is_done = true;
+                    return Ok(None);
+                }
+                chunk_length => self.chunk_remaining = chunk_length,
             }
         }
 
+        // Read the next piece of the chunk, the last one is followed by \r\n
+        let len = self.chunk_remaining.min(DEFAULT_BUFFER_SIZE);
+        let mut byte_buf = vec![0; len];
+        self.reader.read_exact(&mut byte_buf)?;
+
+        self.chunk_remaining -= len;
+        self.read_bytes += len;
+
+        if self.chunk_remaining == 0 {
+            let mut crlf = [0; 2];
+            self.reader.read_exact(&mut crlf)?;
+
+            if &crlf != b"\r\n" {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "chunk data is not followed by CRLF",
+                ));
+            }
+        }
+
+        Ok(Some(byte_buf))
+    }
+}
+
+impl<R: Read> ChunkedBodyReader<R> {
+    /// Reads the chunk size line, for the last chunk the trailers are skipped.
+    fn read_chunk_size(&mut self) -> std::io::Result<usize> {
         let mut str_buf = String::new();
-        let mut byte_buf = Vec::new();
 
-        // Read the chunk size line: {size in hex}\r\n
-        self.reader.read_line(&mut str_buf)?;
-        self.read_bytes += str_buf.as_bytes().len();
+        // Read the chunk size line: {size in hex}[;extensions]\r\n
+        if self.reader.read_line(&mut str_buf)? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "chunked body incomplete",
+            ));
+        }
 
-        let chunk_length = usize::from_str_radix(str_buf.trim(), 16).map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid chunk length")
-        })?;
+        let invalid_chunk_length =
+            || std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid chunk length");
+
+        // `from_str_radix` also accepts a leading `+`, which is not valid in a chunk size
+        let size = str_buf.split(';').next().unwrap_or_default().trim();
+        if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid_chunk_length());
+        }
+
+        let chunk_length = usize::from_str_radix(size, 16).map_err(|_| invalid_chunk_length())?;
+
+        let body_size = match self.read_bytes.checked_add(chunk_length) {
+            Some(body_size) => body_size,
+            None => {
+                return Err(match self.max_body_size {
+                    Some(max_body_size) => body_limit_error(usize::MAX, max_body_size),
+                    None => invalid_chunk_length(),
+                })
+            }
+        };
 
         if let Some(max_body_size) = self.max_body_size {
-            if self.read_bytes + chunk_length > max_body_size {
-                return Err(body_limit_error(self.read_bytes, max_body_size));
+            if body_size > max_body_size {
+                return Err(body_limit_error(body_size, max_body_size));
             }
         }
 
-        // End of chunks
+        // End of chunks, skip the trailers until the empty line
         if chunk_length == 0 {
-            return Ok(None);
+            loop {
+                str_buf.clear();
+                if self.reader.read_line(&mut str_buf)? == 0 || str_buf.trim().is_empty() {
+                    break;
+                }
+            }
         }
 
-        // Read the chunk
-        self.reader.read_exact(&mut byte_buf)?;
-        self.read_bytes += byte_buf.len();
+        Ok(chunk_length)
+    }
+}
 
-        Ok(Some(byte_buf))
+/// Error returned when the body is larger than the max body size.
+#[derive(Debug)]
+pub struct BodyLimitExceeded {
+    body_size: usize,
+    max_body_size: usize,
+}
+
+impl BodyLimitExceeded {
+    /// Checks if the given error was caused by a body exceeding the size limit.
+    pub fn is_body_limit_error(err: &std::io::Error) -> bool {
+        err.get_ref().is_some_and(|e| e.is::<BodyLimitExceeded>())
     }
 }
 
-fn body_limit_error(read_bytes: usize, max_body_size: usize) -> std::io::Error {
-    std::io::Error::new(
-        std::io::ErrorKind::Other,
-        format!("Max request body size reached `{read_bytes} >= {max_body_size}` bytes",),
-    )
+impl std::fmt::Display for BodyLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Max request body size reached `{} >= {}` bytes",
+            self.body_size, self.max_body_size
+        )
+    }
+}
+
+impl std::error::Error for BodyLimitExceeded {}
+
+pub(crate) fn body_limit_error(body_size: usize, max_body_size: usize) -> std::io::Error {
+    std::io::Error::other(BodyLimitExceeded {
+        body_size,
+        max_body_size,
+    })
 }
 
 pub struct BodyReader {
@@ -227,9 +348,9 @@ impl Read for BodyReader {
 mod tests {
     use std::io::Read;
 
-    use crate::body::Body;
+    use crate::body::{http_body::HttpBody, Body};
 
-    use super::BodyReader;
+    use super::{BodyLimitExceeded, BodyReader, ChunkedBodyReader};
 
     #[test]
     fn should_read_complete_body() {
@@ -306,4 +427,86 @@ mod tests {
 
         assert_eq!(reader.read(buf).unwrap(), 0); // No more data to read
     }
+
+    #[test]
+    fn should_read_chunked_body_within_limit() {
+        let data = "5\r\nHello\r\n6;ext=1\r\n World\r\n0\r\n\r\n";
+        let mut reader = ChunkedBodyReader::new(data.as_bytes(), Some(11));
+
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"Hello");
+        assert_eq!(reader.read_next().unwrap().unwrap(), b" World");
+        assert!(reader.read_next().unwrap().is_none());
+
+        let mut reader = ChunkedBodyReader::new(data.as_bytes(), Some(10));
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"Hello");
+
+        let err = reader.read_next().unwrap_err();
+        assert!(BodyLimitExceeded::is_body_limit_error(&err));
+    }
+
+    #[test]
+    fn should_decode_chunked_body() {
+        let data = "5\r\nhello\r\n0\r\n\r\n";
+        let mut body = Body::new(ChunkedBodyReader::new(data.as_bytes(), None));
+
+        assert_eq!(body.read_all_bytes().unwrap(), b"hello");
+        assert!(body.read_next().unwrap().is_none());
+
+        // Trailers are skipped
+        let data = "5\r\nhello\r\n0\r\nExpires: never\r\n\r\nGET";
+        let mut reader = ChunkedBodyReader::new(data.as_bytes(), None);
+
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"hello");
+        assert!(reader.read_next().unwrap().is_none());
+
+        let mut rest = String::new();
+        reader.into_inner().read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "GET");
+
+        // Incomplete or malformed chunks
+        let mut reader = ChunkedBodyReader::new("5\r\nhello\r\n".as_bytes(), None);
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"hello");
+        assert!(reader.read_next().is_err());
+
+        let mut reader = ChunkedBodyReader::new("5\r\nhello world\r\n".as_bytes(), None);
+        assert!(reader.read_next().is_err());
+        let mut reader = ChunkedBodyReader::new("+5\r\nhello\r\n0\r\n\r\n".as_bytes(), None);
+        assert!(reader.read_next().is_err());
+    }
+
+    #[test]
+    fn should_reject_chunk_length_overflow() {
+        let data = "1\r\na\r\nffffffffffffffff\r\n";
+
+        let mut reader = ChunkedBodyReader::new(data.as_bytes(), Some(1024));
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"a");
+        let err = reader.read_next().unwrap_err();
+        assert!(BodyLimitExceeded::is_body_limit_error(&err));
+
+        let mut reader = ChunkedBodyReader::new(data.as_bytes(), None);
+        assert_eq!(reader.read_next().unwrap().unwrap(), b"a");
+        let err = reader.read_next().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn should_read_large_chunk_in_pieces() {
+        let mut data = b"2710\r\n".to_vec(); // 10000 bytes
+        data.extend(std::iter::repeat_n(b'a', 10_000));
+        data.extend_from_slice(b"\r\n0\r\n\r\n");
+
+        let mut reader = ChunkedBodyReader::new(data.as_slice(), None);
+        let mut total = 0;
+
+        while let Some(piece) = reader.read_next().unwrap() {
+            assert!(piece.len() <= 4 * 1024);
+            total += piece.len();
+        }
+
+        assert_eq!(total, 10_000);
+
+        // A huge chunk size without a body limit is not allocated up front
+        let mut reader = ChunkedBodyReader::new("ffffffffffff\r\nabc".as_bytes(), None);
+        assert!(reader.read_next().is_err());
+    }
 }
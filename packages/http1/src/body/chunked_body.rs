@@ -1,9 +1,12 @@
 use std::{
     io::{BufRead, BufReader, Read, Write},
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, OnceLock,
+    },
 };
 
-use crate::error::BoxError;
+use crate::{error::BoxError, headers::Headers};
 
 use super::{http_body::HttpBody, Body};
 
@@ -60,10 +63,24 @@ impl<T: AsRef<[u8]> + Send + 'static> From<ChunkedBody<T>> for Body {
     }
 }
 
+/// The trailer headers sent after the last chunk of a chunked body.
+///
+/// The trailers are only available after the body was read completely.
+#[derive(Default, Clone, Debug)]
+pub struct Trailers(Arc<OnceLock<Headers>>);
+
+impl Trailers {
+    /// Returns the trailer headers, or `None` if the body was not read yet.
+    pub fn get(&self) -> Option<&Headers> {
+        self.0.get()
+    }
+}
+
 pub struct ReadChunkedBody<R> {
     reader: BufReader<R>,
     buf: Vec<u8>,
     eof: bool,
+    trailers: Trailers,
 }
 
 impl<R> ReadChunkedBody<R> {
@@ -72,8 +89,14 @@ impl<R> ReadChunkedBody<R> {
             reader,
             buf: Vec::new(),
             eof: false,
+            trailers: Trailers::default(),
         }
     }
+
+    /// Returns a handle to the trailers of this body.
+    pub fn trailers(&self) -> Trailers {
+        self.trailers.clone()
+    }
 }
 
 impl<R> ReadChunkedBody<R>
@@ -106,24 +129,20 @@ where
         }
     }
 
-    fn read_empty_line(&mut self) -> std::io::Result<()> {
-        if let Some(line) = self.read_line(0)? {
-            if !line.is_empty() {
-                return Err(std::io::Error::other(format!(
-                    "expected chunk ending `\r\n` but was `\r\n{:?}`",
-                    String::from_utf8_lossy(&line)
-                )));
-            }
-        }
-
+    fn read_trailers(&mut self) -> std::io::Result<()> {
+        let mut buf = String::new();
+        let headers = crate::protocol::h1::request::read_headers(&mut self.reader, &mut buf)?;
+        let _ = self.trailers.0.set(headers);
         Ok(())
     }
 
     fn read_chunk_size(&mut self) -> std::io::Result<usize> {
         match self.read_line(1)? {
             Some(bytes) => {
-                let hex = String::from_utf8_lossy(&bytes);
-                let chunk_len = usize::from_str_radix(&hex, 16).map_err(std::io::Error::other)?;
+                // The chunk size can be followed by extensions: {size in hex}[;extensions]
+                let line = String::from_utf8_lossy(&bytes);
+                let hex = line.split(';').next().unwrap_or_default().trim();
+                let chunk_len = usize::from_str_radix(hex, 16).map_err(std::io::Error::other)?;
                 Ok(chunk_len)
             }
             None => Err(std::io::Error::other("chunk length not found")),
@@ -165,7 +184,7 @@ impl<R: Read> HttpBody for ReadChunkedBody<R> {
             Ok(Some(chunk))
         } else {
             self.eof = true;
-            self.read_empty_line()?;
+            self.read_trailers()?;
             Ok(None)
         }
     }
@@ -175,7 +194,7 @@ impl<R: Read> HttpBody for ReadChunkedBody<R> {
 mod tests {
     use std::io::{BufReader, Read};
 
-    use crate::body::{body_reader::BodyReader, Body};
+    use crate::body::{body_reader::BodyReader, http_body::HttpBody, Body};
 
     use super::{ChunkedBody, ReadChunkedBody};
 
@@ -212,6 +231,23 @@ mod tests {
         assert_eq!(read_exact(&mut reader, 5), "0\r\n\r\n");
     }
 
+    #[test]
+    fn should_read_chunked_body_trailers() {
+        let data = "7;name=value\r\nChunk 1\r\n0\r\nExpires: never\r\nX-Checksum: abc\r\n\r\n";
+        let buf_reader = BufReader::new(data.as_bytes());
+
+        let read_chunked_body = ReadChunkedBody::new(buf_reader);
+        let trailers = read_chunked_body.trailers();
+        let mut body = Body::new(read_chunked_body);
+        assert!(trailers.get().is_none());
+
+        assert_eq!(body.read_all_bytes().unwrap(), b"Chunk 1");
+
+        let trailers = trailers.get().unwrap();
+        assert_eq!(trailers.get("Expires").unwrap().as_str(), "never");
+        assert_eq!(trailers.get("X-Checksum").unwrap().as_str(), "abc");
+    }
+
     #[test]
     fn should_read_chunked_body() {
         let data = "7\r\nChunk 1\r\n7\r\nChunk 2\r\n7\r\nChunk 3\r\n0\r\n\r\n";
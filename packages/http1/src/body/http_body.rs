@@ -1,10 +1,15 @@
 use std::{
     convert::Infallible,
+    fmt::Display,
     fs::File,
     io::{BufReader, Chain, Cursor, Empty, Read, Take},
     sync::Arc,
 };
 
+use crate::error::BoxError;
+
+use super::body_reader::BodyLimitExceeded;
+
 /// Represents an object that can be used to read the request or response body data.
 pub trait HttpBody {
     type Err;
@@ -30,8 +35,76 @@ pub trait HttpBody {
 
         Ok(bytes)
     }
+
+    /// Read all the chunks and returns a `Vec` containing all the bytes,
+    /// fails with `CollectError::LimitExceeded` if the body is larger than `max` bytes.
+    fn collect(&mut self, max: usize) -> Result<Vec<u8>, CollectError>
+    where
+        Self::Err: Into<BoxError>,
+    {
+        let size_hint = self.size_hint();
+
+        if size_hint.is_some_and(|size| size > max) {
+            return Err(CollectError::LimitExceeded);
+        }
+
+        let capacity = size_hint.unwrap_or(4).min(max);
+        let mut bytes = Vec::with_capacity(capacity);
+
+        while let Some(chunk) = self
+            .read_next()
+            .map_err(|err| CollectError::from_read_error(err.into()))?
+        {
+            let chunk = chunk.into();
+
+            if bytes.len() + chunk.len() > max {
+                return Err(CollectError::LimitExceeded);
+            }
+
+            bytes.extend(chunk);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// An error returned by `HttpBody::collect`.
+#[derive(Debug)]
+pub enum CollectError {
+    /// The body is larger than the max size.
+    LimitExceeded,
+
+    /// Failed to read the body.
+    Other(BoxError),
+}
+
+impl CollectError {
+    fn from_read_error(err: BoxError) -> Self {
+        // The body was rejected by the server while reading it
+        let is_limit_error = err.is::<BodyLimitExceeded>()
+            || err
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(BodyLimitExceeded::is_body_limit_error);
+
+        if is_limit_error {
+            CollectError::LimitExceeded
+        } else {
+            CollectError::Other(err)
+        }
+    }
+}
+
+impl Display for CollectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectError::LimitExceeded => write!(f, "body size limit exceeded"),
+            CollectError::Other(err) => write!(f, "failed to read body: {err}"),
+        }
+    }
 }
 
+impl std::error::Error for CollectError {}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub(crate) struct BytesBuf<T>(Option<T>);
 
@@ -237,7 +310,7 @@ mod tests {
     use std::time::UNIX_EPOCH;
 
     use crate::body::chunked_body::ChunkedBody;
-    use crate::body::http_body::{BytesBuf, HttpBody};
+    use crate::body::http_body::{BytesBuf, CollectError, HttpBody};
     use crate::body::Body;
 
     fn read_all_body_data(body: &mut Body) -> Vec<u8> {
@@ -395,4 +468,27 @@ mod tests {
         let result = read_all_body_data(&mut body);
         assert_eq!(result, b"Hello, world!");
     }
+
+    #[test]
+    fn should_collect_body_within_limit() {
+        let mut body = Body::from("Hello World!");
+        assert_eq!(body.collect(12).unwrap(), b"Hello World!");
+
+        let mut body = Body::new(Cursor::new(vec![1, 2, 3, 4, 5]));
+        assert_eq!(body.collect(100).unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn should_fail_to_collect_body_over_limit() {
+        let mut body = Body::from("Hello World!");
+        assert!(matches!(body.collect(11), Err(CollectError::LimitExceeded)));
+
+        // Without size hint
+        let mut body = Body::new(Cursor::new(vec![0; 10_000]));
+        assert_eq!(body.size_hint(), None);
+        assert!(matches!(
+            body.collect(5_000),
+            Err(CollectError::LimitExceeded)
+        ));
+    }
 }
@@ -0,0 +1,1119 @@
+use std::{
+    borrow::Cow, collections::HashSet, fmt::Display, net::TcpStream, str::FromStr, sync::Arc,
+    time::Duration,
+};
+
+mod multipart;
+mod pool;
+mod sse;
+pub use multipart::MultipartBuilder;
+use pool::{ConnectionPool, PooledBody};
+pub use sse::{SseClient, SseEvent};
+
+use serde::ser::Serialize;
+
+use crate::{
+    body::{http_body::HttpBody, Body},
+    common::gzip,
+    error::BoxError,
+    headers::{self, HeaderName, HeaderValue, Headers, InvalidHeaderName, InvalidHeaderValue},
+    method::Method,
+    request::{InvalidRequest, Parts, Request},
+    response::Response,
+    status::StatusCode,
+    uri::{
+        scheme::Scheme,
+        uri::{InvalidUri, Uri},
+    },
+    version::Version,
+};
+
+/// A request error.
+const DEFAULT_USER_AGENT: &str = "rust";
+
+/// Default max number of redirects to follow.
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const DEFAULT_MAX_DECODED_BODY_SIZE: usize = 64 * 1024 * 1024; // 64mb
+
+#[derive(Debug)]
+pub enum RequestError {
+    InvalidRequest(InvalidRequest),
+    IO(std::io::Error),
+    FailedToConnect { addr: String, err: std::io::Error },
+    Other(BoxError),
+}
+
+impl std::error::Error for RequestError {}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::InvalidRequest(error) => write!(f, "{error}"),
+            RequestError::IO(error) => write!(f, "{error}"),
+            RequestError::FailedToConnect { addr, err } => {
+                write!(f, "failed to connect to `{addr}`: {err}")
+            }
+            RequestError::Other(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<InvalidRequest> for RequestError {
+    fn from(value: InvalidRequest) -> Self {
+        RequestError::InvalidRequest(value)
+    }
+}
+
+impl From<std::io::Error> for RequestError {
+    fn from(value: std::io::Error) -> Self {
+        RequestError::IO(value)
+    }
+}
+
+/// A http client.
+///
+/// Keep-alive connections are returned to a pool after the response body is read completely
+/// and reused for the next requests to the same host, a connection is never reused after a `Connection: close` response.
+pub struct Client {
+    user_agent: Option<String>,
+    default_headers: Headers,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    follow_redirects: bool,
+    max_redirects: usize,
+    max_decoded_body_size: Option<usize>,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Option<Duration>,
+    pool: Arc<ConnectionPool>,
+}
+
+impl Client {
+    /// Constructs a [`Client`].
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Returns a [`Client`] builder.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Returns the `User-Agent` for this client.
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Returns the default headers for this client.
+    pub fn default_headers(&self) -> &Headers {
+        &self.default_headers
+    }
+
+    /// Returns a request builder.
+    pub fn request<T>(&self, method: Method, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        RequestBuilder::new(self, method, url)
+    }
+
+    /// Returns a `GET` request builder.
+    pub fn get<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::GET, url)
+    }
+
+    /// Returns a `POST` request builder.
+    pub fn post<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::POST, url)
+    }
+
+    /// Returns a `PUT` request builder.
+    pub fn put<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::PUT, url)
+    }
+
+    /// Returns a `PATCH` request builder.
+    pub fn patch<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Returns a `DELETE` request builder.
+    pub fn delete<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Returns a `OPTION` request builder.
+    pub fn option<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::OPTIONS, url)
+    }
+
+    /// Returns a `HEAD` request builder.
+    pub fn head<T>(&self, url: T) -> RequestBuilder
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        self.request(Method::HEAD, url)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct ClientBuilder(Client);
+
+impl ClientBuilder {
+    /// Constructs a new [`ClientBuilder`].
+    pub fn new() -> Self {
+        ClientBuilder(Client {
+            user_agent: None,
+            default_headers: Headers::new(),
+            read_timeout: None,
+            write_timeout: None,
+            follow_redirects: false,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            max_decoded_body_size: Some(DEFAULT_MAX_DECODED_BODY_SIZE),
+            pool_max_idle_per_host: pool::DEFAULT_MAX_IDLE_PER_HOST,
+            pool_idle_timeout: Some(pool::DEFAULT_IDLE_TIMEOUT),
+            pool: Arc::new(ConnectionPool::new(0, None)),
+        })
+    }
+
+    /// Sets the user agent.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.0.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Append a default header.
+    pub fn append_default_header(
+        mut self,
+        name: HeaderName,
+        value: impl Into<HeaderValue>,
+    ) -> Self {
+        self.0.default_headers.append(name, value.into());
+        self
+    }
+
+    /// Insert a default header.
+    pub fn insert_default_header(
+        mut self,
+        name: HeaderName,
+        value: impl Into<HeaderValue>,
+    ) -> Self {
+        self.0.default_headers.insert(name, value.into());
+        self
+    }
+
+    /// Add a list of headers.
+    pub fn default_headers(mut self, headers: Headers) -> Self {
+        self.0.default_headers.extend(headers);
+        self
+    }
+
+    /// Sets the read timeout.
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.0.read_timeout = timeout;
+        self
+    }
+
+    /// Sets the write timeout.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.0.write_timeout = timeout;
+        self
+    }
+
+    /// Whether if follow the `301`, `302`, `303`, `307` and `308` redirects, this defaults to `false`.
+    ///
+    /// `303` redirects and `301`/`302` redirects of `POST` requests are followed with a `GET` request without body,
+    /// other redirects preserve the method and body.
+    pub fn follow_redirects(mut self, follow_redirects: bool) -> Self {
+        self.0.follow_redirects = follow_redirects;
+        self
+    }
+
+    /// Sets the max number of redirects to follow before failing, this defaults to `10`.
+    pub fn max_redirects(mut self, max_redirects: usize) -> Self {
+        self.0.max_redirects = max_redirects;
+        self
+    }
+
+    /// Sets the max size of a gzip response body after decoding it, this defaults to `64mb`.
+    ///
+    /// Responses that exceed the size fail to decode, this prevents a small compressed response from exhausting the memory.
+    pub fn max_decoded_body_size(mut self, max_size: Option<usize>) -> Self {
+        self.0.max_decoded_body_size = max_size;
+        self
+    }
+
+    /// Sets the max number of idle connections kept per host, this defaults to `8`.
+    ///
+    /// Setting it to `0` disables the connection pooling.
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.0.pool_max_idle_per_host = max_idle;
+        self
+    }
+
+    /// Sets how long an idle connection is kept in the pool, this defaults to `90` seconds.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.0.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Builds the [`Client`].
+    pub fn build(mut self) -> Client {
+        self.0.pool = Arc::new(ConnectionPool::new(
+            self.0.pool_max_idle_per_host,
+            self.0.pool_idle_timeout,
+        ));
+
+        self.0
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct RequestBuilder<'a> {
+    request: crate::request::Builder,
+    client: &'a Client,
+}
+
+impl<'a> RequestBuilder<'a> {
+    /// Constructs a new [`RequestBuilder`].
+    pub fn new<T>(client: &'a Client, method: Method, url: T) -> Self
+    where
+        T: TryInto<Uri>,
+        T::Error: Into<InvalidUri>,
+    {
+        let request = Request::builder().method(method).uri(url);
+        RequestBuilder { request, client }
+    }
+
+    fn tap<F>(self, f: F) -> Self
+    where
+        F: FnOnce(crate::request::Builder) -> crate::request::Builder,
+    {
+        RequestBuilder {
+            request: f(self.request),
+            client: self.client,
+        }
+    }
+
+    /// Sets the request headers.
+    pub fn headers(self, headers: Headers) -> Self {
+        self.tap(|mut request| {
+            if let Some(h) = request.headers_mut() {
+                h.extend(headers);
+            }
+
+            request
+        })
+    }
+
+    /// Append a header.
+    pub fn append_header<K, V>(self, name: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        K::Error: Into<InvalidHeaderName>,
+        V: TryInto<HeaderValue>,
+        V::Error: Into<InvalidHeaderValue>,
+    {
+        self.tap(|request| request.append_header(name, value))
+    }
+
+    /// Insert a header.
+    pub fn insert_header<K, V>(self, name: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        K::Error: Into<InvalidHeaderName>,
+        V: TryInto<HeaderValue>,
+        V::Error: Into<InvalidHeaderValue>,
+    {
+        self.tap(|request| request.insert_header(name, value))
+    }
+
+    /// Sends the request using the given `JSON` as body.
+    pub fn json<T: Serialize>(self, json: &T) -> Result<Response<Body>, RequestError> {
+        let json_bytes =
+            serde::json::to_bytes(json).map_err(|err| RequestError::Other(err.into()))?;
+
+        self.append_header(
+            headers::CONTENT_TYPE,
+            HeaderValue::from_static("application/json;charset-utf8"),
+        )
+        .send(json_bytes)
+    }
+
+    /// Sends the request using the given `multipart/form-data` form as body.
+    pub fn multipart(self, form: MultipartBuilder) -> Result<Response<Body>, RequestError> {
+        let (content_type, body) = form.build();
+        self.insert_header(headers::CONTENT_TYPE, content_type)
+            .send(body)
+    }
+
+    /// Sends a request with the given body.
+    pub fn send(self, body: impl Into<Body>) -> Result<Response<Body>, RequestError> {
+        let Self { request, client } = self;
+
+        let user_agent = client
+            .user_agent
+            .as_deref()
+            .map(|s| Cow::Owned(s.to_owned()))
+            .unwrap_or_else(|| Cow::Borrowed(DEFAULT_USER_AGENT));
+
+        let mut request = request
+            .insert_header(headers::USER_AGENT, user_agent)
+            .body(body.into())?;
+
+        if !request.headers().contains_key(headers::ACCEPT) {
+            request
+                .headers_mut()
+                .insert(headers::ACCEPT, HeaderValue::from_static("*/*"));
+        }
+
+        // We only decode the response if the encoding was not requested by the user
+        let decode_gzip = !request.headers().contains_key(headers::ACCEPT_ENCODING);
+        if decode_gzip {
+            request
+                .headers_mut()
+                .insert(headers::ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        }
+
+        let is_head = request.method() == Method::HEAD;
+        let response = if client.follow_redirects {
+            send_following_redirects(client, request)?
+        } else {
+            send_request(client, request)?
+        };
+
+        if decode_gzip {
+            return decode_gzip_response(response, is_head, client.max_decoded_body_size);
+        }
+
+        Ok(response)
+    }
+}
+
+fn send_request(
+    client: &Client,
+    mut request: Request<Body>,
+) -> Result<Response<Body>, RequestError> {
+    let (host, port) = get_addr(&request)?;
+    let addr = format!("{host}:{port}");
+
+    request
+        .headers_mut()
+        .insert(headers::HOST, HeaderValue::from_string(host.clone()));
+    request.headers_mut().extend(client.default_headers.clone());
+
+    let mut stream = match client.pool.take(&addr) {
+        Some(stream) => stream,
+        None => TcpStream::connect(&addr).map_err(|err| RequestError::FailedToConnect {
+            addr: addr.clone(),
+            err,
+        })?,
+    };
+
+    stream.set_write_timeout(client.write_timeout)?;
+    stream.set_read_timeout(client.read_timeout)?;
+
+    let is_head = request.method() == Method::HEAD;
+    crate::protocol::h1::request::write_request(&mut stream, request)?;
+
+    let mut response = crate::protocol::h1::response::read_response(stream.try_clone()?)?;
+
+    // The `Content-Length` of these responses is not followed by a body
+    let status = response.status();
+    if is_head || status == StatusCode::NO_CONTENT || status == StatusCode::NOT_MODIFIED {
+        *response.body_mut() = Body::empty();
+    }
+
+    if can_reuse_connection(&response) {
+        if is_empty_response(&response) {
+            client.pool.put(addr, stream);
+        } else {
+            let pool = client.pool.clone();
+            response =
+                response.map_body(|body| Body::new(PooledBody::new(body, pool, addr, stream)));
+        }
+    }
+
+    Ok(response)
+}
+
+/// Whether if the server keeps the connection open and we know where the response ends.
+fn can_reuse_connection(response: &Response<Body>) -> bool {
+    let headers = response.headers();
+
+    response.version() == Version::Http1_1
+        && !headers
+            .get_all(headers::CONNECTION)
+            .any(|x| x.as_str().eq_ignore_ascii_case("close"))
+        && (headers.contains_key(headers::CONTENT_LENGTH)
+            || headers.contains_key(headers::TRANSFER_ENCODING)
+            || is_empty_response(response))
+}
+
+fn is_empty_response(response: &Response<Body>) -> bool {
+    let headers = response.headers();
+
+    response.body().size_hint() == Some(0)
+        || (!headers.contains_key(headers::TRANSFER_ENCODING)
+            && headers
+                .get(headers::CONTENT_LENGTH)
+                .is_some_and(|x| x.as_str().trim() == "0"))
+}
+
+fn send_following_redirects(
+    client: &Client,
+    request: Request<Body>,
+) -> Result<Response<Body>, RequestError> {
+    // The body is buffered so it can be sent again on redirects that preserve it
+    let (mut body, mut parts) = request.into_parts();
+    let mut body = body.read_all_bytes().map_err(RequestError::Other)?;
+    let mut visited = HashSet::from([parts.uri.clone()]);
+    let mut redirects = 0;
+
+    loop {
+        let request = Request::from_parts(parts.clone(), Body::from(body.clone()));
+        let response = send_request(client, request)?;
+        let status = response.status();
+
+        let Some(location) = redirect_location(&response) else {
+            return Ok(response);
+        };
+
+        if redirects >= client.max_redirects {
+            return Err(RequestError::Other(
+                format!("too many redirects, max is {}", client.max_redirects).into(),
+            ));
+        }
+
+        redirects += 1;
+
+        let uri = resolve_location(&parts.uri, location)?;
+        if !visited.insert(uri.clone()) {
+            return Err(RequestError::Other(
+                format!("redirect loop detected at `{location}`").into(),
+            ));
+        }
+
+        let is_see_other = status == StatusCode::SEE_OTHER
+            || (parts.method == Method::POST
+                && (status == StatusCode::MOVED_PERMANENTLY || status == StatusCode::FOUND));
+
+        if is_see_other {
+            change_to_get(&mut parts);
+            body.clear();
+        }
+
+        // Do not leak the credentials to other hosts
+        if uri.authority() != parts.uri.authority() {
+            parts.headers.remove(headers::AUTHORIZATION);
+            parts.headers.remove(headers::COOKIE);
+        }
+
+        parts.uri = uri;
+    }
+}
+
+fn redirect_location(response: &Response<Body>) -> Option<&str> {
+    let status = response.status();
+    let is_redirect = status == StatusCode::MOVED_PERMANENTLY
+        || status == StatusCode::FOUND
+        || status == StatusCode::SEE_OTHER
+        || status == StatusCode::TEMPORARY_REDIRECT
+        || status == StatusCode::PERMANENT_REDIRECT;
+
+    if !is_redirect {
+        return None;
+    }
+
+    response
+        .headers()
+        .get(headers::LOCATION)
+        .map(|x| x.as_str().trim())
+}
+
+fn change_to_get(parts: &mut Parts) {
+    if parts.method != Method::HEAD {
+        parts.method = Method::GET;
+    }
+
+    parts.headers.remove(headers::CONTENT_LENGTH);
+    parts.headers.remove(headers::CONTENT_TYPE);
+    parts.headers.remove(headers::TRANSFER_ENCODING);
+}
+
+/// Resolves the `Location` of a redirect relative to the uri of the request.
+fn resolve_location(base: &Uri, location: &str) -> Result<Uri, RequestError> {
+    let invalid_location = |err: InvalidUri| {
+        RequestError::Other(format!("invalid redirect location `{location}`: {err}").into())
+    };
+
+    if location.contains("://") {
+        return Uri::from_str(location).map_err(invalid_location);
+    }
+
+    let scheme = base.scheme().cloned().unwrap_or(Scheme::Http);
+
+    if let Some(rest) = location.strip_prefix("//") {
+        return Uri::from_str(&format!("{scheme}://{rest}")).map_err(invalid_location);
+    }
+
+    let authority = base
+        .authority()
+        .ok_or_else(|| RequestError::Other(String::from("missing authority").into()))?;
+
+    let path = if location.starts_with('/') {
+        location.to_owned()
+    } else {
+        // Relative to the directory of the current path
+        let base_path = base.path_and_query().path();
+        let dir = &base_path[..base_path.rfind('/').map(|i| i + 1).unwrap_or(0)];
+        format!("{dir}{location}")
+    };
+
+    Uri::from_str(&format!("{scheme}://{authority}{path}")).map_err(invalid_location)
+}
+
+fn decode_gzip_response(
+    mut response: Response<Body>,
+    is_head: bool,
+    max_decoded_body_size: Option<usize>,
+) -> Result<Response<Body>, RequestError> {
+    let is_gzip = response
+        .headers()
+        .get(headers::CONTENT_ENCODING)
+        .is_some_and(|x| x.as_str().trim().eq_ignore_ascii_case("gzip"));
+
+    // These responses don't have a body to decode
+    let status = response.status();
+    if !is_gzip || is_head || status == StatusCode::NO_CONTENT || status == StatusCode::NOT_MODIFIED
+    {
+        return Ok(response);
+    }
+
+    let bytes = response
+        .body_mut()
+        .read_all_bytes()
+        .map_err(RequestError::Other)?;
+
+    if bytes.is_empty() {
+        *response.body_mut() = Body::empty();
+        return Ok(response);
+    }
+
+    let decoded = match max_decoded_body_size {
+        Some(max_size) => gzip::decompress_with_limit(&bytes, max_size),
+        None => gzip::decompress(&bytes),
+    }
+    .map_err(|err| RequestError::Other(err.into()))?;
+
+    let headers = response.headers_mut();
+    headers.remove(headers::CONTENT_ENCODING);
+    headers.insert(headers::CONTENT_LENGTH, HeaderValue::from(decoded.len()));
+
+    *response.body_mut() = Body::from(decoded);
+    Ok(response)
+}
+
+fn get_addr(request: &Request<Body>) -> Result<(String, u16), RequestError> {
+    let authority = request
+        .uri()
+        .authority()
+        .ok_or_else(|| RequestError::Other(String::from("missing authority").into()))?;
+
+    let is_https = request.uri().scheme().is_some_and(|s| *s == Scheme::Https);
+    let host = authority.host().to_owned();
+    let port: u16 = authority.port().unwrap_or(if is_https { 443 } else { 80 });
+
+    Ok((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc::channel, time::Duration};
+
+    use crate::{
+        body::{
+            chunked_body::{ChunkedBody, Trailers},
+            http_body::HttpBody,
+            Body,
+        },
+        common::gzip,
+        headers::{self, HeaderValue},
+        method::Method,
+        protocol::connection::Connected,
+        response::Response,
+        server::Server,
+        status::StatusCode,
+    };
+
+    use super::Client;
+
+    #[test]
+    fn should_send_request_to_server_and_get_response() {
+        let port = crate::common::find_open_port::find_open_port().unwrap();
+        let addr = format!("0.0.0.0:{port}");
+
+        let server = Server::new();
+        let handle = server.handle();
+
+        let (tx, rx) = channel();
+        let (ready_tx, ready_rx) = channel();
+
+        {
+            std::thread::spawn(move || {
+                server
+                    .on_ready(move |addr| {
+                        println!("server running on: {addr}");
+                        ready_tx.send(()).unwrap();
+                    })
+                    .listen(addr.clone(), move |request| {
+                        tx.send(request).unwrap();
+                        Response::new(StatusCode::OK, "Bloom Into You".into())
+                    })
+                    .unwrap();
+            });
+        }
+
+        // Wait for server to be ready
+        ready_rx
+            .recv()
+            .unwrap_or_else(|_| panic!("Server failed to start"));
+
+        let client = Client::new();
+        let res = client
+            .request(Method::POST, format!("http://127.0.0.1:{port}"))
+            .send(Body::from("Yagate Kimi ni Naru"))
+            .unwrap();
+
+        // Assert server
+        let mut req = rx.recv().unwrap();
+
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(
+            req.body_mut().read_all_bytes().unwrap(),
+            b"Yagate Kimi ni Naru"
+        );
+
+        // Assert client
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Bloom Into You");
+
+        // Shutdown server
+        handle.shutdown();
+    }
+
+    #[test]
+    fn should_send_request_to_server_and_get_chunked_encoded_response() {
+        let port = crate::common::find_open_port::find_open_port_in_range(3001..).unwrap();
+        let addr = format!("0.0.0.0:{port}");
+
+        let server = Server::new();
+        let handle = server.handle();
+        let (tx, rx) = channel();
+        let (ready_tx, ready_rx) = channel();
+
+        {
+            std::thread::spawn(move || {
+                server
+                    .on_ready(move |addr| {
+                        println!("server running on: {addr}");
+                        ready_tx.send(()).unwrap();
+                    })
+                    .listen(addr.clone(), move |request| {
+                        tx.send(request).unwrap();
+                        let (body, sender) = ChunkedBody::new();
+
+                        std::thread::spawn(move || {
+                            for i in 1..=3 {
+                                std::thread::sleep(Duration::from_millis(10));
+                                sender.send(format!("Chunk {i}\n")).unwrap();
+                            }
+                        });
+
+                        Response::builder()
+                            .status(StatusCode::CREATED)
+                            .append_header(
+                                headers::TRANSFER_ENCODING,
+                                HeaderValue::from_static("chunked"),
+                            )
+                            .body(body.into())
+                    })
+                    .unwrap();
+            });
+        }
+
+        // Wait for server to be ready
+        ready_rx
+            .recv()
+            .unwrap_or_else(|_| panic!("Server failed to start"));
+
+        let client = Client::builder()
+            .read_timeout(Some(Duration::from_millis(5_000)))
+            .build();
+        let res = client
+            .request(
+                Method::GET,
+                format!("http://127.0.0.1:{port}/message?text=hello"),
+            )
+            .send(())
+            .unwrap();
+
+        // Assert server
+        let mut req = rx.recv().unwrap();
+
+        assert_eq!(req.method(), Method::GET);
+        assert_eq!(req.body_mut().read_all_bytes().unwrap(), b"");
+
+        // Assert client
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(
+            res.into_body().read_all_bytes().unwrap(),
+            b"Chunk 1\nChunk 2\nChunk 3\n"
+        );
+
+        // Shutdown server
+        handle.shutdown();
+    }
+
+    #[test]
+    fn should_decode_gzip_response() {
+        let port = crate::common::find_open_port::find_open_port_in_range(3002..).unwrap();
+        let addr = format!("0.0.0.0:{port}");
+
+        let server = Server::new();
+        let handle = server.handle();
+        let (tx, rx) = channel();
+        let (ready_tx, ready_rx) = channel();
+
+        {
+            std::thread::spawn(move || {
+                server
+                    .on_ready(move |_| {
+                        ready_tx.send(()).unwrap();
+                    })
+                    .listen(addr.clone(), move |request| {
+                        tx.send(request).unwrap();
+                        let compressed = gzip::compress("Citrus ".repeat(100).as_bytes());
+
+                        Response::builder()
+                            .append_header(
+                                headers::CONTENT_ENCODING,
+                                HeaderValue::from_static("gzip"),
+                            )
+                            .body(compressed.into())
+                    })
+                    .unwrap();
+            });
+        }
+
+        // Wait for server to be ready
+        ready_rx
+            .recv()
+            .unwrap_or_else(|_| panic!("Server failed to start"));
+
+        let client = Client::new();
+        let res = client
+            .get(format!("http://127.0.0.1:{port}"))
+            .send(())
+            .unwrap();
+
+        // Assert server
+        let req = rx.recv().unwrap();
+        assert_eq!(
+            req.headers()
+                .get(headers::ACCEPT_ENCODING)
+                .unwrap()
+                .as_str(),
+            "gzip"
+        );
+
+        // Assert client
+        assert!(res.headers().get(headers::CONTENT_ENCODING).is_none());
+        assert_eq!(
+            res.into_body().read_all_bytes().unwrap(),
+            "Citrus ".repeat(100).as_bytes()
+        );
+
+        // Shutdown server
+        handle.shutdown();
+    }
+
+    #[test]
+    fn should_not_decode_gzip_responses_without_body() {
+        Server::new()
+            .run_scoped(
+                |req: crate::request::Request<Body>| {
+                    let status = match req.uri().path_and_query().path() {
+                        "/not-modified" => StatusCode::NOT_MODIFIED,
+                        _ => StatusCode::OK,
+                    };
+
+                    let compressed = gzip::compress("Citrus ".repeat(100).as_bytes());
+                    Response::builder()
+                        .status(status)
+                        .append_header(headers::CONTENT_ENCODING, HeaderValue::from_static("gzip"))
+                        .body(compressed.into())
+                },
+                |addr| {
+                    let client = Client::new();
+
+                    let mut res = client.head(format!("http://{addr}")).send(()).unwrap();
+                    assert_eq!(res.status(), StatusCode::OK);
+                    assert!(res.body_mut().read_all_bytes().unwrap().is_empty());
+
+                    let mut res = client
+                        .get(format!("http://{addr}/not-modified"))
+                        .send(())
+                        .unwrap();
+                    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+                    assert!(res.body_mut().read_all_bytes().unwrap().is_empty());
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn should_fail_to_decode_gzip_response_larger_than_max_size() {
+        Server::new()
+            .run_scoped(
+                |_| {
+                    let compressed = gzip::compress(&vec![0; 1024 * 1024]);
+                    Response::builder()
+                        .append_header(headers::CONTENT_ENCODING, HeaderValue::from_static("gzip"))
+                        .body(compressed.into())
+                },
+                |addr| {
+                    let client = Client::builder().max_decoded_body_size(Some(1024)).build();
+
+                    assert!(client.get(format!("http://{addr}")).send(()).is_err());
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn should_expose_chunked_response_trailers() {
+        let res = Server::new()
+            .run_scoped(
+                |_| {
+                    // The body is already chunk encoded, followed by the trailers
+                    Response::builder()
+                        .insert_header(
+                            headers::TRANSFER_ENCODING,
+                            HeaderValue::from_static("chunked"),
+                        )
+                        .insert_header(headers::TRAILER, HeaderValue::from_static("X-Checksum"))
+                        .body("5\r\nHello\r\n6\r\n World\r\n0\r\nX-Checksum: 1234\r\n\r\n".into())
+                },
+                |addr| {
+                    let mut res = Client::new()
+                        .get(format!("http://{addr}"))
+                        .send(())
+                        .unwrap();
+                    let trailers = res.extensions().get::<Trailers>().cloned().unwrap();
+                    assert!(trailers.get().is_none());
+
+                    let body = res.body_mut().read_all_bytes().unwrap();
+                    (body, trailers)
+                },
+            )
+            .unwrap();
+
+        let (body, trailers) = res;
+        assert_eq!(body, b"Hello World");
+        assert_eq!(
+            trailers.get().unwrap().get("X-Checksum").unwrap().as_str(),
+            "1234"
+        );
+    }
+
+    #[test]
+    fn should_open_new_connection_after_connection_close() {
+        let peers = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        Server::new()
+            .include_conn_info(true)
+            .run_scoped(
+                {
+                    let peers = peers.clone();
+                    move |req: crate::request::Request<Body>| {
+                        let peer_addr = req
+                            .extensions()
+                            .get::<Connected>()
+                            .and_then(|x| x.peer_addr());
+
+                        peers.lock().unwrap().push(peer_addr.unwrap());
+                        Response::builder()
+                            .insert_header(headers::CONNECTION, HeaderValue::from_static("close"))
+                            .body(Body::from("Hello"))
+                    }
+                },
+                |addr| {
+                    let client = Client::new();
+
+                    for _ in 0..2 {
+                        let res = client.get(format!("http://{addr}")).send(()).unwrap();
+                        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello");
+                    }
+                },
+            )
+            .unwrap();
+
+        let peers = peers.lock().unwrap();
+        assert_eq!(peers.len(), 2);
+        assert_ne!(peers[0], peers[1]);
+    }
+
+    #[test]
+    fn should_reuse_pooled_connection_for_same_host() {
+        let peers = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        Server::new()
+            .include_conn_info(true)
+            .run_scoped(
+                {
+                    let peers = peers.clone();
+                    move |req: crate::request::Request<Body>| {
+                        let peer_addr = req
+                            .extensions()
+                            .get::<Connected>()
+                            .and_then(|x| x.peer_addr());
+
+                        peers.lock().unwrap().push(peer_addr.unwrap());
+                        Response::new(StatusCode::OK, Body::from("Hello"))
+                    }
+                },
+                |addr| {
+                    // The pooled connections are closed when the client is dropped
+                    for client in [
+                        Client::new(),
+                        Client::builder().pool_max_idle_per_host(0).build(),
+                    ] {
+                        for _ in 0..2 {
+                            let res = client.get(format!("http://{addr}")).send(()).unwrap();
+                            assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello");
+                        }
+                    }
+                },
+            )
+            .unwrap();
+
+        let peers = peers.lock().unwrap();
+        assert_eq!(peers.len(), 4);
+        assert_eq!(peers[0], peers[1]);
+        assert_ne!(peers[2], peers[3]);
+    }
+
+    #[test]
+    fn should_follow_redirects() {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        Server::new()
+            .run_scoped(
+                {
+                    let seen = seen.clone();
+                    move |mut req: crate::request::Request<Body>| {
+                        let path = req.uri().path_and_query().path().to_owned();
+                        let body = req.body_mut().read_all_bytes().unwrap();
+                        let body = String::from_utf8(body).unwrap();
+                        seen.lock()
+                            .unwrap()
+                            .push(format!("{} {path} {body}", req.method().as_str()));
+
+                        let redirect = |status, location| {
+                            Response::builder()
+                                .status(status)
+                                .insert_header(
+                                    headers::LOCATION,
+                                    HeaderValue::from_static(location),
+                                )
+                                .body(Body::empty())
+                        };
+
+                        match path.as_str() {
+                            "/a" => redirect(StatusCode::TEMPORARY_REDIRECT, "/b"),
+                            "/b" => redirect(StatusCode::SEE_OTHER, "c"),
+                            "/loop" => redirect(StatusCode::FOUND, "/loop"),
+                            _ => Response::new(StatusCode::OK, Body::from("done")),
+                        }
+                    }
+                },
+                |addr| {
+                    let client = Client::builder().follow_redirects(true).build();
+                    let res = client
+                        .post(format!("http://{addr}/a"))
+                        .send("Yuri")
+                        .unwrap();
+
+                    assert_eq!(res.status(), StatusCode::OK);
+                    assert_eq!(res.into_body().read_all_bytes().unwrap(), b"done");
+
+                    // Redirect loop
+                    let result = client.get(format!("http://{addr}/loop")).send(());
+                    assert!(result.is_err());
+
+                    // Close the pooled connection, a server with a single worker only handles one connection at a time
+                    drop(client);
+
+                    // Not following
+                    let res = Client::new()
+                        .post(format!("http://{addr}/a"))
+                        .send("Yuri")
+                        .unwrap();
+                    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+                },
+            )
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            seen[..3],
+            ["POST /a Yuri", "POST /b Yuri", "GET /c "].map(String::from)
+        );
+    }
+
+    #[test]
+    fn should_get_example_com() {
+        let client = Client::new();
+        let result = client.get("http://example.com").send(()).unwrap();
+        let bytes = result.into_body().read_all_bytes().unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        assert!(text.contains("<h1>Example Domain</h1>"))
+    }
+}
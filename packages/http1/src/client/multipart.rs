@@ -0,0 +1,171 @@
+use crate::{body::Body, headers::HeaderValue};
+
+enum Part {
+    Text {
+        name: String,
+        value: String,
+    },
+    File {
+        name: String,
+        filename: String,
+        content_type: String,
+        bytes: Vec<u8>,
+    },
+}
+
+/// A builder for `multipart/form-data` request bodies.
+pub struct MultipartBuilder {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+impl MultipartBuilder {
+    /// Constructs a new `MultipartBuilder` with a random boundary.
+    pub fn new() -> Self {
+        let boundary = rng::sequence::<rng::Alphanumeric>()
+            .take(30)
+            .collect::<String>();
+
+        Self::with_boundary(format!("----http1-{boundary}"))
+    }
+
+    /// Constructs a new `MultipartBuilder` with the given boundary.
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        MultipartBuilder {
+            boundary: boundary.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Returns the boundary that separates the parts.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Adds a text field.
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(Part::Text {
+            name: name.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Adds a file field.
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.parts.push(Part::File {
+            name: name.into(),
+            filename: filename.into(),
+            content_type: content_type.into(),
+            bytes: bytes.into(),
+        });
+        self
+    }
+
+    /// Returns the `Content-Type` header value of the form.
+    pub fn content_type(&self) -> HeaderValue {
+        HeaderValue::from_string(format!("multipart/form-data; boundary={}", self.boundary))
+    }
+
+    /// Returns the form bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let boundary = &self.boundary;
+        let mut buf = Vec::new();
+
+        for part in &self.parts {
+            buf.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+
+            match part {
+                Part::Text { name, value } => {
+                    let name = escape_quoted(name);
+                    buf.extend_from_slice(
+                        format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n")
+                            .as_bytes(),
+                    );
+                    buf.extend_from_slice(value.as_bytes());
+                }
+                Part::File {
+                    name,
+                    filename,
+                    content_type,
+                    bytes,
+                } => {
+                    let name = escape_quoted(name);
+                    let filename = escape_quoted(filename);
+                    buf.extend_from_slice(
+                        format!("Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\n")
+                            .as_bytes(),
+                    );
+                    buf.extend_from_slice(
+                        format!("Content-Type: {content_type}\r\n\r\n").as_bytes(),
+                    );
+                    buf.extend_from_slice(bytes);
+                }
+            }
+
+            buf.extend_from_slice(b"\r\n");
+        }
+
+        buf.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+        buf
+    }
+
+    /// Returns the `Content-Type` header value and the body of the form.
+    pub fn build(self) -> (HeaderValue, Body) {
+        (self.content_type(), Body::from(self.to_bytes()))
+    }
+}
+
+impl Default for MultipartBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escapes the characters that cannot be in a quoted `Content-Disposition` parameter.
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultipartBuilder;
+
+    #[test]
+    fn should_build_multipart_form() {
+        let form = MultipartBuilder::with_boundary("my-boundary")
+            .text("title", "Hello")
+            .file("file", "hello \"world\".txt", "text/plain", "Hello World!");
+
+        assert_eq!(
+            form.content_type().as_str(),
+            "multipart/form-data; boundary=my-boundary"
+        );
+
+        assert_eq!(
+            String::from_utf8(form.to_bytes()).unwrap(),
+            "--my-boundary\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+            Hello\r\n\
+            --my-boundary\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"hello %22world%22.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            Hello World!\r\n\
+            --my-boundary--\r\n"
+        );
+
+        assert_ne!(
+            MultipartBuilder::new().boundary(),
+            MultipartBuilder::new().boundary()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    net::TcpStream,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    body::{http_body::HttpBody, Body},
+    error::BoxError,
+};
+
+/// Default max number of idle connections kept per host.
+pub(crate) const DEFAULT_MAX_IDLE_PER_HOST: usize = 8;
+
+/// Default time an idle connection is kept before being closed.
+pub(crate) const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+struct IdleConnection {
+    stream: TcpStream,
+    idle_since: Instant,
+}
+
+/// A pool of idle keep-alive connections keyed by `host:port`.
+pub(crate) struct ConnectionPool {
+    max_idle_per_host: usize,
+    idle_timeout: Option<Duration>,
+    idle: Mutex<HashMap<String, Vec<IdleConnection>>>,
+}
+
+impl ConnectionPool {
+    pub fn new(max_idle_per_host: usize, idle_timeout: Option<Duration>) -> Self {
+        ConnectionPool {
+            max_idle_per_host,
+            idle_timeout,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes an idle connection to the given address that is still usable.
+    pub fn take(&self, addr: &str) -> Option<TcpStream> {
+        let mut idle = self.idle.lock().expect("failed to lock connection pool");
+        let conns = idle.get_mut(addr)?;
+
+        // The most recently used connections are the most likely to still be open
+        while let Some(conn) = conns.pop() {
+            let expired = self
+                .idle_timeout
+                .is_some_and(|timeout| conn.idle_since.elapsed() >= timeout);
+
+            if !expired && is_usable(&conn.stream) {
+                return Some(conn.stream);
+            }
+        }
+
+        idle.remove(addr);
+        None
+    }
+
+    /// Returns a connection to the pool, the connection is closed if the pool for the address is full.
+    pub fn put(&self, addr: String, stream: TcpStream) {
+        if self.max_idle_per_host == 0 {
+            return;
+        }
+
+        let mut idle = self.idle.lock().expect("failed to lock connection pool");
+        let conns = idle.entry(addr).or_default();
+
+        if conns.len() >= self.max_idle_per_host {
+            conns.remove(0);
+        }
+
+        conns.push(IdleConnection {
+            stream,
+            idle_since: Instant::now(),
+        });
+    }
+}
+
+/// Whether if the server had not closed the connection and had not sent any unexpected data.
+fn is_usable(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return false;
+    }
+
+    let mut buf = [0; 1];
+    let usable = match stream.peek(&mut buf) {
+        Err(err) => err.kind() == ErrorKind::WouldBlock,
+        Ok(_) => false,
+    };
+
+    usable && stream.set_nonblocking(false).is_ok()
+}
+
+/// A response body that returns the connection to the pool after being read completely.
+///
+/// If the body is dropped before reaching the end the connection is closed.
+pub(crate) struct PooledBody {
+    body: Body,
+    release: Option<(Arc<ConnectionPool>, String, TcpStream)>,
+}
+
+impl PooledBody {
+    pub fn new(body: Body, pool: Arc<ConnectionPool>, addr: String, stream: TcpStream) -> Self {
+        PooledBody {
+            body,
+            release: Some((pool, addr, stream)),
+        }
+    }
+}
+
+impl HttpBody for PooledBody {
+    type Err = BoxError;
+    type Data = Vec<u8>;
+
+    fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+        let chunk = self.body.read_next()?;
+
+        if chunk.is_none() {
+            if let Some((pool, addr, stream)) = self.release.take() {
+                pool.put(addr, stream);
+            }
+        }
+
+        Ok(chunk)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.body.size_hint()
+    }
+}
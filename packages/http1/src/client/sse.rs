@@ -0,0 +1,425 @@
+use std::time::Duration;
+
+use serde::de::Deserialize;
+
+use crate::{
+    body::{http_body::HttpBody, Body},
+    headers::{self, HeaderValue},
+    status::StatusCode,
+};
+
+use super::{Client, RequestError};
+
+const DEFAULT_RETRY: Duration = Duration::from_millis(3000);
+
+/// An event received from a server-sent events stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    event: Option<String>,
+    data: String,
+    id: Option<String>,
+}
+
+impl SseEvent {
+    /// Returns the event type, if `None` the event should be treated as a `message`.
+    pub fn event(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+
+    /// Returns the data of this event.
+    pub fn data(&self) -> &str {
+        self.data.as_str()
+    }
+
+    /// Returns the last event id at the moment this event was received.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Deserialize the data of this event from `JSON`.
+    pub fn json<T: Deserialize>(&self) -> Result<T, serde::de::Error> {
+        serde::json::from_str(&self.data)
+    }
+}
+
+/// Parses the lines of an event stream into events.
+#[derive(Debug)]
+struct EventParser {
+    buf: Vec<u8>,
+    event: Option<String>,
+    data: String,
+    last_event_id: Option<String>,
+    retry: Duration,
+}
+
+impl EventParser {
+    fn new() -> Self {
+        EventParser {
+            buf: Vec::new(),
+            event: None,
+            data: String::new(),
+            last_event_id: None,
+            retry: DEFAULT_RETRY,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Discards any incomplete event.
+    fn reset(&mut self) {
+        self.buf.clear();
+        self.event = None;
+        self.data.clear();
+    }
+
+    fn next_event(&mut self) -> Option<SseEvent> {
+        while let Some(line) = self.next_line() {
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        let pos = self.buf.iter().position(|b| *b == b'\n' || *b == b'\r')?;
+        let mut end = pos + 1;
+
+        // A `\r` may be followed by a `\n` we had not received yet
+        if self.buf[pos] == b'\r' {
+            match self.buf.get(end) {
+                Some(b'\n') => end += 1,
+                Some(_) => {}
+                None => return None,
+            }
+        }
+
+        let line = String::from_utf8_lossy(&self.buf[..pos]).into_owned();
+        self.buf.drain(..end);
+        Some(line)
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        // Comment
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "event" => {
+                self.event = Some(value.to_owned());
+            }
+            "id" if !value.contains('\0') => {
+                self.last_event_id = Some(value.to_owned()).filter(|x| !x.is_empty());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse::<u64>() {
+                    self.retry = Duration::from_millis(ms);
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take().filter(|x| !x.is_empty());
+
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        if data.ends_with('\n') {
+            data.pop();
+        }
+
+        Some(SseEvent {
+            event,
+            data,
+            id: self.last_event_id.clone(),
+        })
+    }
+}
+
+/// A client for receiving server-sent events.
+///
+/// When the server closes the connection the client reconnects after the retry time,
+/// sending the `Last-Event-ID` header. The stream ends when the server responds with `204 No Content`.
+pub struct SseClient {
+    client: Client,
+    url: String,
+    body: Option<Body>,
+    parser: EventParser,
+    is_closed: bool,
+    is_connected: bool,
+}
+
+impl SseClient {
+    /// Constructs a new `SseClient` for the given url.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_client(Client::new(), url)
+    }
+
+    /// Constructs a new `SseClient` for the given url using the given client.
+    pub fn with_client(client: Client, url: impl Into<String>) -> Self {
+        SseClient {
+            client,
+            url: url.into(),
+            body: None,
+            parser: EventParser::new(),
+            is_closed: false,
+            is_connected: false,
+        }
+    }
+
+    /// Returns the id of the last event received.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.last_event_id.as_deref()
+    }
+
+    /// Returns the time to wait before reconnecting.
+    pub fn retry(&self) -> Duration {
+        self.parser.retry
+    }
+
+    fn connect(&mut self) -> Result<Option<Body>, RequestError> {
+        let mut request = self
+            .client
+            .get(self.url.as_str())
+            .insert_header(headers::ACCEPT, "text/event-stream")
+            .insert_header(headers::CACHE_CONTROL, "no-cache");
+
+        if let Some(id) = self.parser.last_event_id.clone() {
+            request = request.insert_header(headers::LAST_EVENT_ID, HeaderValue::from_string(id));
+        }
+
+        let response = request.send(())?;
+
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(RequestError::Other(
+                format!("unexpected event stream status: {}", response.status()).into(),
+            ));
+        }
+
+        if let Some(content_type) = response.headers().get(headers::CONTENT_TYPE) {
+            if !content_type.as_str().starts_with("text/event-stream") {
+                return Err(RequestError::Other(
+                    format!("unexpected event stream content type: {content_type}").into(),
+                ));
+            }
+        }
+
+        Ok(Some(response.into_body()))
+    }
+
+    /// Returns the next event, or `None` if the server closed the stream.
+    pub fn next_event(&mut self) -> Result<Option<SseEvent>, RequestError> {
+        loop {
+            if let Some(event) = self.parser.next_event() {
+                return Ok(Some(event));
+            }
+
+            if self.is_closed {
+                return Ok(None);
+            }
+
+            let body = match self.body.as_mut() {
+                Some(body) => body,
+                None => {
+                    if self.is_connected {
+                        std::thread::sleep(self.parser.retry);
+                    }
+
+                    match self.connect()? {
+                        Some(body) => {
+                            self.is_connected = true;
+                            self.body.insert(body)
+                        }
+                        None => {
+                            self.is_closed = true;
+                            return Ok(None);
+                        }
+                    }
+                }
+            };
+
+            match body.read_next() {
+                Ok(Some(chunk)) => self.parser.feed(&chunk),
+                Ok(None) => {
+                    self.body = None;
+                    self.parser.reset();
+                }
+                Err(err) => {
+                    self.body = None;
+                    self.parser.reset();
+                    return Err(RequestError::Other(err));
+                }
+            }
+        }
+    }
+
+    /// Returns the next event deserializing its data from `JSON`.
+    pub fn next_json<T: Deserialize>(&mut self) -> Result<Option<T>, RequestError> {
+        match self.next_event()? {
+            Some(event) => {
+                let value = event
+                    .json::<T>()
+                    .map_err(|err| RequestError::Other(err.into()))?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Iterator for SseClient {
+    type Item = Result<SseEvent, RequestError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc::channel, time::Duration};
+
+    use crate::{
+        body::Body,
+        headers::{self, HeaderValue},
+        request::Request,
+        response::Response,
+        server::Server,
+        status::StatusCode,
+    };
+
+    use super::{EventParser, SseClient};
+
+    #[test]
+    fn should_parse_events() {
+        let mut parser = EventParser::new();
+        parser.feed(b": comment\r\nevent: greet\r\ndata: hello\r\ndata:world\r\nid: 1\r\n\r\n");
+        parser.feed(b"retry: 500\n\ndata: partial");
+
+        let event = parser.next_event().unwrap();
+        assert_eq!(event.event(), Some("greet"));
+        assert_eq!(event.data(), "hello\nworld");
+        assert_eq!(event.id(), Some("1"));
+
+        assert!(parser.next_event().is_none());
+        assert_eq!(parser.retry, Duration::from_millis(500));
+
+        parser.feed(b"\n\n");
+        let event = parser.next_event().unwrap();
+        assert_eq!(event.event(), None);
+        assert_eq!(event.data(), "partial");
+        assert_eq!(event.id(), Some("1"));
+    }
+
+    struct Counter {
+        count: usize,
+    }
+
+    serde::impl_deserialize_struct!(Counter => {
+        count: usize
+    });
+
+    #[test]
+    fn should_receive_events_and_reconnect_with_last_event_id() {
+        let port = crate::common::find_open_port::find_open_port_in_range(3500..).unwrap();
+        let addr = format!("0.0.0.0:{port}");
+
+        let server = Server::new();
+        let handle = server.handle();
+        let (tx, rx) = channel();
+        let (ready_tx, ready_rx) = channel();
+
+        std::thread::spawn(move || {
+            server
+                .on_ready(move |_| {
+                    ready_tx.send(()).unwrap();
+                })
+                .listen(addr, move |request: Request<Body>| {
+                    let last_event_id = request
+                        .headers()
+                        .get(headers::LAST_EVENT_ID)
+                        .map(|x| x.as_str().parse::<u32>().unwrap());
+
+                    tx.send(last_event_id).unwrap();
+
+                    let start = last_event_id.unwrap_or(0) + 1;
+                    if start > 3 {
+                        return Response::new(StatusCode::NO_CONTENT, ().into());
+                    }
+
+                    // Sends at most 2 events per connection
+                    let mut body = String::from("retry: 10\n\n");
+                    for i in start..=(start + 1).min(3) {
+                        body.push_str(&format!("data: {{ \"count\": {i} }}\ndata: \nid: {i}\n\n"));
+                    }
+
+                    Response::builder()
+                        .insert_header(
+                            headers::CONTENT_TYPE,
+                            HeaderValue::from_static("text/event-stream"),
+                        )
+                        .body(body.into())
+                })
+                .unwrap();
+        });
+
+        ready_rx
+            .recv()
+            .unwrap_or_else(|_| panic!("Server failed to start"));
+
+        let mut client = SseClient::new(format!("http://127.0.0.1:{port}/counter"));
+        let mut events = Vec::new();
+
+        while let Some(event) = client.next_event().unwrap() {
+            events.push(event);
+        }
+
+        assert_eq!(events.len(), 3);
+
+        for (idx, event) in events.iter().enumerate() {
+            let count = idx + 1;
+            assert_eq!(event.id(), Some(count.to_string().as_str()));
+            assert_eq!(event.data(), format!("{{ \"count\": {count} }}\n"));
+
+            let counter = event.json::<Counter>().unwrap();
+            assert_eq!(counter.count, count);
+        }
+
+        assert_eq!(client.retry(), Duration::from_millis(10));
+        assert_eq!(client.last_event_id(), Some("3"));
+
+        // Initial connection and the reconnections
+        assert_eq!(rx.recv().unwrap(), None);
+        assert_eq!(rx.recv().unwrap(), Some(2));
+        assert_eq!(rx.recv().unwrap(), Some(3));
+
+        handle.shutdown();
+    }
+}
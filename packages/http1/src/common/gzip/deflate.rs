@@ -0,0 +1,197 @@
+use super::{DIST_BASE, DIST_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+const NONE: usize = usize::MAX;
+
+/// Writes bits starting from the least significant bit, as required by DEFLATE.
+struct BitWriter {
+    out: Vec<u8>,
+    bits: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            out: Vec::new(),
+            bits: 0,
+            count: 0,
+        }
+    }
+
+    fn write_bits(&mut self, value: u32, len: u32) {
+        self.bits |= (value as u64) << self.count;
+        self.count += len;
+
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are packed starting from the most significant bit.
+    fn write_code(&mut self, code: u32, len: u32) {
+        let reversed = code.reverse_bits() >> (32 - len);
+        self.write_bits(reversed, len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+
+        self.out
+    }
+}
+
+/// Finds repeated sequences in the previous `WINDOW_SIZE` bytes using hash chains.
+///
+/// The chains only cover the window, `prev` holds the distance to the previous position
+/// with the same hash or `0` if there is none.
+struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<usize>,
+    prev: Vec<u16>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Matcher {
+            data,
+            head: vec![NONE; 1 << HASH_BITS],
+            prev: vec![0; WINDOW_SIZE],
+        }
+    }
+
+    fn hash(&self, pos: usize) -> usize {
+        let bytes = &self.data[pos..pos + MIN_MATCH];
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, pos: usize) {
+        if pos + MIN_MATCH <= self.data.len() {
+            let h = self.hash(pos);
+            let distance = match self.head[h] {
+                NONE => 0,
+                last if pos - last <= WINDOW_SIZE => pos - last,
+                _ => 0,
+            };
+
+            self.prev[pos % WINDOW_SIZE] = distance as u16;
+            self.head[h] = pos;
+        }
+    }
+
+    /// Returns the length and distance of the longest match at the given position.
+    fn find_match(&self, pos: usize) -> Option<(usize, usize)> {
+        if pos + MIN_MATCH > self.data.len() {
+            return None;
+        }
+
+        let max_len = MAX_MATCH.min(self.data.len() - pos);
+        let mut candidate = self.head[self.hash(pos)];
+        let mut best: Option<(usize, usize)> = None;
+        let mut chain = 0;
+
+        while candidate != NONE && pos - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+            let len = self.data[candidate..]
+                .iter()
+                .zip(&self.data[pos..pos + max_len])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if len >= MIN_MATCH && best.is_none_or(|(best_len, _)| len > best_len) {
+                best = Some((len, pos - candidate));
+
+                if len == max_len {
+                    break;
+                }
+            }
+
+            candidate = match self.prev[candidate % WINDOW_SIZE] {
+                0 => NONE,
+                distance => candidate - distance as usize,
+            };
+            chain += 1;
+        }
+
+        best
+    }
+}
+
+/// Writes a literal/length symbol using the fixed Huffman codes.
+fn write_symbol(writer: &mut BitWriter, symbol: u16) {
+    let symbol = symbol as u32;
+
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + (symbol - 144), 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xC0 + (symbol - 280), 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let length_idx = LENGTH_BASE
+        .iter()
+        .rposition(|base| *base as usize <= length)
+        .expect("invalid match length");
+
+    write_symbol(writer, 257 + length_idx as u16);
+    writer.write_bits(
+        (length - LENGTH_BASE[length_idx] as usize) as u32,
+        LENGTH_EXTRA[length_idx] as u32,
+    );
+
+    let dist_idx = DIST_BASE
+        .iter()
+        .rposition(|base| *base as usize <= distance)
+        .expect("invalid match distance");
+
+    // Distance codes are fixed 5-bits codes
+    writer.write_code(dist_idx as u32, 5);
+    writer.write_bits(
+        (distance - DIST_BASE[dist_idx] as usize) as u32,
+        DIST_EXTRA[dist_idx] as u32,
+    );
+}
+
+/// Compresses the data into a single DEFLATE block using the fixed Huffman codes.
+pub(crate) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    let mut matcher = Matcher::new(data);
+    let mut pos = 0;
+
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes)
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    while pos < data.len() {
+        match matcher.find_match(pos) {
+            Some((length, distance)) => {
+                write_match(&mut writer, length, distance);
+
+                for p in pos..pos + length {
+                    matcher.insert(p);
+                }
+
+                pos += length;
+            }
+            None => {
+                write_symbol(&mut writer, data[pos] as u16);
+                matcher.insert(pos);
+                pos += 1;
+            }
+        }
+    }
+
+    // End of block
+    write_symbol(&mut writer, 256);
+    writer.finish()
+}
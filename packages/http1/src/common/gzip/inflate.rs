@@ -0,0 +1,262 @@
+use super::{GzipError, DIST_BASE, DIST_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+
+const MAX_BITS: usize = 15;
+
+/// Order of the code length codes in a dynamic block header.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Reads bits starting from the least significant bit.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            pos: 0,
+            bits: 0,
+            count: 0,
+        }
+    }
+
+    fn read_bits(&mut self, len: u32) -> Result<u32, GzipError> {
+        while self.count < len {
+            let byte = *self.data.get(self.pos).ok_or(GzipError::UnexpectedEof)?;
+            self.bits |= (byte as u32) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+
+        let value = self.bits & ((1u64 << len) - 1) as u32;
+        self.bits >>= len;
+        self.count -= len;
+        Ok(value)
+    }
+
+    /// Discards the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.bits = 0;
+        self.count = 0;
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], GzipError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(GzipError::UnexpectedEof)?;
+
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+/// A canonical Huffman code, represented by the number of codes of each length and the symbols ordered by code.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for len in lengths {
+            counts[*len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                symbols[offsets[*len as usize] as usize] = symbol as u16;
+                offsets[*len as usize] += 1;
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, GzipError> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for len in 1..=MAX_BITS {
+            code |= reader.read_bits(1)? as i32;
+            let count = self.counts[len] as i32;
+
+            if code - count < first {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+
+        Err(GzipError::InvalidData("invalid huffman code"))
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), GzipError> {
+    let num_lengths = reader.read_bits(5)? as usize + 257;
+    let num_distances = reader.read_bits(5)? as usize + 1;
+    let num_code_lengths = reader.read_bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for idx in CODE_LENGTH_ORDER.iter().take(num_code_lengths) {
+        code_lengths[*idx] = reader.read_bits(3)? as u8;
+    }
+
+    let code_lengths = Huffman::new(&code_lengths);
+    let mut lengths = Vec::with_capacity(num_lengths + num_distances);
+
+    while lengths.len() < num_lengths + num_distances {
+        let (value, repeat) = match code_lengths.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let prev = *lengths
+                    .last()
+                    .ok_or(GzipError::InvalidData("repeat with no previous length"))?;
+                (prev, 3 + reader.read_bits(2)?)
+            }
+            17 => (0, 3 + reader.read_bits(3)?),
+            _ => (0, 11 + reader.read_bits(7)?),
+        };
+
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+
+    if lengths.len() > num_lengths + num_distances {
+        return Err(GzipError::InvalidData("too many code lengths"));
+    }
+
+    let (lit_lengths, dist_lengths) = lengths.split_at(num_lengths);
+    Ok((Huffman::new(lit_lengths), Huffman::new(dist_lengths)))
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    out: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+    max_output: Option<usize>,
+) -> Result<(), GzipError> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+
+        match symbol {
+            0..=255 => {
+                check_output_size(out.len() + 1, max_output)?;
+                out.push(symbol as u8);
+            }
+            256 => return Ok(()),
+            _ => {
+                let idx = symbol - 257;
+                if idx >= LENGTH_BASE.len() {
+                    return Err(GzipError::InvalidData("invalid length symbol"));
+                }
+
+                let length = LENGTH_BASE[idx] as usize
+                    + reader.read_bits(LENGTH_EXTRA[idx] as u32)? as usize;
+
+                let idx = distances.decode(reader)? as usize;
+                if idx >= DIST_BASE.len() {
+                    return Err(GzipError::InvalidData("invalid distance symbol"));
+                }
+
+                let distance =
+                    DIST_BASE[idx] as usize + reader.read_bits(DIST_EXTRA[idx] as u32)? as usize;
+
+                if distance > out.len() {
+                    return Err(GzipError::InvalidData("distance too far back"));
+                }
+
+                check_output_size(out.len() + length, max_output)?;
+
+                // The match may overlap with the bytes being copied
+                let start = out.len() - distance;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+fn check_output_size(len: usize, max_output: Option<usize>) -> Result<(), GzipError> {
+    match max_output {
+        Some(max_output) if len > max_output => Err(GzipError::OutputTooLarge(max_output)),
+        _ => Ok(()),
+    }
+}
+
+/// Decompresses DEFLATE data, returns the decompressed bytes and the number of bytes read.
+///
+/// Fails with `OutputTooLarge` if the decompressed data exceeds `max_output` bytes.
+pub(crate) fn inflate(
+    data: &[u8],
+    max_output: Option<usize>,
+) -> Result<(Vec<u8>, usize), GzipError> {
+    let mut reader = BitReader::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let is_final = reader.read_bits(1)? == 1;
+
+        match reader.read_bits(2)? {
+            // Stored
+            0 => {
+                reader.align();
+                let header = reader.read_bytes(4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+
+                if len != !nlen {
+                    return Err(GzipError::InvalidData("invalid stored block length"));
+                }
+
+                check_output_size(out.len() + len as usize, max_output)?;
+                out.extend_from_slice(reader.read_bytes(len as usize)?);
+            }
+            // Fixed Huffman codes
+            1 => {
+                let (literals, distances) = fixed_codes();
+                inflate_block(&mut reader, &mut out, &literals, &distances, max_output)?;
+            }
+            // Dynamic Huffman codes
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut reader)?;
+                inflate_block(&mut reader, &mut out, &literals, &distances, max_output)?;
+            }
+            _ => return Err(GzipError::InvalidData("invalid block type")),
+        }
+
+        if is_final {
+            break;
+        }
+    }
+
+    Ok((out, reader.pos))
+}
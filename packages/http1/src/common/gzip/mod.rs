@@ -0,0 +1,338 @@
+mod deflate;
+mod inflate;
+
+use std::fmt::Display;
+
+/// Base lengths for the length symbols 257..285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// Extra bits for the length symbols 257..285.
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances for the distance symbols 0..29.
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits for the distance symbols 0..29.
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// CMF and FLG of a zlib stream using deflate with a 32K window and the default level.
+const ZLIB_HEADER: [u8; 2] = [0x78, 0x9c];
+const METHOD_DEFLATE: u8 = 8;
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+/// An error decompressing gzip data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GzipError {
+    /// The data is not in the gzip format.
+    InvalidHeader,
+
+    /// The compressed data is invalid.
+    InvalidData(&'static str),
+
+    /// The checksum or size of the decompressed data don't match.
+    ChecksumMismatch,
+
+    /// The data ended before expected.
+    UnexpectedEof,
+
+    /// The decompressed data is larger than the given max size.
+    OutputTooLarge(usize),
+}
+
+impl Display for GzipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GzipError::InvalidHeader => write!(f, "invalid gzip header"),
+            GzipError::InvalidData(msg) => write!(f, "invalid compressed data: {msg}"),
+            GzipError::ChecksumMismatch => write!(f, "gzip checksum mismatch"),
+            GzipError::UnexpectedEof => write!(f, "unexpected end of gzip data"),
+            GzipError::OutputTooLarge(max) => {
+                write!(f, "decompressed data exceeds the max size of {max} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GzipError {}
+
+/// Compresses the data using the gzip format.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2 + 18);
+
+    // ID1, ID2, CM, FLG, MTIME (4 bytes), XFL, OS (unknown)
+    out.extend_from_slice(&GZIP_MAGIC);
+    out.extend_from_slice(&[METHOD_DEFLATE, 0, 0, 0, 0, 0, 0, 0xff]);
+    out.extend_from_slice(&deflate::deflate(data));
+
+    // CRC32 and size modulo 2^32
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Decompresses data in the gzip format.
+///
+/// The output size is not limited, use [`decompress_with_limit`] for untrusted input.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, GzipError> {
+    decompress_gzip(data, None)
+}
+
+/// Decompresses data in the gzip format, fails if the decompressed data exceeds `max_output` bytes.
+pub fn decompress_with_limit(data: &[u8], max_output: usize) -> Result<Vec<u8>, GzipError> {
+    decompress_gzip(data, Some(max_output))
+}
+
+fn decompress_gzip(data: &[u8], max_output: Option<usize>) -> Result<Vec<u8>, GzipError> {
+    if data.len() < 18 || data[..2] != GZIP_MAGIC || data[2] != METHOD_DEFLATE {
+        return Err(GzipError::InvalidHeader);
+    }
+
+    let flags = data[3];
+    let mut pos = 10;
+
+    if flags & FLAG_EXTRA != 0 {
+        let len = data.get(pos..pos + 2).ok_or(GzipError::UnexpectedEof)?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(pos..)
+                .and_then(|x| x.iter().position(|b| *b == 0))
+                .ok_or(GzipError::UnexpectedEof)?;
+            pos += end + 1;
+        }
+    }
+
+    if flags & FLAG_HCRC != 0 {
+        pos += 2;
+    }
+
+    let compressed = data.get(pos..).ok_or(GzipError::UnexpectedEof)?;
+    let (out, read) = inflate::inflate(compressed, max_output)?;
+
+    let trailer = compressed
+        .get(read..read + 8)
+        .ok_or(GzipError::UnexpectedEof)?;
+
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+
+    if crc != crc32(&out) || size != out.len() as u32 {
+        return Err(GzipError::ChecksumMismatch);
+    }
+
+    Ok(out)
+}
+
+/// Compresses the data using the zlib format, which is used by the `deflate` content encoding.
+pub fn compress_zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2 + 6);
+    out.extend_from_slice(&ZLIB_HEADER);
+    out.extend_from_slice(&deflate::deflate(data));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+/// Decompresses data in the zlib format.
+///
+/// The output size is not limited, use [`decompress_zlib_with_limit`] for untrusted input.
+pub fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>, GzipError> {
+    decompress_zlib_inner(data, None)
+}
+
+/// Decompresses data in the zlib format, fails if the decompressed data exceeds `max_output` bytes.
+pub fn decompress_zlib_with_limit(data: &[u8], max_output: usize) -> Result<Vec<u8>, GzipError> {
+    decompress_zlib_inner(data, Some(max_output))
+}
+
+fn decompress_zlib_inner(data: &[u8], max_output: Option<usize>) -> Result<Vec<u8>, GzipError> {
+    if data.len() < 6
+        || data[0] & 0x0f != METHOD_DEFLATE
+        || !u16::from_be_bytes([data[0], data[1]]).is_multiple_of(31)
+    {
+        return Err(GzipError::InvalidHeader);
+    }
+
+    // Preset dictionaries are not supported
+    if data[1] & 0x20 != 0 {
+        return Err(GzipError::InvalidHeader);
+    }
+
+    let (out, read) = inflate::inflate(&data[2..], max_output)?;
+    let trailer = data
+        .get(2 + read..2 + read + 4)
+        .ok_or(GzipError::UnexpectedEof)?;
+
+    if u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) != adler32(&out) {
+        return Err(GzipError::ChecksumMismatch);
+    }
+
+    Ok(out)
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+
+    // Largest number of bytes that can be added before the sums overflow
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+
+        a %= MOD_ADLER;
+        b %= MOD_ADLER;
+    }
+
+    (b << 16) | a
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut k = 0;
+
+        while k < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            k += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in data {
+        crc = CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adler32, compress, compress_zlib, crc32, decompress, decompress_with_limit,
+        decompress_zlib, decompress_zlib_with_limit, GzipError,
+    };
+
+    #[test]
+    fn should_compute_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn should_compute_adler32() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+    }
+
+    #[test]
+    fn should_compress_and_decompress_zlib() {
+        let payload = "<li>Hoshino Ai</li>".repeat(500);
+        let compressed = compress_zlib(payload.as_bytes());
+
+        assert_eq!(&compressed[..2], &[0x78, 0x9c]);
+        assert_eq!(decompress_zlib(&compressed).unwrap(), payload.as_bytes());
+        assert_eq!(decompress_zlib(&compress_zlib(b"")).unwrap(), b"");
+        assert_eq!(
+            decompress_zlib(&compress(b"Hello")),
+            Err(GzipError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn should_compress_and_decompress() {
+        let payload = "<li>Hoshino Ai</li>".repeat(500);
+        let compressed = compress(payload.as_bytes());
+
+        assert!(compressed.len() < payload.len() / 10);
+        assert_eq!(decompress(&compressed).unwrap(), payload.as_bytes());
+
+        let bytes = (0..=255u8).cycle().take(70_000).collect::<Vec<_>>();
+        assert_eq!(decompress(&compress(&bytes)).unwrap(), bytes);
+        assert_eq!(decompress(&compress(b"")).unwrap(), b"");
+    }
+
+    #[test]
+    fn should_limit_decompressed_size() {
+        let payload = vec![0; 1024 * 1024];
+        let compressed = compress(&payload);
+
+        assert_eq!(
+            decompress_with_limit(&compressed, 1024),
+            Err(GzipError::OutputTooLarge(1024))
+        );
+        assert_eq!(
+            decompress_with_limit(&compressed, payload.len()).unwrap(),
+            payload
+        );
+        assert_eq!(
+            decompress_zlib_with_limit(&compress_zlib(&payload), 1024),
+            Err(GzipError::OutputTooLarge(1024))
+        );
+    }
+
+    #[test]
+    fn should_decompress_dynamic_huffman_block() {
+        // Compressed with zlib at level 9
+        let data = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xb5, 0xcb, 0xc1, 0x01,
+            0x40, 0x30, 0x10, 0x05, 0xd1, 0xbb, 0x2a, 0xbe, 0x06, 0xd4, 0xe2, 0x90, 0x06, 0x82,
+            0x4d, 0x2c, 0x61, 0x89, 0x04, 0x49, 0xf5, 0xb6, 0x09, 0xe7, 0x79, 0x63, 0x66, 0xc2,
+            0x99, 0x79, 0x5c, 0x31, 0x44, 0x79, 0x76, 0x38, 0x79, 0xb1, 0xe4, 0xed, 0xb8, 0x20,
+            0x37, 0x45, 0x24, 0xcd, 0xc1, 0xd6, 0x82, 0x49, 0x7c, 0x07, 0xf3, 0x1b, 0xee, 0xad,
+            0xba, 0xad, 0x60, 0x50, 0xf4, 0x70, 0x9a, 0xe1, 0xf8, 0x26, 0x4d, 0x95, 0x76, 0x04,
+            0x3e, 0xb3, 0x44, 0x7d, 0xfd, 0xd5, 0x36, 0x1f, 0x9b, 0x04, 0x8c, 0x46, 0xb0, 0x00,
+            0x00, 0x00,
+        ];
+
+        let expected = "The quick brown fox jumps over the lazy dog. ".repeat(3)
+            + "Pack my box with five dozen liquor jugs!\n";
+
+        assert_eq!(decompress(&data).unwrap(), expected.as_bytes());
+    }
+
+    #[test]
+    fn should_reject_corrupted_data() {
+        let mut compressed = compress(b"Hello World!");
+        let len = compressed.len();
+        compressed[len - 8] ^= 0xff;
+
+        assert_eq!(decompress(&compressed), Err(GzipError::ChecksumMismatch));
+        assert_eq!(decompress(b"hello"), Err(GzipError::InvalidHeader));
+    }
+}
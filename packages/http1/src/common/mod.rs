@@ -7,9 +7,15 @@ pub mod broadcast;
 /// Base64 utilities.
 pub mod base64;
 
+/// Gzip compression.
+pub mod gzip;
+
 /// SHA-1 encryption.
 pub mod sha1;
 
+/// SHA-256 hashing and HMAC-SHA256.
+pub mod sha256;
+
 /// Provides an OS tempfile.
 pub mod temp_file;
 
@@ -1,2 +1,11 @@
 /// Max allowed size for the request body.
 pub const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024 * 1024; // 64mb
+
+/// Max number of requests handled by a single keep-alive connection.
+pub const DEFAULT_MAX_REQUESTS_PER_CONNECTION: usize = 100;
+
+/// Max time to wait for the next request in a keep-alive connection.
+pub const DEFAULT_KEEP_ALIVE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Value of the `Server` header included in each response.
+pub const DEFAULT_SERVER_HEADER: &str = concat!("http1-rs/", env!("CARGO_PKG_VERSION"));
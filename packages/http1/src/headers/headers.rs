@@ -82,6 +82,14 @@ impl Headers {
         GetAll { iter }
     }
 
+    /// Returns all the comma-separated items of the values of the given header, each item is trimmed and empty items are skipped.
+    pub fn get_split(&self, key: impl AsHeaderName) -> GetSplit<'_> {
+        GetSplit {
+            values: self.get_all(key),
+            items: None,
+        }
+    }
+
     pub fn get_mut(&mut self, key: impl AsHeaderName) -> Option<&mut HeaderValue> {
         match key.find(self) {
             Some(idx) => {
@@ -219,6 +227,31 @@ impl<'a> Iterator for GetAll<'a> {
     }
 }
 
+pub struct GetSplit<'a> {
+    values: GetAll<'a>,
+    items: Option<std::str::Split<'a, char>>,
+}
+
+impl<'a> Iterator for GetSplit<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(items) = self.items.as_mut() {
+                for item in items.by_ref() {
+                    let item = item.trim();
+                    if !item.is_empty() {
+                        return Some(item);
+                    }
+                }
+            }
+
+            let value = self.values.next()?;
+            self.items = Some(value.as_str().split(','));
+        }
+    }
+}
+
 pub struct Keys<'a> {
     iter: std::slice::Iter<'a, HeaderEntry>,
 }
@@ -417,6 +450,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_get_all_repeated_set_cookie() {
+        let mut headers = Headers::new();
+        headers.append(
+            "Set-Cookie".try_into().unwrap(),
+            HeaderValue::from_static("session=abc; Path=/"),
+        );
+        headers.append(
+            "Set-Cookie".try_into().unwrap(),
+            HeaderValue::from_static("theme=dark; Expires=Wed, 21 Oct 2026 07:28:00 GMT"),
+        );
+
+        assert_eq!(
+            headers
+                .get_all("set-cookie")
+                .map(|x| x.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "session=abc; Path=/",
+                "theme=dark; Expires=Wed, 21 Oct 2026 07:28:00 GMT"
+            ]
+        );
+        assert_eq!(headers.get_all("cookie").count(), 0);
+    }
+
+    #[test]
+    fn should_get_split_comma_separated_values() {
+        let mut headers = Headers::new();
+        headers.append(
+            "Accept-Encoding".try_into().unwrap(),
+            HeaderValue::from_static("gzip, deflate;q=0.5 ,,"),
+        );
+        headers.append(
+            "Accept-Encoding".try_into().unwrap(),
+            HeaderValue::from_static(" br "),
+        );
+
+        assert_eq!(
+            headers.get_split("accept-encoding").collect::<Vec<_>>(),
+            vec!["gzip", "deflate;q=0.5", "br"]
+        );
+        assert_eq!(headers.get_split("accept").count(), 0);
+    }
+
+    #[test]
+    fn should_parse_header_value() {
+        assert_eq!(
+            HeaderValue::from_static(" 120 ").parse::<u64>().unwrap(),
+            120
+        );
+        assert!(HeaderValue::from_static("abc").parse::<u64>().is_err());
+    }
+
     #[test]
     fn should_replace_existing_on_insert() {
         let mut headers = Headers::new();
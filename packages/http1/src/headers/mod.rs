@@ -32,6 +32,7 @@ define_header_names! {
     CONTENT_LENGTH => "Content-Length",
     CONTENT_TYPE => "Content-Type",
     CONTENT_ENCODING => "Content-Encoding",
+    TRAILER => "Trailer",
     TRANSFER_ENCODING => "Transfer-Encoding",
     CONTENT_DISPOSITION => "Content-Disposition",
     ACCEPT => "Accept",
@@ -48,6 +49,7 @@ define_header_names! {
     UPGRADE => "Upgrade",
     LOCATION => "Location",
     X_FORWARDED_FOR => "X-Forwarded-For",
+    X_FORWARDED_PROTO => "X-Forwarded-Proto",
     X_REAL_IP => "X-Real-IP", // NON-STANDARD
     X_CLIENT_IP => "X-Client-IP", // NON-STANDARD
     X_FRAME_OPTIONS => "X-Frame-Options",
@@ -62,8 +64,10 @@ define_header_names! {
     IF_MODIFIED_SINCE => "If-Modified-Since",
     IF_UNMODIFIED_SINCE => "If-Unmodified-Since",
     RANGE => "Range",
+    CONTENT_RANGE => "Content-Range",
     ACCEPT_RANGES => "Accept-Ranges",
     RETRY_AFTER => "Retry-After",
+    SERVER => "Server",
     VARY => "Vary",
     EXPECT => "Expect",
     ALLOW => "Allow",
@@ -78,7 +82,9 @@ define_header_names! {
     CONTENT_SECURITY_POLICY => "Content-Security-Policy",
     CONTENT_SECURITY_POLICY_REPORT_ONLY => "Content-Security-Policy-Report-Only",
     FEATURE_POLICY => "Feature-Policy",
+    SERVER_TIMING => "Server-Timing",
     PERMISSIONS_POLICY => "Permissions-Policy",
+    IDEMPOTENCY_KEY => "Idempotency-Key",
 
     // Web sockets
     SEC_WEBSOCKET_ACCEPT => "Sec-WebSocket-Accept",
@@ -86,4 +92,7 @@ define_header_names! {
     SEC_WEBSOCKET_VERSION => "Sec-WebSocket-Version",
     SEC_WEBSOCKET_PROTOCOL => "Sec-WebSocket-Protocol",
     SEC_WEBSOCKET_EXTENSIONS => "Sec-WebSocket-Extensions",
+
+    // Server-sent events
+    LAST_EVENT_ID => "Last-Event-ID",
 }
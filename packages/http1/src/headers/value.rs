@@ -1,4 +1,4 @@
-use std::{borrow::Cow, convert::Infallible, fmt::Display};
+use std::{borrow::Cow, convert::Infallible, fmt::Display, str::FromStr};
 
 #[derive(Debug)]
 pub struct InvalidHeaderValue(String);
@@ -52,6 +52,11 @@ impl HeaderValue {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Parses the trimmed value to the given type.
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.as_str().trim().parse()
+    }
 }
 
 impl Display for HeaderValue {
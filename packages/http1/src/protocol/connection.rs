@@ -2,6 +2,7 @@ use std::{
     fmt::Debug,
     io::{Read, Write},
     net::{SocketAddr, TcpStream},
+    time::Duration,
 };
 
 use super::h1::io::IoStream;
@@ -33,6 +34,22 @@ impl Connection {
             Connection::Io(_) => None,
         }
     }
+
+    /// Sets the read timeout of the connection, this have no effect for `Io` connections.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            Connection::Tcp(tcp_stream) => tcp_stream.set_read_timeout(timeout),
+            Connection::Io(_) => Ok(()),
+        }
+    }
+
+    /// Sets the write timeout of the connection, this have no effect for `Io` connections.
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            Connection::Tcp(tcp_stream) => tcp_stream.set_write_timeout(timeout),
+            Connection::Io(_) => Ok(()),
+        }
+    }
 }
 
 impl Debug for Connection {
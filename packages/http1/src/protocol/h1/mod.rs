@@ -2,12 +2,29 @@ pub(crate) mod io;
 pub mod request;
 pub mod response;
 
-use std::io::ErrorKind;
+use std::{
+    io::{BufRead, BufReader, ErrorKind},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use crate::{
-    body::Body, handler::RequestHandler, headers, method::Method, request::Request, server::Config,
+    body::{body_reader::BodyLimitExceeded, http_body::HttpBody, Body},
+    handler::RequestHandler,
+    headers::{self, HeaderValue},
+    method::Method,
+    request::Request,
+    response::Response,
+    server::Config,
+    status::StatusCode,
+    version::Version,
 };
 
+use request::ReaderSlot;
+
 use super::{
     connection::{Connected, Connection},
     upgrade::{PendingUpgrade, Upgrade},
@@ -15,8 +32,32 @@ use super::{
 
 /**
  * Handles and send a response to a HTTP1 request.
+ *
+ * The connection is kept alive to handle more requests until the client closes it or asks to close it,
+ * the connection is also closed when the `max_requests_per_connection` or `keep_alive_timeout` are reached.
  */
 pub fn handle_incoming<H>(handler: &H, config: &Config, conn: Connection) -> std::io::Result<()>
+where
+    H: RequestHandler + Send + Sync + 'static,
+{
+    handle_connection(
+        handler,
+        config,
+        conn,
+        &AtomicBool::new(false),
+        &AtomicUsize::new(0),
+    )
+}
+
+/// Handles the requests of a connection, idle connections are closed when `is_closed` is set
+/// or when `pending` connections are waiting for a worker.
+pub(crate) fn handle_connection<H>(
+    handler: &H,
+    config: &Config,
+    conn: Connection,
+    is_closed: &AtomicBool,
+    pending: &AtomicUsize,
+) -> std::io::Result<()>
 where
     H: RequestHandler + Send + Sync + 'static,
 {
@@ -27,49 +68,202 @@ where
         }
     };
 
-    // Create the request object
-    let mut request = request::read_request(conn, config)?;
-
-    // If the connection can be upgraded, we create a pending upgrade
-    let can_be_upgraded = is_upgrade_request(&request);
-    let pending_upgrade = if can_be_upgraded {
-        let (sender, pending) = PendingUpgrade::new();
-        let conn = write_conn
-            .try_clone()
-            .expect("failed to clone connection stream for upgrade connection");
-
-        request.extensions_mut().insert(pending);
-        Some((sender, conn))
-    } else {
-        None
-    };
+    let slot: ReaderSlot<Connection> = Arc::new(Mutex::new(None));
+    let mut reader = BufReader::new(conn);
+    let mut request_count = 0;
 
-    // Append any extra information to the request
-    pre_process_request(&mut request, &write_conn, config);
+    loop {
+        request_count += 1;
 
-    // Get the response from the handler
-    let discard_body = request.method() == Method::HEAD;
-    let response = handler.handle(request);
+        // Create the request object
+        let mut request = match request::read_keep_alive_request(reader, config, &slot) {
+            Ok(request) => request,
+            Err(err) if BodyLimitExceeded::is_body_limit_error(&err) => {
+                log::debug!("Rejecting request: {err}");
+                let mut response = Response::new(StatusCode::PAYLOAD_TOO_LARGE, Body::empty());
 
-    // Write the response to the stream
-    match response::write_response(response, &mut write_conn, discard_body, config) {
-        Ok(_) => {
-            // If the connection can be upgrade, notify after write the response
-            if let Some((notifier, conn)) = pending_upgrade {
-                let upgrade = Upgrade::new(conn);
-                notifier.notify(upgrade);
+                // The body was not read so the connection cannot be reused
+                response
+                    .headers_mut()
+                    .insert(headers::CONNECTION, HeaderValue::from_static("close"));
+                return response::write_response(response, &mut write_conn, false, config);
+            }
+            Err(err) if is_timeout_error(&err) => {
+                log::debug!("Timeout reading request: {err}");
+                let mut response = Response::new(StatusCode::REQUEST_TIMEOUT, Body::empty());
+                response
+                    .headers_mut()
+                    .insert(headers::CONNECTION, HeaderValue::from_static("close"));
+                return response::write_response(response, &mut write_conn, false, config);
+            }
+            Err(err) if err.kind() == ErrorKind::Unsupported => {
+                log::debug!("Rejecting request: {err}");
+                let mut response = Response::new(StatusCode::NOT_IMPLEMENTED, Body::empty());
+                response
+                    .headers_mut()
+                    .insert(headers::CONNECTION, HeaderValue::from_static("close"));
+                return response::write_response(response, &mut write_conn, false, config);
             }
+            Err(err) if err.kind() == ErrorKind::InvalidData => {
+                log::debug!("Rejecting malformed request: {err}");
+                let response = Response::new(StatusCode::BAD_REQUEST, Body::empty());
+                return response::write_response(response, &mut write_conn, false, config);
+            }
+            Err(err) => return Err(err),
+        };
+
+        // If the connection can be upgraded, we create a pending upgrade
+        let is_connect = request.method() == Method::CONNECT;
+        let can_be_upgraded = is_connect || is_upgrade_request(&request);
+        let pending_upgrade = if can_be_upgraded {
+            let (sender, pending) = PendingUpgrade::new();
+            let conn = write_conn
+                .try_clone()
+                .expect("failed to clone connection stream for upgrade connection");
+
+            request.extensions_mut().insert(pending);
+            Some((sender, conn))
+        } else {
+            None
+        };
+
+        let is_http_1_0 = *request.version() == Version::Http1_0;
+        let can_keep_alive = !can_be_upgraded
+            && is_keep_alive_request(&request)
+            && config
+                .max_requests_per_connection
+                .is_none_or(|max| request_count < max);
+
+        // Append any extra information to the request
+        pre_process_request(&mut request, &write_conn, config);
+
+        // Get the response from the handler
+        let discard_body = request.method() == Method::HEAD;
+        let mut response = handler.handle(request);
+
+        // A tunnel is only established if the `CONNECT` request succeeds
+        let is_tunnel = is_connect && response.status().is_success();
+        let pending_upgrade = pending_upgrade.filter(|_| !is_connect || is_tunnel);
+        let is_upgrade_accepted =
+            is_tunnel || (!is_connect && response.status() == StatusCode::SWITCHING_PROTOCOLS);
 
-            Ok(())
+        // HTTP/1.0 clients don't understand chunked encoding, for those the body ends when the connection is closed
+        if !is_http_1_0 && !can_be_upgraded {
+            response::apply_chunked_encoding(&mut response);
         }
-        Err(err) if err.kind() == ErrorKind::ConnectionAborted => {
-            log::debug!("Connection was aborted");
-            Ok(())
+
+        // The connection only can be reused if the request body was consumed and we know where the response ends
+        let next_reader = slot.lock().expect("failed to lock reader slot").take();
+        let next_reader =
+            next_reader.filter(|_| can_keep_alive && (discard_body || has_known_length(&response)));
+
+        // A refused upgrade also closes the connection
+        if next_reader.is_none() && !is_upgrade_accepted {
+            response
+                .headers_mut()
+                .insert(headers::CONNECTION, HeaderValue::from_static("close"));
+        } else if next_reader.is_some() && is_http_1_0 {
+            response
+                .headers_mut()
+                .insert(headers::CONNECTION, HeaderValue::from_static("keep-alive"));
+        }
+
+        // Write the response to the stream
+        let result = if is_tunnel {
+            response::write_tunnel_response(response, &mut write_conn, config)
+        } else {
+            response::write_response(response, &mut write_conn, discard_body, config)
+        };
+
+        match result {
+            Ok(_) => {
+                // If the connection can be upgrade, notify after write the response
+                if let Some((notifier, conn)) = pending_upgrade {
+                    // Upgraded connections are long-lived, the request timeouts no longer apply
+                    conn.set_read_timeout(None)?;
+                    conn.set_write_timeout(None)?;
+
+                    let upgrade = Upgrade::new(conn);
+                    notifier.notify(upgrade);
+                    return Ok(());
+                }
+            }
+            Err(err) if is_disconnect_error(&err) => {
+                log::debug!("Client disconnected while writing the response: {err}");
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        }
+
+        reader = match next_reader {
+            Some(reader) => reader,
+            None => return Ok(()),
+        };
+
+        if !wait_next_request(&mut reader, config, is_closed, pending)? {
+            log::debug!("Closing idle connection");
+            return Ok(());
         }
-        Err(err) => Err(err),
     }
 }
 
+/// Waits until the next request is available, returns `false` if the connection should be closed.
+///
+/// An idle connection holds a worker, so it is closed if other connections are waiting for one.
+fn wait_next_request(
+    reader: &mut BufReader<Connection>,
+    config: &Config,
+    is_closed: &AtomicBool,
+    pending: &AtomicUsize,
+) -> std::io::Result<bool> {
+    // We wake up periodically to check if the server was closed
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    let deadline = config.keep_alive_timeout.map(|x| Instant::now() + x);
+
+    let result = loop {
+        if is_closed.load(Ordering::Relaxed) {
+            break Ok(false);
+        }
+
+        let timeout = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => remaining.min(POLL_INTERVAL),
+                _ => break Ok(false),
+            },
+            None => POLL_INTERVAL,
+        };
+
+        reader.get_ref().set_read_timeout(Some(timeout))?;
+
+        match reader.fill_buf() {
+            Ok(buf) => break Ok(!buf.is_empty()),
+            Err(err) if is_timeout_error(&err) => {
+                if pending.load(Ordering::Relaxed) > 0 {
+                    log::debug!("Releasing idle connection, other connections are waiting");
+                    break Ok(false);
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::ConnectionReset => break Ok(false),
+            Err(err) => break Err(err),
+        }
+    };
+
+    reader.get_ref().set_read_timeout(config.read_timeout)?;
+    result
+}
+
+fn is_timeout_error(err: &std::io::Error) -> bool {
+    matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+fn is_disconnect_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe | ErrorKind::ConnectionReset
+    )
+}
+
 fn pre_process_request(request: &mut Request<Body>, conn: &Connection, config: &Config) {
     if config.include_conn_info {
         request
@@ -82,6 +276,25 @@ fn pre_process_request(request: &mut Request<Body>, conn: &Connection, config: &
     }
 }
 
+fn is_keep_alive_request(req: &Request<Body>) -> bool {
+    let connection = req.headers().get(headers::CONNECTION);
+
+    match req.version() {
+        Version::Http1_0 => {
+            connection.is_some_and(|conn| conn.as_str().eq_ignore_ascii_case("keep-alive"))
+        }
+        Version::Http1_1 => {
+            !connection.is_some_and(|conn| conn.as_str().eq_ignore_ascii_case("close"))
+        }
+    }
+}
+
+fn has_known_length(res: &Response<Body>) -> bool {
+    res.headers().contains_key(headers::CONTENT_LENGTH)
+        || res.headers().contains_key(headers::TRANSFER_ENCODING)
+        || res.body().size_hint().is_some()
+}
+
 fn is_upgrade_request(req: &Request<Body>) -> bool {
     req.headers()
         .get(headers::CONNECTION)
@@ -91,16 +304,25 @@ fn is_upgrade_request(req: &Request<Body>) -> bool {
 #[cfg(test)]
 mod tests {
     use crate::protocol::connection::Connection;
-    use crate::{response::Response, server::Config, status::StatusCode};
+    use crate::{
+        body::{body_writer::BodyWriter, http_body::HttpBody, Body},
+        request::Request,
+        response::Response,
+        server::Config,
+        status::StatusCode,
+    };
 
     use super::handle_incoming;
     use std::io::{self, Cursor, Read, Write};
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     #[derive(Clone)]
     struct Inner {
         read_buffer: Cursor<Vec<u8>>,
         write_buffer: Vec<u8>,
+        stalled: bool,
+        write_limit: Option<usize>,
     }
 
     #[derive(Clone)]
@@ -116,10 +338,29 @@ mod tests {
                 inner: Arc::new(Mutex::new(Inner {
                     read_buffer: Cursor::new(bytes.into()),
                     write_buffer: vec![],
+                    stalled: false,
+                    write_limit: None,
                 })),
             }
         }
 
+        /// A pipe that stops sending data after the given bytes, as a client that never completes the request.
+        pub fn stalled(bytes: impl Into<std::borrow::Cow<'static, [u8]>>) -> Self {
+            let pipe = Pipe::new(bytes);
+            pipe.inner.lock().unwrap().stalled = true;
+            pipe
+        }
+
+        /// A pipe that fails with `BrokenPipe` after writing the given number of bytes, as a client that disconnects.
+        pub fn broken(
+            bytes: impl Into<std::borrow::Cow<'static, [u8]>>,
+            write_limit: usize,
+        ) -> Self {
+            let pipe = Pipe::new(bytes);
+            pipe.inner.lock().unwrap().write_limit = Some(write_limit);
+            pipe
+        }
+
         pub fn into_writer(self) -> Vec<u8> {
             self.inner.lock().unwrap().write_buffer.clone()
         }
@@ -140,13 +381,28 @@ mod tests {
     impl Read for Pipe {
         fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
             let mut inner = self.inner.lock().unwrap();
-            inner.read_buffer.read(buf)
+            let n = inner.read_buffer.read(buf)?;
+
+            // Same as a socket when the read timeout elapses
+            if n == 0 && inner.stalled {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "read timed out"));
+            }
+
+            Ok(n)
         }
     }
 
     impl Write for Pipe {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
             let mut inner = self.inner.lock().unwrap();
+
+            if inner
+                .write_limit
+                .is_some_and(|limit| inner.write_buffer.len() >= limit)
+            {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+            }
+
             inner.write_buffer.extend_from_slice(buf);
             Ok(buf.len())
         }
@@ -162,6 +418,7 @@ mod tests {
 
         let config = Config {
             include_date_header: false,
+            server_header: None,
             ..Default::default()
         };
 
@@ -177,4 +434,365 @@ mod tests {
             "HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nHello World!"
         );
     }
+
+    #[test]
+    fn should_use_content_length_for_known_size_and_chunked_otherwise() {
+        let pipe = Pipe::from(
+            "GET /bytes HTTP/1.1\r\nHost: localhost:3000\r\n\r\nGET /stream HTTP/1.1\r\nHost: localhost:3000\r\nConnection: close\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |req: Request<Body>| {
+            if req.uri().path_and_query().path() == "/bytes" {
+                return Response::new(StatusCode::OK, Body::from(b"Hello World!".to_vec()));
+            }
+
+            let (body, sender) = BodyWriter::new();
+            sender.send("Hello").unwrap();
+            sender.send(" World!").unwrap();
+            drop(sender);
+
+            Response::new(StatusCode::OK, body.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nHello World!\
+            HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n5\r\nHello\r\n7\r\n World!\r\n0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_return_ok_when_client_disconnects_mid_response() {
+        let pipe = Pipe::broken(
+            b"GET / HTTP/1.1\r\nHost: localhost:3000\r\n\r\n".as_slice(),
+            10,
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |_| {
+            let (body, sender) = BodyWriter::new();
+            for _ in 0..10 {
+                sender.send("Hello World!").unwrap();
+            }
+
+            drop(sender);
+            Response::new(StatusCode::OK, body.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        assert!(handle_incoming(&handler, &config, conn).is_ok());
+        assert!(pipe.into_writer().len() < 120);
+    }
+
+    #[test]
+    fn should_decode_path_and_keep_query_encoded() {
+        let pipe =
+            Pipe::from("GET /hello%20world?q=a%26b%3Dc HTTP/1.1\r\nHost: localhost:3000\r\n\r\n");
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |req: Request<Body>| {
+            let path_and_query = req.uri().path_and_query();
+            let body = format!(
+                "{} {}",
+                path_and_query.path(),
+                path_and_query.query_map().get("q").unwrap()
+            );
+
+            Response::new(StatusCode::OK, body.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+        assert!(response_text.ends_with("\r\n\r\n/hello world a&b=c"));
+    }
+
+    #[test]
+    fn should_reject_request_with_content_length_and_transfer_encoding() {
+        let pipe = Pipe::from(
+            "POST / HTTP/1.1\r\nHost: localhost:3000\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |_| -> Response<crate::body::Body> {
+            panic!("handler should not be called");
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_respond_not_implemented_for_unknown_transfer_encoding() {
+        let pipe = Pipe::from(
+            "POST / HTTP/1.1\r\nHost: localhost:3000\r\nTransfer-Encoding: gzip, chunked\r\n\r\n0\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |_| -> Response<crate::body::Body> {
+            panic!("handler should not be called");
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 501 Not Implemented\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_not_send_content_length_on_connect_tunnel() {
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |_| Response::new(StatusCode::OK, Body::empty());
+        let pipe = Pipe::from("CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n");
+        handle_incoming(&handler, &config, Connection::from_io(pipe.clone())).unwrap();
+
+        let response_text = std::io::read_to_string(pipe.into_writer().as_slice()).unwrap();
+        assert_eq!(response_text, "HTTP/1.1 200 OK\r\n\r\n");
+
+        // A refused tunnel closes the connection
+        let handler = |_| Response::new(StatusCode::FORBIDDEN, Body::empty());
+        let pipe = Pipe::from("CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n");
+        handle_incoming(&handler, &config, Connection::from_io(pipe.clone())).unwrap();
+
+        let response_text = std::io::read_to_string(pipe.into_writer().as_slice()).unwrap();
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 403 Forbidden\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_handle_pipelined_requests_in_same_connection() {
+        let pipe = Pipe::from(
+            "GET /first HTTP/1.1\r\nHost: localhost:3000\r\n\r\nGET /second HTTP/1.1\r\nHost: localhost:3000\r\nConnection: close\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |req: Request<Body>| {
+            let path = req.uri().path_and_query().path().to_owned();
+            Response::new(StatusCode::OK, path.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n/first\
+            HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 7\r\n\r\n/second"
+        );
+    }
+
+    #[test]
+    fn should_discard_unread_body_before_next_request() {
+        let pipe = Pipe::from(
+            "POST /first HTTP/1.1\r\nContent-Length: 5\r\n\r\nHelloGET /second HTTP/1.1\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            max_requests_per_connection: Some(2),
+            ..Default::default()
+        };
+
+        let handler = |req: Request<Body>| {
+            let path = req.uri().path_and_query().path().to_owned();
+            Response::new(StatusCode::OK, path.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n/first\
+            HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 7\r\n\r\n/second"
+        );
+    }
+
+    #[test]
+    fn should_read_chunked_body_and_reuse_connection() {
+        let pipe = Pipe::from(
+            "POST /first HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n\
+            POST /second HTTP/1.1\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n\
+            6\r\nchunks\r\n0\r\nExpires: never\r\n\r\n",
+        );
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        };
+
+        let handler = |req: Request<Body>| {
+            let bytes = req.into_body().read_all_bytes().unwrap();
+            Response::new(StatusCode::OK, bytes.into())
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello\
+            HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 6\r\n\r\nchunks"
+        );
+    }
+
+    #[test]
+    fn should_reject_request_with_content_length_larger_than_max_body_size() {
+        let pipe = Pipe::from("POST / HTTP/1.1\r\nContent-Length: 11\r\n\r\nHello World");
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            max_body_size: Some(10),
+            ..Default::default()
+        };
+
+        let handler = |_| -> Response<crate::body::Body> {
+            panic!("handler should not be called");
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 413 Payload Too Large\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_respond_with_request_timeout_when_client_stalls() {
+        let pipe = Pipe::stalled(b"GET / HTTP/1.1\r\nHost: local".as_slice());
+
+        let config = Config {
+            include_date_header: false,
+            server_header: None,
+            read_timeout: Some(Duration::from_millis(10)),
+            ..Default::default()
+        };
+
+        let handler = |_| -> Response<crate::body::Body> {
+            panic!("handler should not be called");
+        };
+
+        let conn = Connection::from_io(pipe.clone());
+        handle_incoming(&handler, &config, conn).unwrap();
+
+        let data = pipe.into_writer();
+        let response_text = std::io::read_to_string(data.as_slice()).unwrap();
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 408 Request Timeout\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn should_include_server_header_unless_disabled() {
+        let handler = |_| Response::new(StatusCode::OK, "Hello World!".into());
+        let send = |config: &Config| {
+            let pipe = Pipe::from("GET / HTTP/1.1\r\nHost: localhost:3000");
+            let conn = Connection::from_io(pipe.clone());
+            handle_incoming(&handler, config, conn).unwrap();
+            std::io::read_to_string(pipe.into_writer().as_slice()).unwrap()
+        };
+
+        let response_text = send(&Config {
+            include_date_header: false,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            response_text,
+            format!(
+                "HTTP/1.1 200 OK\r\nServer: {}\r\nContent-Length: 12\r\n\r\nHello World!",
+                crate::constants::DEFAULT_SERVER_HEADER
+            )
+        );
+
+        let response_text = send(&Config {
+            include_date_header: false,
+            server_header: None,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            response_text,
+            "HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nHello World!"
+        );
+    }
 }
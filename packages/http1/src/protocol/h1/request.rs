@@ -1,17 +1,18 @@
 use std::{
     io::{BufRead, BufReader, Read},
     str::FromStr,
+    sync::{Arc, Mutex},
 };
 
 use crate::{
     body::{
-        body_reader::{ChunkedBodyReader, FixedLengthBodyReader},
+        body_reader::{body_limit_error, ChunkedBodyReader, FixedLengthBodyReader},
         http_body::HttpBody,
         Body,
     },
     headers::{self, HeaderName, HeaderValue, Headers, CONTENT_LENGTH, TRANSFER_ENCODING},
     method::Method,
-    request::{Parts, Request},
+    request::{Builder, Parts, Request},
     server::Config,
     uri::uri::Uri,
     version::Version,
@@ -51,10 +52,60 @@ pub fn read_request<R: Read + Send + 'static>(
     config: &Config,
 ) -> std::io::Result<Request<Body>> {
     let mut reader = BufReader::new(stream);
+    let (builder, headers, can_discard_body) = read_request_head(&mut reader)?;
+
+    // Read the body
+    let body = read_request_body(reader, &headers, can_discard_body, config)?;
+    build_request(builder, headers, body)
+}
+
+/// Holds the connection reader after the request body was fully read, so the next request can be read from it.
+pub(crate) type ReaderSlot<R> = Arc<Mutex<Option<BufReader<R>>>>;
+
+/// Reads a request from a connection that may be reused for other requests.
+///
+/// Once the request body is read or dropped the reader is returned to the `slot`, if is not returned
+/// the connection cannot be reused, this is the case of bodies without a known length.
+pub(crate) fn read_keep_alive_request<R: Read + Send + 'static>(
+    mut reader: BufReader<R>,
+    config: &Config,
+    slot: &ReaderSlot<R>,
+) -> std::io::Result<Request<Body>> {
+    let (builder, headers, can_discard_body) = read_request_head(&mut reader)?;
+
+    let body = match read_body_framing(&headers, config)? {
+        BodyFraming::None if can_discard_body => {
+            *slot.lock().expect("failed to lock reader slot") = Some(reader);
+            Body::empty()
+        }
+        BodyFraming::ContentLength(length) => {
+            let body_reader =
+                FixedLengthBodyReader::new(reader, Some(length), config.max_body_size);
+            Body::new(KeepAliveBody {
+                body: Some(KeepAliveReader::Fixed(body_reader)),
+                slot: slot.clone(),
+            })
+        }
+        BodyFraming::Chunked => {
+            let body_reader = ChunkedBodyReader::with_buf_reader(reader, config.max_body_size);
+            Body::new(KeepAliveBody {
+                body: Some(KeepAliveReader::Chunked(body_reader)),
+                slot: slot.clone(),
+            })
+        }
+        _ => read_request_body(reader, &headers, can_discard_body, config)?,
+    };
+
+    build_request(builder, headers, body)
+}
+
+fn read_request_head<R: Read>(
+    reader: &mut BufReader<R>,
+) -> std::io::Result<(Builder, Headers, bool)> {
     let mut buf = String::new();
 
     // Read first line
-    read_line(&mut buf, &mut reader)?;
+    read_line(&mut buf, &mut *reader)?;
 
     let mut builder = Request::builder();
 
@@ -67,11 +118,16 @@ pub fn read_request<R: Read + Send + 'static>(
 
     // Read headers
     buf.clear();
-    let headers = read_headers(&mut reader, &mut buf)?;
+    let headers = read_headers(reader, &mut buf)?;
 
-    // Read the body
-    let body = read_request_body(reader, &headers, can_discard_body, config)?;
+    Ok((builder, headers, can_discard_body))
+}
 
+fn build_request(
+    mut builder: Builder,
+    headers: Headers,
+    body: Body,
+) -> std::io::Result<Request<Body>> {
     // Set headers
     if let Some(req_headers) = builder.headers_mut() {
         req_headers.extend(headers);
@@ -84,45 +140,177 @@ pub fn read_request<R: Read + Send + 'static>(
     Ok(request)
 }
 
+/// A body reader that knows where the body ends.
+enum KeepAliveReader<R> {
+    Fixed(FixedLengthBodyReader<BufReader<R>>),
+    Chunked(ChunkedBodyReader<R>),
+}
+
+impl<R: Read> KeepAliveReader<R> {
+    fn read_next(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        match self {
+            KeepAliveReader::Fixed(body) => body.read_next(),
+            KeepAliveReader::Chunked(body) => body.read_next(),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self {
+            KeepAliveReader::Fixed(body) => body.size_hint(),
+            KeepAliveReader::Chunked(body) => body.size_hint(),
+        }
+    }
+
+    fn into_inner(self) -> BufReader<R> {
+        match self {
+            KeepAliveReader::Fixed(body) => body.into_inner(),
+            KeepAliveReader::Chunked(body) => body.into_inner(),
+        }
+    }
+}
+
+/// A request body that returns the connection reader once is done.
+struct KeepAliveBody<R: Read> {
+    body: Option<KeepAliveReader<R>>,
+    slot: ReaderSlot<R>,
+}
+
+impl<R: Read> HttpBody for KeepAliveBody<R> {
+    type Err = std::io::Error;
+    type Data = Vec<u8>;
+
+    fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+        match self.body.as_mut() {
+            Some(body) => body.read_next(),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.body.as_ref().and_then(|x| x.size_hint())
+    }
+}
+
+impl<R: Read> Drop for KeepAliveBody<R> {
+    fn drop(&mut self) {
+        let Some(mut body) = self.body.take() else {
+            return;
+        };
+
+        // Discard the unread body, the next request starts after it
+        loop {
+            match body.read_next() {
+                Ok(Some(_)) => {}
+                Ok(None) => break,
+                Err(_) => return,
+            }
+        }
+
+        if let Ok(mut slot) = self.slot.lock() {
+            *slot = Some(body.into_inner());
+        }
+    }
+}
+
+/// How the length of a message body is determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyFraming {
+    /// The body length is given by the `Content-Length` header.
+    ContentLength(usize),
+
+    /// The body uses `Transfer-Encoding: chunked`.
+    Chunked,
+
+    /// No framing headers, the body is read until the connection closes.
+    None,
+}
+
+impl BodyFraming {
+    /// Gets the body framing from the headers.
+    ///
+    /// Returns an `InvalidData` error if both `Content-Length` and `Transfer-Encoding` are present,
+    /// this is rejected to prevent request smuggling (RFC 7230 section 3.3.3).
+    pub(crate) fn from_headers(headers: &Headers) -> std::io::Result<Self> {
+        let content_length = headers.get(CONTENT_LENGTH);
+        let transfer_encoding = headers.get(TRANSFER_ENCODING);
+
+        match (content_length, transfer_encoding) {
+            (Some(_), Some(_)) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "request contains both `Content-Length` and `Transfer-Encoding` headers",
+            )),
+            (Some(length), None) => {
+                let length = length.as_str().trim().parse().map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid content length: `{length}`"),
+                    )
+                })?;
+
+                Ok(BodyFraming::ContentLength(length))
+            }
+            (None, Some(_)) => {
+                // Only `chunked` is supported, other encodings like `gzip, chunked` are rejected
+                let encodings = headers
+                    .get_all(TRANSFER_ENCODING)
+                    .map(|x| x.as_str().trim())
+                    .collect::<Vec<_>>();
+
+                match encodings.as_slice() {
+                    [encoding] if encoding.eq_ignore_ascii_case("chunked") => {
+                        Ok(BodyFraming::Chunked)
+                    }
+                    _ => Err(std::io::Error::new(
+                        std::io::ErrorKind::Unsupported,
+                        format!("Unknown transfer encoding: `{}`", encodings.join(", ")),
+                    )),
+                }
+            }
+            (None, None) => Ok(BodyFraming::None),
+        }
+    }
+}
+
+/// Gets the body framing, rejecting the request before reading the body if the `Content-Length` exceeds the max body size.
+fn read_body_framing(headers: &Headers, config: &Config) -> std::io::Result<BodyFraming> {
+    let framing = BodyFraming::from_headers(headers)?;
+
+    if let (BodyFraming::ContentLength(length), Some(max_body_size)) =
+        (framing, config.max_body_size)
+    {
+        if length > max_body_size {
+            return Err(body_limit_error(length, max_body_size));
+        }
+    }
+
+    Ok(framing)
+}
+
 fn read_request_body<R: Read + Send + 'static>(
     reader: BufReader<R>,
     headers: &Headers,
     can_discard_body: bool,
     config: &Config,
 ) -> std::io::Result<Body> {
-    let content_length = headers
-        .get(CONTENT_LENGTH)
-        .and_then(|x| x.as_str().parse().ok());
-
-    let transfer_encoding = headers.get(TRANSFER_ENCODING).map(|x| x.as_str());
-
-    if can_discard_body && transfer_encoding.is_none() && content_length.is_none() {
-        return Ok(Body::empty());
-    }
-
-    let body = if let Some(length) = content_length {
+    let body = match read_body_framing(headers, config)? {
+        BodyFraming::None if can_discard_body => Body::empty(),
         // Read body based on Content-Length
-        Body::new(FixedLengthBodyReader::new(
+        BodyFraming::ContentLength(length) => Body::new(FixedLengthBodyReader::new(
             reader,
             Some(length),
             config.max_body_size,
-        ))
-    } else if let Some(encoding) = transfer_encoding {
+        )),
         // Read body based on Chunked Transfer-Encoding
-        if encoding == "chunked" {
-            Body::new(ChunkedBodyReader::new(reader, config.max_body_size))
-        } else {
-            return Err(std::io::Error::other(format!(
-                "Unknown transfer encoding: `{encoding}`"
-            )));
-        }
-    } else {
+        BodyFraming::Chunked => Body::new(ChunkedBodyReader::with_buf_reader(
+            reader,
+            config.max_body_size,
+        )),
         // Read until the connection closes
-        Body::new(FixedLengthBodyReader::new(
+        BodyFraming::None => Body::new(FixedLengthBodyReader::new(
             reader,
             None,
             config.max_body_size,
-        ))
+        )),
     };
 
     Ok(body)
@@ -146,7 +334,7 @@ fn read_request_line(buf: &str) -> std::io::Result<(Method, Uri, Version)> {
 
     let url = parts
         .next()
-        .and_then(|s| crate::uri::url_encoding::decode(s).ok())
+        .and_then(decode_request_target)
         .and_then(|s| Uri::from_str(&s).ok())
         .ok_or_else(|| std::io::Error::other("Failed to parse request url"))?;
 
@@ -158,6 +346,17 @@ fn read_request_line(buf: &str) -> std::io::Result<(Method, Uri, Version)> {
     Ok((method, url, version))
 }
 
+/// Decodes the path of the request target, the query is kept encoded so the `&` and `=` in the values are not mistaken as separators.
+fn decode_request_target(target: &str) -> Option<String> {
+    let (path, rest) = match target.find(['?', '#']) {
+        Some(idx) => target.split_at(idx),
+        None => (target, ""),
+    };
+
+    let path = crate::uri::percent_decode(path).ok()?;
+    Some(format!("{path}{rest}"))
+}
+
 pub(crate) fn read_headers<R: Read>(
     reader: &mut BufReader<R>,
     buf: &mut String,
@@ -7,6 +7,7 @@ use crate::{
     body::{
         buf_body_reader::BufBodyReader, chunked_body::ReadChunkedBody, http_body::HttpBody, Body,
     },
+    error::BoxError,
     headers::{self, HeaderValue, Headers},
     response::Response,
     server::Config,
@@ -15,6 +16,65 @@ use crate::{
 };
 use datetime::DateTime;
 
+/// Encodes a body of unknown size using `Transfer-Encoding: chunked`,
+/// so the client knows where the response ends without closing the connection.
+///
+/// Bodies with a known size, a `Content-Length` or that already set a `Transfer-Encoding` are left as they are.
+pub fn apply_chunked_encoding(response: &mut Response<Body>) {
+    let status = response.status().as_u16();
+    let headers = response.headers();
+
+    if (100..200).contains(&status)
+        || status == StatusCode::NO_CONTENT.as_u16()
+        || status == StatusCode::NOT_MODIFIED.as_u16()
+        || headers.contains_key(headers::CONTENT_LENGTH)
+        || headers.contains_key(headers::TRANSFER_ENCODING)
+        || response.body().size_hint().is_some()
+    {
+        return;
+    }
+
+    response.headers_mut().insert(
+        headers::TRANSFER_ENCODING,
+        HeaderValue::from_static("chunked"),
+    );
+
+    let body = std::mem::take(response.body_mut());
+    *response.body_mut() = Body::new(EncodeChunkedBody(Some(body)));
+}
+
+/// Writes each chunk of the inner body with its size, and the last chunk when the inner body ends.
+struct EncodeChunkedBody(Option<Body>);
+
+impl HttpBody for EncodeChunkedBody {
+    type Err = BoxError;
+    type Data = Vec<u8>;
+
+    fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+        let Some(body) = self.0.as_mut() else {
+            return Ok(None);
+        };
+
+        loop {
+            match body.read_next()? {
+                // An empty chunk would be taken as the last chunk
+                Some(bytes) if bytes.is_empty() => continue,
+                Some(bytes) => {
+                    let mut buf = Vec::with_capacity(bytes.len() + 10);
+                    write!(buf, "{:X}\r\n", bytes.len())?;
+                    buf.extend_from_slice(&bytes);
+                    buf.extend_from_slice(b"\r\n");
+                    return Ok(Some(buf));
+                }
+                None => {
+                    self.0 = None;
+                    return Ok(Some(b"0\r\n\r\n".to_vec()));
+                }
+            }
+        }
+    }
+}
+
 pub fn write_response<W: Write>(
     response: Response<Body>,
     stream: &mut W,
@@ -29,7 +89,7 @@ pub fn write_response<W: Write>(
     write!(stream, "{version} {status} {reason_phrase}\r\n")?;
 
     // 2. Write headers
-    write_headers(headers, &body, stream, config)?;
+    write_headers(headers, Some(&body), stream, config)?;
 
     // 3. Write body
     if !discard_body {
@@ -48,9 +108,29 @@ pub fn write_response<W: Write>(
     Ok(())
 }
 
+/// Writes the successful response of a `CONNECT` request, after it the connection becomes a tunnel.
+///
+/// The response cannot have a body so the `Content-Length` and `Transfer-Encoding` headers are not sent (RFC 9110 section 9.3.6).
+pub(crate) fn write_tunnel_response<W: Write>(
+    response: Response<Body>,
+    stream: &mut W,
+    config: &Config,
+) -> std::io::Result<()> {
+    let version = response.version();
+    let (status, mut headers, ..) = response.into_parts();
+    let reason_phrase = status.reason_phrase().unwrap_or("");
+
+    headers.remove(headers::CONTENT_LENGTH);
+    headers.remove(headers::TRANSFER_ENCODING);
+
+    write!(stream, "{version} {status} {reason_phrase}\r\n")?;
+    write_headers(headers, None, stream, config)?;
+    stream.flush()
+}
+
 fn write_headers<W: Write>(
     mut headers: Headers,
-    body: &Body,
+    body: Option<&Body>,
     stream: &mut W,
     config: &Config,
 ) -> std::io::Result<()> {
@@ -61,7 +141,20 @@ fn write_headers<W: Write>(
         );
     }
 
-    if let Some(content_length) = body.size_hint() {
+    // A `Server` header set by the handler takes precedence
+    if let Some(server_header) = &config.server_header {
+        if !headers.contains_key(headers::SERVER) {
+            headers.insert(
+                headers::SERVER,
+                HeaderValue::from_string(server_header.clone()),
+            );
+        }
+    }
+
+    if headers.contains_key(headers::TRANSFER_ENCODING) {
+        // A message must not contain both `Content-Length` and `Transfer-Encoding`
+        headers.remove(headers::CONTENT_LENGTH);
+    } else if let Some(content_length) = body.and_then(|body| body.size_hint()) {
         // If the response provided a content-length we trust it
         if !headers.contains_key(headers::CONTENT_LENGTH) {
             headers.insert(headers::CONTENT_LENGTH, HeaderValue::from(content_length));
@@ -124,6 +217,7 @@ where
         Some(encoding) => {
             if encoding.as_str() == "chunked" {
                 let chunked_body = ReadChunkedBody::new(buf_reader);
+                response.extensions_mut().insert(chunked_body.trailers());
                 Body::new(chunked_body)
             } else {
                 return Err(std::io::Error::other(format!(
@@ -94,6 +94,12 @@ impl Upgrade {
         Upgrade(conn)
     }
 
+    /// Constructs an upgrade over the given connection, only meant for testing.
+    #[doc(hidden)]
+    pub fn from_connection(conn: Connection) -> Self {
+        Upgrade(conn)
+    }
+
     pub fn try_clone(&self) -> Option<Self> {
         self.0.try_clone().map(Upgrade)
     }
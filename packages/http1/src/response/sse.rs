@@ -1,6 +1,7 @@
 use std::{
     fmt::Display,
-    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+    sync::mpsc::{channel, sync_channel, Receiver, Sender, SyncSender, TryRecvError},
+    time::Duration,
 };
 
 use crate::body::{http_body::HttpBody, Body};
@@ -14,7 +15,18 @@ impl SseStream {
     pub fn new() -> (SseBroadcast, Self) {
         let (sender, receiver) = channel();
 
-        let sse_broadcast = SseBroadcast(sender);
+        let sse_broadcast = SseBroadcast(EventSender::Unbounded(sender));
+        (sse_broadcast, SseStream(Some(receiver)))
+    }
+
+    /// Constructs a stream that buffers at most `capacity` events.
+    ///
+    /// When the buffer is full `SseBroadcast::send` blocks until the client reads an event,
+    /// so a fast producer cannot outpace a slow client.
+    pub fn bounded(capacity: usize) -> (SseBroadcast, Self) {
+        let (sender, receiver) = sync_channel(capacity);
+
+        let sse_broadcast = SseBroadcast(EventSender::Bounded(sender));
         (sse_broadcast, SseStream(Some(receiver)))
     }
 }
@@ -71,11 +83,21 @@ impl HttpBody for SseStream {
 }
 
 #[derive(Clone)]
-pub struct SseBroadcast(Sender<SseEvent>);
+enum EventSender {
+    Unbounded(Sender<SseEvent>),
+    Bounded(SyncSender<SseEvent>),
+}
+
+#[derive(Clone)]
+pub struct SseBroadcast(EventSender);
 
 impl SseBroadcast {
+    /// Sends an event to the stream, if the stream is bounded and full this blocks until there is space.
     pub fn send(&self, event: SseEvent) -> Result<(), SseSendError> {
-        self.0.send(event).map_err(|_| SseSendError)
+        match &self.0 {
+            EventSender::Unbounded(sender) => sender.send(event).map_err(|_| SseSendError),
+            EventSender::Bounded(sender) => sender.send(event).map_err(|_| SseSendError),
+        }
     }
 }
 
@@ -84,7 +106,7 @@ pub struct SseEvent {
     id: Option<String>,
     event: Option<String>,
     data: String,
-    retry: Option<usize>,
+    retry: Option<Duration>,
 }
 
 impl SseEvent {
@@ -118,7 +140,7 @@ impl SseEvent {
         self.data.as_str()
     }
 
-    pub fn retry(&self) -> Option<usize> {
+    pub fn retry(&self) -> Option<Duration> {
         self.retry
     }
 }
@@ -133,8 +155,9 @@ impl Display for SseEvent {
             writeln!(f, "event: {event}")?;
         }
 
+        // The reconnection time is sent in milliseconds
         if let Some(retry) = &self.retry {
-            writeln!(f, "retry: {retry}")?;
+            writeln!(f, "retry: {}", retry.as_millis())?;
         }
 
         for line in self.data.lines() {
@@ -150,7 +173,7 @@ impl Display for SseEvent {
 struct Parts {
     id: Option<String>,
     event: Option<String>,
-    retry: Option<usize>,
+    retry: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -213,7 +236,8 @@ impl Builder {
         })
     }
 
-    pub fn retry(self, retry: usize) -> Self {
+    /// Sets the time the client should wait before reconnecting.
+    pub fn retry(self, retry: Duration) -> Self {
         self.update(|parts| {
             parts.retry = Some(retry);
             Ok(())
@@ -242,3 +266,62 @@ impl Default for Builder {
 fn has_line_break(s: &str) -> bool {
     s.bytes().any(|c| c == b'\n')
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::body::http_body::HttpBody;
+
+    use super::{SseEvent, SseStream};
+
+    #[test]
+    fn should_write_event_with_id_type_and_retry() {
+        let event = SseEvent::builder()
+            .id("42")
+            .event("message")
+            .retry(Duration::from_secs(3))
+            .data("first line\nsecond line")
+            .unwrap();
+
+        assert_eq!(
+            event.to_string(),
+            "id: 42\nevent: message\nretry: 3000\ndata: first line\ndata: second line\n\n"
+        );
+    }
+
+    #[test]
+    fn should_block_producer_when_bounded_stream_is_full() {
+        let (broadcast, mut stream) = SseStream::bounded(2);
+        let sent = Arc::new(AtomicUsize::new(0));
+
+        let producer = {
+            let sent = sent.clone();
+            std::thread::spawn(move || {
+                for i in 0..5 {
+                    broadcast.send(SseEvent::with_data(i)).unwrap();
+                    sent.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        // The consumer is stalled, only the buffered events can be sent
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+        assert!(!producer.is_finished());
+
+        for i in 0..5 {
+            let event = stream.read_next().unwrap().unwrap();
+            assert_eq!(event, format!("data: {i}\n\n").into_bytes());
+        }
+
+        producer.join().unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 5);
+    }
+}
@@ -1,12 +1,16 @@
 use std::{
-    net::{SocketAddr, TcpListener, ToSocketAddrs},
-    sync::{atomic::AtomicBool, Arc},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize},
+        Arc, Condvar, Mutex, OnceLock,
+    },
+    time::Duration,
 };
 
 use crate::{
     common::thread_pool::ThreadPool,
     handler::RequestHandler,
-    protocol::{connection::Connection, h1::handle_incoming},
+    protocol::{connection::Connection, h1::handle_connection},
 };
 
 /// Server handle to shutdown the server.
@@ -14,13 +18,34 @@ use crate::{
 pub struct ServerHandle {
     is_closed: Arc<AtomicBool>,
     is_ready: Arc<AtomicBool>,
+    local_addr: Arc<OnceLock<SocketAddr>>,
 }
 
 impl ServerHandle {
     /// Signal the server to stop accepting more connections.
+    ///
+    /// The server will return after all the in-flight requests are completed.
     pub fn shutdown(self) {
         self.is_closed
             .store(true, std::sync::atomic::Ordering::Relaxed);
+
+        // Wake up the server if is waiting for a connection
+        if let Some(addr) = self.local_addr.get() {
+            let mut addr = *addr;
+
+            if addr.ip().is_unspecified() {
+                let ip = match addr.ip() {
+                    IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                };
+
+                addr.set_ip(ip);
+            }
+
+            if let Err(err) = TcpStream::connect(addr) {
+                log::debug!("Failed to wake up server: {err}");
+            }
+        }
     }
 
     /// Whether the server is stopped.
@@ -41,6 +66,41 @@ impl Drop for Guard {
     }
 }
 
+/// Keeps track of the connections being handled.
+#[derive(Default)]
+struct InFlight {
+    count: Mutex<usize>,
+    drained: Condvar,
+}
+
+impl InFlight {
+    fn enter(self: &Arc<Self>) -> InFlightGuard {
+        *self.count.lock().expect("failed to lock in-flight count") += 1;
+        InFlightGuard(self.clone())
+    }
+
+    /// Blocks until all the connections are completed.
+    fn wait(&self) {
+        let count = self.count.lock().expect("failed to lock in-flight count");
+        let _count = self
+            .drained
+            .wait_while(count, |count| *count > 0)
+            .expect("failed to wait for in-flight connections");
+    }
+}
+
+struct InFlightGuard(Arc<InFlight>);
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock().expect("failed to lock in-flight count");
+        *count -= 1;
+
+        if *count == 0 {
+            self.0.drained.notify_all();
+        }
+    }
+}
+
 /// Server configuration.
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -55,6 +115,24 @@ pub struct Config {
 
     /// Whether if include the server config to the request extensions.
     pub include_server_info: bool,
+
+    /// Number of worker threads of the pool that handles the connections, defaults to the available parallelism.
+    pub worker_threads: usize,
+
+    /// Max number of requests handled in a single connection, `None` for no limit.
+    pub max_requests_per_connection: Option<usize>,
+
+    /// Max time a keep-alive connection waits for the next request before being closed.
+    pub keep_alive_timeout: Option<Duration>,
+
+    /// Max time to wait for data when reading from a connection.
+    pub read_timeout: Option<Duration>,
+
+    /// Max time to wait when writing to a connection.
+    pub write_timeout: Option<Duration>,
+
+    /// Value of the `Server` header included in each response, `None` to not include it.
+    pub server_header: Option<String>,
 }
 
 impl Default for Config {
@@ -64,10 +142,24 @@ impl Default for Config {
             max_body_size: Some(crate::constants::DEFAULT_MAX_BODY_SIZE),
             include_conn_info: false,
             include_server_info: true,
+            worker_threads: default_worker_threads(),
+            max_requests_per_connection: Some(
+                crate::constants::DEFAULT_MAX_REQUESTS_PER_CONNECTION,
+            ),
+            keep_alive_timeout: Some(crate::constants::DEFAULT_KEEP_ALIVE_TIMEOUT),
+            read_timeout: None,
+            write_timeout: None,
+            server_header: Some(crate::constants::DEFAULT_SERVER_HEADER.to_owned()),
         }
     }
 }
 
+fn default_worker_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|x| x.get())
+        .unwrap_or(4)
+}
+
 type OnReady = Box<dyn FnOnce(&SocketAddr) + Send>;
 
 /// The server implementation.
@@ -80,9 +172,38 @@ pub struct Server<E = ThreadPool> {
 
 impl Server<()> {
     /// Constructs a new server.
+    ///
+    /// Connections are handled by a pool with a worker thread for each available core.
     pub fn new() -> Server<ThreadPool> {
-        let executor = ThreadPool::new().expect("failed to initialize thread pool executor");
-        Self::with_executor(executor)
+        Self::with_config(Config::default())
+    }
+
+    /// Constructs a new server that handle the connections using the given number of worker threads.
+    ///
+    /// The pool never grows beyond that number, connections wait until a worker is available.
+    pub fn with_worker_threads(worker_threads: usize) -> Server<ThreadPool> {
+        Self::with_config(Config {
+            worker_threads,
+            ..Default::default()
+        })
+    }
+
+    /// Constructs a new server with the given config, the pool is sized using `Config::worker_threads`.
+    pub fn with_config(config: Config) -> Server<ThreadPool> {
+        assert!(
+            config.worker_threads > 0,
+            "worker threads must be greater than 0"
+        );
+
+        let executor = ThreadPool::builder()
+            .num_workers(config.worker_threads)
+            .max_workers_count(Some(config.worker_threads))
+            .build()
+            .expect("failed to initialize thread pool executor");
+
+        let mut server = Self::with_executor(executor);
+        server.config = config;
+        server
     }
 
     /// Constructs a new server with the given executor.
@@ -91,6 +212,7 @@ impl Server<()> {
         let server_handle = ServerHandle {
             is_closed: Arc::new(AtomicBool::new(false)),
             is_ready: Arc::new(AtomicBool::new(false)),
+            local_addr: Arc::new(OnceLock::new()),
         };
 
         Server {
@@ -131,6 +253,45 @@ impl<E> Server<E> {
         self
     }
 
+    /// The max number of requests a single connection can send, after that the connection is closed.
+    ///
+    /// Use `Some(1)` to disable keep-alive connections.
+    pub fn max_requests_per_connection(mut self, max_requests: Option<usize>) -> Self {
+        if let Some(max_requests) = max_requests {
+            assert!(max_requests > 0);
+        }
+
+        self.config.max_requests_per_connection = max_requests;
+        self
+    }
+
+    /// The max time an idle keep-alive connection waits for the next request.
+    ///
+    /// Idle connections are closed earlier if other connections are waiting for a worker.
+    pub fn keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// The max time to wait for a client to send data, if a request is not received in time
+    /// the server responds with `408 Request Timeout`.
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.read_timeout = timeout;
+        self
+    }
+
+    /// The max time to wait for a client to receive data.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.write_timeout = timeout;
+        self
+    }
+
+    /// The value of the `Server` header included in each response, `None` to not include it.
+    pub fn server_header(mut self, server_header: Option<String>) -> Self {
+        self.config.server_header = server_header;
+        self
+    }
+
     /// Adds a callback that will be executed right after the server starts.
     pub fn on_ready<F>(mut self, f: F) -> Self
     where
@@ -151,6 +312,8 @@ where
     E: Executor,
 {
     /// Starts listening on the given address and handle incoming request with the given request handler.
+    ///
+    /// This blocks until the server is shutdown using the [`ServerHandle`].
     pub fn listen<H, A: ToSocketAddrs>(self, addr: A, handler: H) -> std::io::Result<()>
     where
         H: RequestHandler + Send + Sync + 'static,
@@ -164,6 +327,7 @@ where
 
         let listener = TcpListener::bind(addr)?;
         let local_addr = listener.local_addr()?;
+        let _ = server_handle.local_addr.set(local_addr);
 
         if let Some(on_ready) = on_ready.take() {
             on_ready(&local_addr)
@@ -176,18 +340,45 @@ where
         let handler = Arc::new(handler);
         let _guard = Guard(server_handle.is_ready.clone());
 
+        let in_flight = Arc::new(InFlight::default());
+
+        // Connections accepted but waiting for the executor to handle them
+        let pending = Arc::new(AtomicUsize::new(0));
+
         loop {
             if server_handle.is_closed() {
-                log::debug!("Closing server...");
                 break;
             }
 
             let (stream, _) = listener.accept()?;
+
+            // The server may be closed while waiting for the connection
+            if server_handle.is_closed() {
+                break;
+            }
+
             let config = config.clone();
             let handler = handler.clone();
+            let is_closed = server_handle.is_closed.clone();
+            let in_flight_guard = in_flight.enter();
+            let pending = pending.clone();
+            pending.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
             let result = executor.execute(move || {
-                match handle_incoming(&handler, &config, Connection::Tcp(stream)) {
+                let _in_flight_guard = in_flight_guard;
+                pending.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+
+                let conn = Connection::Tcp(stream);
+
+                if let Err(err) = conn
+                    .set_read_timeout(config.read_timeout)
+                    .and_then(|_| conn.set_write_timeout(config.write_timeout))
+                {
+                    log::error!("Failed to set connection timeouts: {err}");
+                    return;
+                }
+
+                match handle_connection(&handler, &config, conn, &is_closed, &pending) {
                     Ok(..) => {}
                     Err(err) => log::error!("{err}"),
                 }
@@ -198,8 +389,70 @@ where
             }
         }
 
+        log::debug!("Closing server...");
+
+        // Stop accepting connections and wait for the pending ones
+        drop(listener);
+        in_flight.wait();
+
         Ok(())
     }
+
+    /// Starts the server on an ephemeral port of `127.0.0.1`, runs the closure with the bound address
+    /// and then shutdowns the server.
+    ///
+    /// This is useful for integration tests, the server is also shutdown if the closure panics.
+    pub fn run_scoped<H, F, R>(mut self, handler: H, f: F) -> std::io::Result<R>
+    where
+        E: Send,
+        H: RequestHandler + Send + Sync + 'static,
+        F: FnOnce(SocketAddr) -> R,
+    {
+        struct ShutdownGuard(ServerHandle);
+        impl Drop for ShutdownGuard {
+            fn drop(&mut self) {
+                self.0.clone().shutdown();
+            }
+        }
+
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+        let on_ready = self.on_ready.take();
+        let server = self.on_ready(move |addr| {
+            if let Some(on_ready) = on_ready {
+                on_ready(addr);
+            }
+
+            let _ = addr_tx.send(*addr);
+        });
+
+        let handle = server.handle();
+
+        std::thread::scope(|scope| {
+            let server_thread = scope.spawn(move || server.listen("127.0.0.1:0", handler));
+
+            let addr = match addr_rx.recv() {
+                Ok(addr) => addr,
+                Err(_) => {
+                    // The server failed before being ready
+                    return match server_thread.join() {
+                        Ok(Err(err)) => Err(err),
+                        _ => Err(std::io::Error::other("failed to start the server")),
+                    };
+                }
+            };
+
+            let result = {
+                let _guard = ShutdownGuard(handle);
+                f(addr)
+            };
+
+            match server_thread.join() {
+                Ok(Ok(())) => Ok(result),
+                Ok(Err(err)) => Err(err),
+                Err(_) => Err(std::io::Error::other("server thread panicked")),
+            }
+        })
+    }
 }
 
 /// Provides a mechanism for execute tasks.
@@ -250,3 +503,249 @@ impl Executor for SpawnExecutor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::mpsc::channel,
+        time::{Duration, Instant},
+    };
+
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        body::http_body::HttpBody, client::Client, protocol::upgrade::PendingUpgrade,
+        response::Response, status::StatusCode,
+    };
+
+    use super::{Config, Server};
+
+    #[test]
+    fn should_shutdown_after_in_flight_requests_complete() {
+        let server = Server::new();
+        let handle = server.handle();
+        let (addr_tx, addr_rx) = channel();
+        let (started_tx, started_rx) = channel();
+
+        let server_thread = std::thread::spawn(move || {
+            server
+                .on_ready(move |addr| addr_tx.send(*addr).unwrap())
+                .listen("127.0.0.1:0", move |_| {
+                    started_tx.send(()).unwrap();
+                    std::thread::sleep(Duration::from_millis(100));
+                    Response::new(StatusCode::OK, "Goodbye".into())
+                })
+        });
+
+        let addr = addr_rx.recv().unwrap();
+        let client_thread = std::thread::spawn(move || {
+            Client::new()
+                .get(format!("http://{addr}"))
+                .send(())
+                .unwrap()
+        });
+
+        // Shutdown while the request is being handled
+        started_rx.recv().unwrap();
+        handle.clone().shutdown();
+
+        let result = server_thread.join().unwrap();
+        assert!(result.is_ok());
+        assert!(handle.is_closed());
+        assert!(!handle.is_ready());
+
+        let res = client_thread.join().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Goodbye");
+
+        // No longer accepting connections
+        assert!(Client::new()
+            .get(format!("http://{addr}"))
+            .send(())
+            .is_err());
+    }
+
+    #[test]
+    fn should_size_thread_pool_from_config() {
+        let server = Server::with_config(Config {
+            worker_threads: 3,
+            max_body_size: None,
+            ..Default::default()
+        });
+
+        assert_eq!(server.executor.worker_count(), 3);
+        assert_eq!(server.executor.max_workers_count(), Some(3));
+        assert_eq!(server.config.max_body_size, None);
+    }
+
+    #[test]
+    fn should_queue_connections_beyond_worker_threads() {
+        let server = Server::with_worker_threads(2);
+        let handle = server.handle();
+        let (addr_tx, addr_rx) = channel();
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+
+        {
+            let active = active.clone();
+            let max_active = max_active.clone();
+
+            std::thread::spawn(move || {
+                server
+                    .on_ready(move |addr| addr_tx.send(*addr).unwrap())
+                    .listen("127.0.0.1:0", move |_| {
+                        let count = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_active.fetch_max(count, Ordering::SeqCst);
+
+                        std::thread::sleep(Duration::from_millis(50));
+                        active.fetch_sub(1, Ordering::SeqCst);
+
+                        Response::new(StatusCode::OK, "Ok".into())
+                    })
+                    .unwrap();
+            });
+        }
+
+        let addr = addr_rx.recv().unwrap();
+        let clients = (0..6)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    Client::new()
+                        .get(format!("http://{addr}"))
+                        .send(())
+                        .unwrap()
+                        .status()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for client in clients {
+            assert_eq!(client.join().unwrap(), StatusCode::OK);
+        }
+
+        assert!(max_active.load(Ordering::SeqCst) <= 2);
+        handle.shutdown();
+    }
+
+    #[test]
+    fn should_close_idle_connection_when_workers_are_busy() {
+        let server = Server::with_worker_threads(1);
+        let handle = server.handle();
+        let (addr_tx, addr_rx) = channel();
+
+        std::thread::spawn(move || {
+            server
+                .on_ready(move |addr| addr_tx.send(*addr).unwrap())
+                .listen("127.0.0.1:0", |_| {
+                    Response::new(StatusCode::OK, "Ok".into())
+                })
+                .unwrap();
+        });
+
+        let addr = addr_rx.recv().unwrap();
+
+        // Keep-alive connection that stays idle after the first response
+        let mut idle = TcpStream::connect(addr).unwrap();
+        idle.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+
+        let mut response = Vec::new();
+        while !response.ends_with(b"Ok") {
+            let mut buf = [0; 256];
+            let n = idle.read(&mut buf).unwrap();
+            assert!(n > 0, "connection closed before the response");
+            response.extend_from_slice(&buf[..n]);
+        }
+
+        // The only worker is released instead of waiting for the keep-alive timeout
+        let start = Instant::now();
+        let res = Client::new()
+            .get(format!("http://{addr}"))
+            .send(())
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        handle.shutdown();
+    }
+
+    #[test]
+    fn should_clear_timeouts_of_upgraded_connection() {
+        let handler = |req: crate::request::Request<crate::body::Body>| {
+            let pending = req.extensions().get::<PendingUpgrade>().cloned().unwrap();
+
+            std::thread::spawn(move || {
+                let mut upgrade = pending.wait().unwrap();
+                let mut buf = [0; 4];
+                upgrade.read_exact(&mut buf).unwrap();
+                upgrade.write_all(&buf).unwrap();
+            });
+
+            Response::new(StatusCode::OK, crate::body::Body::empty())
+        };
+
+        let echo = Server::new()
+            .read_timeout(Some(Duration::from_millis(100)))
+            .run_scoped(handler, |addr| {
+                let mut stream = TcpStream::connect(addr).unwrap();
+                stream
+                    .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n")
+                    .unwrap();
+
+                let mut response = Vec::new();
+                while !response.ends_with(b"\r\n\r\n") {
+                    let mut byte = [0; 1];
+                    stream.read_exact(&mut byte).unwrap();
+                    response.push(byte[0]);
+                }
+
+                // Idle for longer than the read timeout
+                std::thread::sleep(Duration::from_millis(300));
+
+                stream.write_all(b"ping").unwrap();
+                let mut buf = [0; 4];
+                stream.read_exact(&mut buf).unwrap();
+                buf
+            })
+            .unwrap();
+
+        assert_eq!(&echo, b"ping");
+    }
+
+    #[test]
+    fn should_run_scoped_server() {
+        let (status, body, addr) = Server::new()
+            .run_scoped(
+                |req: crate::request::Request<crate::body::Body>| {
+                    let path = req.uri().path_and_query().path().to_owned();
+                    Response::new(StatusCode::OK, format!("Hello from {path}").into())
+                },
+                |addr| {
+                    let res = Client::new()
+                        .get(format!("http://{addr}/scoped"))
+                        .send(())
+                        .unwrap();
+
+                    let status = res.status();
+                    let body = res.into_body().read_all_bytes().unwrap();
+                    (status, body, addr)
+                },
+            )
+            .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"Hello from /scoped");
+
+        // The server was shutdown after the closure returned
+        assert!(Client::new()
+            .get(format!("http://{addr}"))
+            .send(())
+            .is_err());
+    }
+}
@@ -5,3 +5,5 @@ pub mod path_query;
 pub mod scheme;
 pub mod uri;
 pub mod url_encoding;
+
+pub use url_encoding::{percent_decode, percent_encode};
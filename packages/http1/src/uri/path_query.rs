@@ -1,11 +1,12 @@
 use std::{
+    borrow::Cow,
     fmt::Display,
     str::{FromStr, Split},
 };
 
 use orderedmap::OrderedMap;
 
-use super::uri::InvalidUri;
+use super::{uri::InvalidUri, url_encoding};
 
 /// Represents the path and query from an URI.
 ///
@@ -49,12 +50,17 @@ impl PathAndQuery {
         self.query.as_deref()
     }
 
+    /// Returns the query as it was received, without decoding.
+    pub fn query_raw(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
     /// Returns the fragment if any or `None`.
     pub fn fragment(&self) -> Option<&str> {
         self.fragment.as_deref()
     }
 
-    /// Returns an iterator over the query values.
+    /// Returns an iterator over the query values, the keys and values are not decoded.
     pub fn query_values(&self) -> QueryValues {
         match &self.query {
             Some(s) => QueryValues::Values { iter: s.split("&") },
@@ -62,27 +68,21 @@ impl PathAndQuery {
         }
     }
 
-    /// Create a map over the query values.
+    /// Returns an iterator over the decoded query key-value pairs, a `+` is decoded as a space.
+    ///
+    /// A key without `=` has an empty value, the keys and values that are not valid percent-encoded values are kept as they are.
+    pub fn query_pairs(&self) -> QueryPairs<'_> {
+        QueryPairs::new(self.query.as_deref().unwrap_or_default())
+    }
+
+    /// Create a map over the decoded query values, a `+` is decoded as a space.
+    ///
+    /// Repeated keys are collected into a list, the keys ending with `[]` like `tag[]=a&tag[]=b` are collected under `tag`.
     pub fn query_map(&self) -> QueryMap {
-        let mut map = OrderedMap::<String, QueryValue>::new();
-
-        for (key, value) in self.query_values() {
-            if map.contains_key(key) {
-                let entry = map.get_mut(key).unwrap();
-                match entry {
-                    QueryValue::One(s) => {
-                        let cur = std::mem::take(s);
-                        let list = vec![cur, value.to_owned()];
-                        *entry = QueryValue::List(list);
-                    }
-                    QueryValue::List(list) => list.push(value.to_owned()),
-                }
-            } else {
-                map.insert(key.to_owned(), QueryValue::One(value.to_string()));
-            }
+        match &self.query {
+            Some(query) => QueryMap::from_query_str(query),
+            None => QueryMap::new(OrderedMap::new()),
         }
-
-        QueryMap(map)
     }
 
     /// An iterator over the segments of the path.
@@ -147,6 +147,37 @@ impl<'a> Iterator for QueryValues<'a> {
     }
 }
 
+/// An iterator over the decoded key-value pairs of a query.
+pub struct QueryPairs<'a>(Split<'a, char>);
+
+impl<'a> QueryPairs<'a> {
+    /// Constructs an iterator over the pairs of the given query string.
+    pub fn new(query: &'a str) -> Self {
+        QueryPairs(query.split('&'))
+    }
+}
+
+impl<'a> Iterator for QueryPairs<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let raw = self.0.find(|x| !x.is_empty())?;
+        let (key, value) = raw.split_once('=').unwrap_or((raw, ""));
+        Some((decode_query_component(key), decode_query_component(value)))
+    }
+}
+
+fn decode_query_component(s: &str) -> Cow<'_, str> {
+    if !s.contains(['%', '+']) {
+        return Cow::Borrowed(s);
+    }
+
+    match url_encoding::decode(s) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(_) => Cow::Borrowed(s),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum QueryValue {
     One(String),
@@ -184,35 +215,51 @@ impl Display for QueryMap {
 }
 
 impl QueryMap {
+    /// Constructs a map from the given query string, the keys and values are decoded
+    /// and kept as they are if are not valid percent-encoded values.
     pub fn from_query_str(value: &str) -> Self {
-        let query_values = QueryValues::Values {
-            iter: value.split("&"),
-        };
-        let mut map = OrderedMap::<String, QueryValue>::new();
-
-        for (key, value) in query_values {
-            if map.contains_key(key) {
-                let entry = map.get_mut(key).unwrap();
-                match entry {
-                    QueryValue::One(s) => {
-                        let cur = std::mem::take(s);
-                        let list = vec![cur, value.to_owned()];
-                        *entry = QueryValue::List(list);
-                    }
-                    QueryValue::List(list) => list.push(value.to_owned()),
-                }
-            } else {
-                map.insert(key.to_owned(), QueryValue::One(value.to_string()));
-            }
+        let mut map = QueryMap::new(OrderedMap::new());
+
+        for (key, value) in QueryPairs::new(value) {
+            map.append(key, value);
         }
 
-        QueryMap(map)
+        map
     }
 
     pub fn new(map: OrderedMap<String, QueryValue>) -> Self {
         QueryMap(map)
     }
 
+    /// Adds a value to the given key, if the key already exists the values are collected into a list.
+    ///
+    /// A key ending with `[]` is stored without the brackets and its values are always a list.
+    pub fn append(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let mut key = key.into();
+        let value = value.into();
+        let is_list = key.ends_with("[]");
+
+        if is_list {
+            key.truncate(key.len() - 2);
+        }
+
+        match self.0.get_mut(&key) {
+            Some(entry) => match entry {
+                QueryValue::One(cur) => {
+                    let cur = std::mem::take(cur);
+                    *entry = QueryValue::List(vec![cur, value]);
+                }
+                QueryValue::List(list) => list.push(value),
+            },
+            None if is_list => {
+                self.0.insert(key, QueryValue::List(vec![value]));
+            }
+            None => {
+                self.0.insert(key, QueryValue::One(value));
+            }
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -356,6 +403,50 @@ mod tests {
         assert_eq!(query_map.get("sort"), Some("email"));
     }
 
+    #[test]
+    fn should_get_query_pairs_with_empty_values() {
+        let pq = PathAndQuery::from_str("/items?a=1&b=&c").unwrap();
+
+        let pairs = pq.query_pairs().collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".into(), "1".into()),
+                ("b".into(), "".into()),
+                ("c".into(), "".into())
+            ]
+        );
+        assert_eq!(pq.query_raw(), Some("a=1&b=&c"));
+
+        let query_map = pq.query_map();
+        assert_eq!(query_map.get("c"), Some(""));
+    }
+
+    #[test]
+    fn should_decode_query_pairs_keys_and_values() {
+        let pq = PathAndQuery::from_str("/items?first%20name=Ai+Hoshino&tag%5B%5D=a%26b&bad%ZZ=1")
+            .unwrap();
+
+        let pairs = pq.query_pairs().collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                ("first name".into(), "Ai Hoshino".into()),
+                ("tag[]".into(), "a&b".into()),
+                ("bad%ZZ".into(), "1".into())
+            ]
+        );
+
+        assert_eq!(
+            pq.query_raw(),
+            Some("first%20name=Ai+Hoshino&tag%5B%5D=a%26b&bad%ZZ=1")
+        );
+        assert_eq!(
+            pq.query_map().get_all("tag").collect::<Vec<_>>(),
+            vec!["a&b"]
+        );
+    }
+
     #[test]
     fn should_get_path_segments() {
         let p = PathAndQuery::from_str("/one/two/three").unwrap();
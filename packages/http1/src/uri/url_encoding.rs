@@ -2,6 +2,11 @@
 pub trait Alphabet {
     /// Whether if this alphabet contains the given value.
     fn contains(&self, value: u8) -> bool;
+
+    /// Whether if spaces are encoded as `+` instead of `%20`.
+    fn space_as_plus(&self) -> bool {
+        false
+    }
 }
 
 /// Percent encode alphabet.
@@ -12,6 +17,53 @@ impl Alphabet for UrlASCII {
     }
 }
 
+/// Alphabet for a path segment, a `/` is encoded.
+pub struct PathSegmentASCII;
+impl Alphabet for PathSegmentASCII {
+    fn contains(&self, value: u8) -> bool {
+        UrlASCII.contains(value)
+            || matches!(
+                value,
+                b'!' | b'$'
+                    | b'&'
+                    | b'\''
+                    | b'('
+                    | b')'
+                    | b'*'
+                    | b'+'
+                    | b','
+                    | b';'
+                    | b'='
+                    | b':'
+                    | b'@'
+            )
+    }
+}
+
+/// Alphabet for a query key or value, the `&`, `=`, `+` and `#` are encoded.
+pub struct QueryASCII;
+impl Alphabet for QueryASCII {
+    fn contains(&self, value: u8) -> bool {
+        UrlASCII.contains(value)
+            || matches!(
+                value,
+                b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b',' | b';' | b':' | b'@' | b'/' | b'?'
+            )
+    }
+}
+
+/// Alphabet for `application/x-www-form-urlencoded` keys and values, spaces are encoded as `+`.
+pub struct FormASCII;
+impl Alphabet for FormASCII {
+    fn contains(&self, value: u8) -> bool {
+        matches!(value, b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*')
+    }
+
+    fn space_as_plus(&self) -> bool {
+        true
+    }
+}
+
 /// Encodes a URI component by using the `UrlComponentEncode` alphabet for percent-encoding.
 /// This function wraps `encode_uri_component_with` with the default alphabet.
 ///
@@ -46,6 +98,9 @@ pub fn encode_with<S: AsRef<str>>(input: S, alphabet: impl Alphabet) -> String {
             _ if alphabet.contains(byte) => {
                 encoded.push(byte as char);
             }
+            b' ' if alphabet.space_as_plus() => {
+                encoded.push('+');
+            }
             // Otherwise, percent-encode the byte and append the result.
             _ => {
                 encoded.push_str(&format!("%{:02X}", byte));
@@ -61,10 +116,39 @@ pub fn encode_with<S: AsRef<str>>(input: S, alphabet: impl Alphabet) -> String {
 /// An error type used when URI component decoding fails.
 pub struct InvalidUriComponent;
 
+/// Percent-encodes the given value, the bytes not contained in the `alphabet` are encoded.
+///
+/// # Parameters
+/// - `input`: The string to be encoded.
+/// - `alphabet`: The set of allowed characters, like [`PathSegmentASCII`], [`QueryASCII`] or [`FormASCII`].
+///
+/// # Returns
+/// A `String` representing the encoded value.
+pub fn percent_encode(input: &str, alphabet: impl Alphabet) -> String {
+    encode_with(input, alphabet)
+}
+
+/// Decodes a percent-encoded value, a `+` is kept as it is.
+///
+/// The multi-byte UTF-8 sequences are decoded from its encoded bytes, like `%C3%A9` to `é`.
+///
+/// # Parameters
+/// - `input`: The percent-encoded string to decode.
+///
+/// # Returns
+/// A `Result<String, InvalidUriComponent>` where:
+/// - `Ok` contains the decoded string.
+/// - `Err` contains the `InvalidUriComponent` error if a `%` is not followed by two hex digits
+///   or the decoded bytes are not valid UTF-8.
+pub fn percent_decode(input: &str) -> Result<String, InvalidUriComponent> {
+    decode_bytes(input, false)
+}
+
 /// Decodes a URI component that may contain percent-encoded characters back into a plain string.
 ///
 /// The function will look for `%` signs and attempt to decode the following two characters as hex digits
-/// representing a byte value. If the encoding is invalid, the function returns an error.
+/// representing a byte value, a `+` is decoded as a space as in form values.
+/// If the encoding is invalid, the function returns an error.
 ///
 /// # Parameters
 /// - `input`: The percent-encoded string to decode.
@@ -74,38 +158,48 @@ pub struct InvalidUriComponent;
 /// - `Ok` contains the decoded string.
 /// - `Err` contains the `InvalidUriComponent` error if the input is not a valid URI component.
 pub fn decode<S: AsRef<str>>(input: S) -> Result<String, InvalidUriComponent> {
-    // Vector to hold the decoded bytes.
-    let mut decoded = Vec::new();
-    let mut chars = input.as_ref().chars();
-
-    // Iterate over the characters of the input string.
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            // If the character is '%', try to decode the next two characters as a hex value.
-            let hex1 = chars.next().ok_or(InvalidUriComponent)?;
-            let hex2 = chars.next().ok_or(InvalidUriComponent)?;
-            let hex = format!("{}{}", hex1, hex2);
-
-            // Convert the hex string to a byte.
-            let byte = u8::from_str_radix(&hex, 16).map_err(|_| InvalidUriComponent)?;
-            decoded.push(byte);
-        } else if c == '+' {
-            // If the character is '+', treat it as a space.
-            decoded.push(b' '); // Push a space byte
-        } else {
-            // If the character is not a percent-encoded value, just push it as-is.
-            decoded.push(c as u8);
+    decode_bytes(input.as_ref(), true)
+}
+
+fn decode_bytes(input: &str, plus_as_space: bool) -> Result<String, InvalidUriComponent> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'%' => {
+                // Both characters after the '%' must be hex digits
+                let hex = bytes
+                    .get((pos + 1)..(pos + 3))
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                    .ok_or(InvalidUriComponent)?;
+
+                let hex = std::str::from_utf8(hex).map_err(|_| InvalidUriComponent)?;
+                let byte = u8::from_str_radix(hex, 16).map_err(|_| InvalidUriComponent)?;
+                decoded.push(byte);
+                pos += 3;
+            }
+            b'+' if plus_as_space => {
+                decoded.push(b' ');
+                pos += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                pos += 1;
+            }
         }
     }
 
-    // Attempt to convert the vector of bytes to a UTF-8 string.
-    // If this fails, return an error.
+    // The decoded bytes may be a multi-byte UTF-8 sequence
     String::from_utf8(decoded).map_err(|_| InvalidUriComponent)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{decode, encode};
+    use super::{
+        decode, encode, percent_decode, percent_encode, FormASCII, PathSegmentASCII, QueryASCII,
+    };
 
     #[test]
     fn should_encode_special_characters() {
@@ -159,6 +253,26 @@ mod tests {
         assert!(decode("hello%").is_err());
     }
 
+    #[test]
+    fn should_percent_decode_reserved_and_multi_byte_characters() {
+        assert_eq!(percent_decode("a%2Fb").unwrap(), "a/b");
+        assert_eq!(percent_decode("a+b").unwrap(), "a+b");
+        assert_eq!(percent_decode("caf%C3%A9 ñ").unwrap(), "café ñ");
+        assert!(percent_decode("%ZZ").is_err());
+        assert!(percent_decode("%+1").is_err());
+        assert!(percent_decode("%C3").is_err());
+
+        // Form values
+        assert_eq!(decode("hello+world%2B").unwrap(), "hello world+");
+    }
+
+    #[test]
+    fn should_percent_encode_with_sets() {
+        assert_eq!(percent_encode("a b/c", PathSegmentASCII), "a%20b%2Fc");
+        assert_eq!(percent_encode("a=b&c/d", QueryASCII), "a%3Db%26c/d");
+        assert_eq!(percent_encode("a b+c", FormASCII), "a+b%2Bc");
+    }
+
     #[test]
     fn should_handle_empty_string() {
         assert_eq!(encode(""), "");
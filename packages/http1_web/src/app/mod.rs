@@ -1,4 +1,5 @@
 mod pre_render;
+mod route_table;
 pub use pre_render::*;
 
 use std::{
@@ -8,20 +9,31 @@ use std::{
 };
 
 use http1::{
-    body::Body, extensions::Extensions, handler::RequestHandler, method::Method, request::Request,
-    response::Response, status::StatusCode,
+    body::{http_body::HttpBody, Body},
+    extensions::Extensions,
+    handler::RequestHandler,
+    headers::{self, HeaderValue},
+    method::Method,
+    request::Request,
+    response::Response,
+    status::StatusCode,
 };
 
 use crate::{
     from_request::FromRequest,
     handler::{BoxedHandler, Handler},
     middleware::{BoxedMiddleware, Middleware},
+    request_time::RequestTime,
+    response::{is_problem_json, set_problem_json},
     routing::{
-        method_route::MethodRoute, params::ParamsMap, route::Route, route_info::RouteInfo, Match,
-        Router,
+        method_route::MethodRoute,
+        params::ParamsMap,
+        route::{get_segments, Route},
+        route_info::RouteInfo,
+        Match, Router,
     },
     state::State,
-    IntoResponse,
+    ErrorDetail, IntoResponse,
 };
 
 /// Represents a server application that provides middlewares and handlers.
@@ -30,6 +42,11 @@ pub struct App {
     scope: Scope,
     middleware: Vec<BoxedMiddleware>,
     app_state: Extensions,
+    debug_errors: bool,
+    debug_routes: bool,
+    routes_json_path: Option<String>,
+    auto_options: bool,
+    finalizers: Vec<fn(&mut Response<Body>)>,
 }
 
 impl App {
@@ -39,6 +56,11 @@ impl App {
             scope: Scope::root(),
             middleware: Vec::new(),
             app_state: Default::default(),
+            debug_errors: cfg!(debug_assertions),
+            debug_routes: cfg!(debug_assertions),
+            routes_json_path: None,
+            auto_options: false,
+            finalizers: Vec::new(),
         }
     }
 }
@@ -95,6 +117,47 @@ impl App {
         self
     }
 
+    /// Whether if include the error details in the body of server errors responses.
+    ///
+    /// When disabled the body only contains a generic message, this defaults to `true` on debug builds.
+    pub fn debug_errors(mut self, enabled: bool) -> Self {
+        self.debug_errors = enabled;
+        self
+    }
+
+    /// Whether if serve the route table at the path set with [`App::routes_json_at`].
+    ///
+    /// This defaults to `true` on debug builds, so the route table is not exposed accidentally on release builds.
+    pub fn debug_routes(mut self, enabled: bool) -> Self {
+        self.debug_routes = enabled;
+        self
+    }
+
+    /// Serves the route table as `JSON` on `GET` requests to the given path, when [`App::debug_routes`] is enabled.
+    ///
+    /// Each route lists its `path`, `methods` and `params`, for example:
+    /// `[{ "path": "/users/:id", "methods": ["GET"], "params": [{ "name": "id", "catch_all": false }] }]`.
+    pub fn routes_json_at(mut self, path: &str) -> Self {
+        self.routes_json_path = Some(path.to_owned());
+        self
+    }
+
+    /// Whether if respond `OPTIONS` requests with `204 No Content` and the `Allow` header listing the methods of the route.
+    ///
+    /// Routes with an explicit `OPTIONS` handler are not affected, this defaults to `false`.
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = enabled;
+        self
+    }
+
+    /// Adds a function that is called with every outgoing response, including the fallbacks.
+    ///
+    /// The finalizers run in the order they were added, after all the middlewares.
+    pub fn on_response(mut self, finalizer: fn(&mut Response<Body>)) -> Self {
+        self.finalizers.push(finalizer);
+        self
+    }
+
     /// Adds a route with the given method, route path and handler.
     pub fn route<H, Args, R>(mut self, method: MethodRoute, route: &str, handler: H) -> Self
     where
@@ -197,6 +260,16 @@ impl App {
         self.route(MethodRoute::TRACE, route, handler)
     }
 
+    /// Adds a `CONNECT` request route handler.
+    pub fn connect<H, Args, R>(self, route: &str, handler: H) -> Self
+    where
+        Args: FromRequest,
+        H: Handler<Args, Output = R> + Sync + Send + 'static,
+        R: IntoResponse,
+    {
+        self.route(MethodRoute::CONNECT, route, handler)
+    }
+
     /// Adds a handler that catches any route in the given route path.
     pub fn any<H, Args, R>(self, route: &str, handler: H) -> Self
     where
@@ -218,10 +291,20 @@ static NOT_FOUND_HANDLER: LazyLock<BoxedHandler> =
 
 impl RequestHandler for App {
     fn handle(&self, mut req: Request<Body>) -> Response<Body> {
+        // Set once so all the timestamps within the request are the same
+        if !req.extensions().contains::<RequestTime>() {
+            req.extensions_mut().insert(RequestTime::now());
+        }
+
         let middlewares = self.middleware.as_slice();
         let method = MethodRoute::from_method(req.method());
         let req_path = req.uri().path_and_query().path();
         let mtch = self.scope.find(req_path, req.method());
+        let builtin = self
+            .routes_json_handler(req_path, req.method())
+            .or_else(|| self.auto_options_handler(req_path, req.method()))
+            .or_else(|| self.method_not_allowed_handler(req_path, req.method()));
+        let handler = builtin.as_ref().unwrap_or(mtch.value);
 
         // Add any additional extensions
         if let Some(r) = self.scope.find_route(req_path) {
@@ -233,26 +316,125 @@ impl RequestHandler for App {
 
         // Handle the request
         let res = if middlewares.is_empty() {
-            mtch.value.call(req)
+            handler.call(req)
         } else {
             let handler = middlewares
                 .iter()
                 .cloned()
-                .fold(mtch.value.clone(), |cur, next| {
+                .fold(handler.clone(), |cur, next| {
                     BoxedHandler::new(move |r| next.on_request(r, &cur))
                 });
 
             handler.call(req)
         };
 
+        let mut res = if self.debug_errors {
+            res
+        } else {
+            hide_error_detail(res)
+        };
+
+        for finalizer in &self.finalizers {
+            finalizer(&mut res);
+        }
+
         match method {
-            // We don't need the body for HEAD requests
-            MethodRoute::HEAD => res.map_body(|_| Body::empty()),
+            // We don't need the body for HEAD requests, but keep the length it would have
+            MethodRoute::HEAD => {
+                let headers = res.headers();
+                if !headers.contains_key(headers::CONTENT_LENGTH)
+                    && !headers.contains_key(headers::TRANSFER_ENCODING)
+                {
+                    if let Some(len) = res.body().size_hint() {
+                        res.headers_mut()
+                            .insert(headers::CONTENT_LENGTH, HeaderValue::from(len));
+                    }
+                }
+
+                res.map_body(|_| Body::empty())
+            }
             _ => res,
         }
     }
 }
 
+impl App {
+    fn routes_json_handler(&self, path: &str, method: &Method) -> Option<BoxedHandler> {
+        if !self.debug_routes || !matches!(method, Method::GET | Method::HEAD) {
+            return None;
+        }
+
+        let routes_json_path = self.routes_json_path.as_deref()?;
+        if get_segments(routes_json_path).ne(get_segments(path)) {
+            return None;
+        }
+
+        Some(route_table::routes_json_handler(&self.scope))
+    }
+
+    fn auto_options_handler(&self, path: &str, method: &Method) -> Option<BoxedHandler> {
+        if !self.auto_options || *method != Method::OPTIONS {
+            return None;
+        }
+
+        let methods = self.scope.allowed_methods(path);
+        if methods.is_empty() || methods.contains(&Method::OPTIONS) {
+            return None;
+        }
+
+        let allow = allow_header_value(&self.allowed_methods(path));
+        Some(BoxedHandler::new(move || {
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .insert_header(headers::ALLOW, allow.clone())
+                .body(Body::empty())
+        }))
+    }
+
+    /// The `405 Method Not Allowed` of the scope does not know about the `OPTIONS` served by the app.
+    fn method_not_allowed_handler(&self, path: &str, method: &Method) -> Option<BoxedHandler> {
+        if !self.auto_options {
+            return None;
+        }
+
+        let methods = self.allowed_methods(path);
+        if methods.is_empty() || methods.contains(method) {
+            return None;
+        }
+
+        Some(method_not_allowed_handler(&methods))
+    }
+
+    /// Returns the methods served for the given path, including `OPTIONS` if `auto_options` is enabled.
+    fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        let mut methods = self.scope.allowed_methods(path);
+
+        if self.auto_options && !methods.is_empty() && !methods.contains(&Method::OPTIONS) {
+            methods.push(Method::OPTIONS);
+            methods.sort();
+        }
+
+        methods
+    }
+}
+
+fn hide_error_detail(mut res: Response<Body>) -> Response<Body> {
+    let status = res.status();
+
+    if !status.is_server_error() || res.extensions_mut().remove::<ErrorDetail>().is_none() {
+        return res;
+    }
+
+    let msg = status.reason_phrase().unwrap_or("Internal Server Error");
+
+    if is_problem_json(&res) {
+        set_problem_json(&mut res, msg, msg);
+        return res;
+    }
+
+    res.map_body(|_| Body::from(msg))
+}
+
 struct DefaultFallback;
 
 impl Handler<Request<Body>> for DefaultFallback {
@@ -279,6 +461,7 @@ pub struct Scope {
     method_router: Router<RouteId>,
     path_to_route: HashMap<String, RouteId>,
     route_to_methods: HashMap<RouteId, HashMap<Method, BoxedHandler>>,
+    method_not_allowed: HashMap<RouteId, BoxedHandler>,
     fallbacks: Router<BoxedHandler>,
     is_root: bool,
 }
@@ -311,6 +494,9 @@ impl Scope {
                 for m in method.into_methods() {
                     methods.insert(m, handler.clone());
                 }
+
+                let not_allowed = method_not_allowed_handler(&served_methods(methods));
+                self.method_not_allowed.insert(*route_id, not_allowed);
             }
             None => {
                 let mut methods = HashMap::new();
@@ -321,6 +507,10 @@ impl Scope {
                 let route_id = RouteId::next();
                 self.method_router.insert(route, route_id);
                 self.path_to_route.insert(route.to_owned(), route_id);
+                self.method_not_allowed.insert(
+                    route_id,
+                    method_not_allowed_handler(&served_methods(&methods)),
+                );
                 self.route_to_methods.insert(route_id, methods);
             }
         }
@@ -369,8 +559,6 @@ impl Scope {
     }
 
     fn find(&self, route: &str, method: &Method) -> Match<&BoxedHandler> {
-        let fallback = self.find_fallback(route);
-
         match self.method_router.find(route) {
             Some(Match {
                 params,
@@ -381,13 +569,24 @@ impl Scope {
                     .get(route_id)
                     .expect("route id it's define to methods are");
 
-                let value = methods.get(method).unwrap_or(fallback);
+                // `HEAD` requests are served by the `GET` handler if there is no explicit `HEAD` route
+                let value = match methods.get(method) {
+                    None if *method == Method::HEAD => methods.get(&Method::GET),
+                    value => value,
+                };
+
+                // The path exists but not for this method
+                let value = value.unwrap_or_else(|| {
+                    self.method_not_allowed
+                        .get(route_id)
+                        .expect("route id it's define to method not allowed handler")
+                });
 
                 Match { params, value }
             }
             None => Match {
                 params: ParamsMap::default(),
-                value: fallback,
+                value: self.find_fallback(route),
             },
         }
     }
@@ -523,6 +722,16 @@ impl Scope {
         self.route(MethodRoute::TRACE, route, handler)
     }
 
+    /// Adds a `CONNECT` request route handler.
+    pub fn connect<H, Args, R>(self, route: &str, handler: H) -> Self
+    where
+        Args: FromRequest,
+        H: Handler<Args, Output = R> + Sync + Send + 'static,
+        R: IntoResponse,
+    {
+        self.route(MethodRoute::CONNECT, route, handler)
+    }
+
     /// Adds a handler that catches any route in the given route path.
     pub fn any<H, Args, R>(self, route: &str, handler: H) -> Self
     where
@@ -543,6 +752,64 @@ impl Scope {
                 .map(|method| (r.clone(), method))
         })
     }
+
+    /// Returns the methods served by the route matching the given path, sorted.
+    ///
+    /// Includes `HEAD` if the route has a `GET` handler, and returns an empty list if no route matches the path.
+    pub fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        let Some(Match {
+            value: route_id, ..
+        }) = self.method_router.find(path)
+        else {
+            return Vec::new();
+        };
+
+        served_methods(
+            self.route_to_methods
+                .get(route_id)
+                .expect("failed to get route methods"),
+        )
+    }
+}
+
+fn sorted_methods(methods: &HashMap<Method, BoxedHandler>) -> Vec<Method> {
+    let mut methods = methods.keys().cloned().collect::<Vec<_>>();
+    methods.sort();
+    methods
+}
+
+fn served_methods(methods: &HashMap<Method, BoxedHandler>) -> Vec<Method> {
+    let mut served = sorted_methods(methods);
+
+    // `HEAD` requests are served by the `GET` handler
+    if methods.contains_key(&Method::GET) && !methods.contains_key(&Method::HEAD) {
+        served.push(Method::HEAD);
+        served.sort();
+    }
+
+    served
+}
+
+/// Returns a handler that responds `405 Method Not Allowed` with the `Allow` header listing the given methods.
+fn method_not_allowed_handler(methods: &[Method]) -> BoxedHandler {
+    let allow = allow_header_value(methods);
+
+    BoxedHandler::new(move || {
+        Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .insert_header(headers::ALLOW, allow.clone())
+            .body(Body::empty())
+    })
+}
+
+fn allow_header_value(methods: &[Method]) -> HeaderValue {
+    let allow = methods
+        .iter()
+        .map(|m| m.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    HeaderValue::from_string(allow)
 }
 
 impl Debug for Scope {
@@ -565,7 +832,7 @@ mod tests {
         sync::{Arc, Mutex},
     };
 
-    use http1::{body::http_body::HttpBody, uri::uri::Uri};
+    use http1::uri::uri::Uri;
 
     use crate::state::State;
 
@@ -858,10 +1125,178 @@ mod tests {
 
         assert_eq!(get_response(not_found_match.value), "root fallback");
         assert_eq!(get_response(items_match.value), "items");
-        assert_eq!(get_response(not_found_items_match.value), "root fallback");
+        assert_eq!(
+            not_found_items_match
+                .value
+                .call(Request::builder().body(Body::empty()).unwrap())
+                .status(),
+            StatusCode::METHOD_NOT_ALLOWED
+        );
         assert_eq!(get_response(parent_fallback_match.value), "root fallback");
     }
 
+    #[test]
+    fn should_return_allowed_methods_for_path() {
+        let scope = Scope::new()
+            .get("/items/:id", || "get")
+            .post("/items/:id", || "post")
+            .delete("/items/:id", || "delete")
+            .get("/health", || "health");
+
+        assert_eq!(
+            scope.allowed_methods("/items/12"),
+            vec![Method::GET, Method::POST, Method::DELETE, Method::HEAD]
+        );
+        assert_eq!(
+            scope.allowed_methods("/health"),
+            vec![Method::GET, Method::HEAD]
+        );
+        assert!(scope.allowed_methods("/unknown").is_empty());
+    }
+
+    #[test]
+    fn should_return_method_not_allowed_for_wrong_method() {
+        let app = App::new().get("/items", || "items").fallback(|| "fallback");
+
+        let req = Request::new(
+            Method::POST,
+            Uri::from_str("/items").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            res.headers().get(headers::ALLOW).unwrap().as_str(),
+            "GET, HEAD"
+        );
+
+        // Unknown paths still use the fallback
+        let req = Request::new(
+            Method::POST,
+            Uri::from_str("/other").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"fallback");
+    }
+
+    #[test]
+    fn should_serve_head_from_get_route() {
+        let app = App::new()
+            .get("/", || "Hello World!")
+            .get("/explicit", || "get")
+            .head("/explicit", || {
+                Response::builder()
+                    .insert_header(headers::CONTENT_LENGTH, HeaderValue::from(3_usize))
+                    .body(Body::empty())
+            });
+
+        let req = Request::new(Method::HEAD, Uri::from_str("/").unwrap(), Body::empty());
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_LENGTH).unwrap().as_str(),
+            "12"
+        );
+        assert!(res.into_body().read_all_bytes().unwrap().is_empty());
+
+        // Explicit HEAD routes take precedence
+        let req = Request::new(
+            Method::HEAD,
+            Uri::from_str("/explicit").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_LENGTH).unwrap().as_str(),
+            "3"
+        );
+    }
+
+    #[test]
+    fn should_respond_options_with_allowed_methods() {
+        let app = App::new()
+            .auto_options(true)
+            .get("/items", || "list")
+            .post("/items", || "create")
+            .options("/custom", || "custom options");
+
+        let req = Request::new(
+            Method::OPTIONS,
+            Uri::from_str("/items").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            res.headers().get(headers::ALLOW).unwrap().as_str(),
+            "GET, POST, OPTIONS, HEAD"
+        );
+
+        // The `405 Method Not Allowed` includes the automatic `OPTIONS`
+        let req = Request::new(
+            Method::DELETE,
+            Uri::from_str("/items").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            res.headers().get(headers::ALLOW).unwrap().as_str(),
+            "GET, POST, OPTIONS, HEAD"
+        );
+
+        // Explicit OPTIONS handler
+        let req = Request::new(
+            Method::OPTIONS,
+            Uri::from_str("/custom").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"custom options");
+    }
+
+    #[test]
+    fn should_serve_routes_json() {
+        let app = App::new()
+            .debug_routes(true)
+            .routes_json_at("/_routes")
+            .get("/users/:id", || "user")
+            .post("/users/:id", || "update_user")
+            .get("/static/:path*", || "static");
+
+        let req = Request::new(
+            Method::GET,
+            Uri::from_str("/_routes").unwrap(),
+            Body::empty(),
+        );
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            concat!(
+                r#"[{"path":"/static/:path*","methods":["GET"],"params":[{"name":"path","catch_all":true}]},"#,
+                r#"{"path":"/users/:id","methods":["GET","POST"],"params":[{"name":"id","catch_all":false}]}]"#
+            )
+        );
+
+        // Disabled
+        let app = App::new()
+            .debug_routes(false)
+            .routes_json_at("/_routes")
+            .get("/users/:id", || "user");
+
+        let req = Request::new(
+            Method::GET,
+            Uri::from_str("/_routes").unwrap(),
+            Body::empty(),
+        );
+        assert_eq!(app.handle(req).status(), StatusCode::NOT_FOUND);
+    }
+
     #[test]
     fn should_list_routes() {
         let scope = Scope::new()
@@ -941,4 +1376,224 @@ mod tests {
         let state = s.lock().unwrap().take().unwrap();
         assert_eq!(state.0, HitPoints(10))
     }
+
+    #[test]
+    fn should_respond_internal_server_error_when_state_is_missing() {
+        #[derive(Debug, Clone)]
+        struct Database;
+
+        let app = App::new()
+            .debug_errors(true)
+            .get("/", |_: State<Database>| "unreachable")
+            .get(
+                "/optional",
+                |db: Option<State<Database>>| {
+                    if db.is_some() {
+                        "found"
+                    } else {
+                        "missing"
+                    }
+                },
+            );
+
+        let (status, body) = get_error_body(&app);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body.contains("Database"), "{body}");
+
+        let res = app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/optional").unwrap(),
+            Body::empty(),
+        ));
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"missing");
+    }
+
+    #[test]
+    fn should_extract_struct_from_request() {
+        use crate::{json::Json, path::Path};
+
+        #[derive(Clone)]
+        struct Greeting(&'static str);
+
+        struct Input {
+            name: String,
+        }
+
+        serde::impl_deserialize_struct!(Input => { name: String });
+
+        struct GreetArgs {
+            greeting: State<Greeting>,
+            id: Path<u64>,
+            body: Json<Input>,
+        }
+
+        crate::impl_from_request!(GreetArgs => {
+            greeting: State<Greeting>,
+            id: Path<u64>,
+            body: Json<Input>,
+        });
+
+        let app = App::new()
+            .state(Greeting("Hello"))
+            .post("/users/:id", |args: GreetArgs| {
+                let GreetArgs {
+                    greeting: State(greeting),
+                    id: Path(id),
+                    body: Json(input),
+                } = args;
+
+                format!("{} {} #{id}", greeting.0, input.name)
+            });
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/users/12")
+            .insert_header(headers::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{ "name": "Ayaka" }"#))
+            .unwrap();
+
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.into_body().read_all_bytes().unwrap(),
+            b"Hello Ayaka #12"
+        );
+
+        // The rejections of all the failing fields are returned
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/users/abc")
+            .insert_header(headers::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{ "name": 1 }"#))
+            .unwrap();
+
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let body = String::from_utf8(res.into_body().read_all_bytes().unwrap()).unwrap();
+        let fields = body
+            .lines()
+            .filter_map(|line| line.split_once(':').map(|(field, _)| field))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, ["id", "body"], "{body}");
+    }
+
+    fn get_error_body(app: &App) -> (StatusCode, String) {
+        let res = app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/").unwrap(),
+            Body::empty(),
+        ));
+
+        let status = res.status();
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        (status, String::from_utf8(bytes).unwrap())
+    }
+
+    #[test]
+    fn should_include_error_detail_when_debug_errors_enabled() {
+        let app = App::new().debug_errors(true).get("/", || {
+            Err::<(), _>(crate::ErrorResponse::from_error(
+                crate::ErrorStatusCode::InternalServerError,
+                "failed to connect to database",
+            ))
+        });
+
+        let (status, body) = get_error_body(&app);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, "failed to connect to database");
+    }
+
+    #[test]
+    fn should_hide_error_detail_when_debug_errors_disabled() {
+        let app = App::new().debug_errors(false).get("/", || {
+            Err::<(), _>(crate::ErrorResponse::from_error(
+                crate::ErrorStatusCode::InternalServerError,
+                "failed to connect to database",
+            ))
+        });
+
+        let (status, body) = get_error_body(&app);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, "Internal Server Error");
+    }
+
+    #[test]
+    fn should_keep_client_error_detail_when_debug_errors_disabled() {
+        let app = App::new().debug_errors(false).get("/", || {
+            Err::<(), _>(crate::ErrorResponse::from_error(
+                crate::ErrorStatusCode::BadRequest,
+                "invalid email",
+            ))
+        });
+
+        let (status, body) = get_error_body(&app);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, "invalid email");
+    }
+
+    #[test]
+    fn should_respond_rejection_as_problem_json_when_accepts_json() {
+        #[derive(Debug, Clone)]
+        struct Database;
+
+        let app = App::new()
+            .debug_errors(false)
+            .get("/", |_: State<Database>| "unreachable");
+
+        let req = Request::builder()
+            .uri("/")
+            .insert_header(headers::ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_TYPE).unwrap().as_str(),
+            "application/problem+json"
+        );
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            r#"{"status":500,"title":"Internal Server Error","detail":"Internal Server Error"}"#
+        );
+
+        let req = Request::builder()
+            .uri("/")
+            .insert_header(headers::ACCEPT, "text/html, */*;q=0.8")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.handle(req);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(headers::CONTENT_TYPE).is_none());
+    }
+
+    #[test]
+    fn should_run_response_finalizers_on_every_response() {
+        let app = App::new()
+            .on_response(|res| {
+                res.headers_mut().insert(
+                    http1::headers::SERVER,
+                    http1::headers::HeaderValue::from_static("http1"),
+                );
+            })
+            .get("/", || "Hello World!");
+
+        for (path, status) in [("/", StatusCode::OK), ("/not-found", StatusCode::NOT_FOUND)] {
+            let res = app.handle(Request::new(
+                Method::GET,
+                Uri::from_str(path).unwrap(),
+                Body::empty(),
+            ));
+
+            assert_eq!(res.status(), status);
+            assert_eq!(
+                res.headers().get(http1::headers::SERVER).unwrap().as_str(),
+                "http1"
+            );
+        }
+    }
 }
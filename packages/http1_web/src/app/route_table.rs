@@ -0,0 +1,70 @@
+use serde::impl_serialize_struct;
+
+use crate::{handler::BoxedHandler, json::Json, routing::route::RouteSegment};
+
+use super::{sorted_methods, Scope};
+
+#[derive(Clone)]
+struct RouteParam {
+    name: String,
+    catch_all: bool,
+}
+
+impl_serialize_struct!(RouteParam => {
+    name: String,
+    catch_all: bool,
+});
+
+#[derive(Clone)]
+struct RouteEntry {
+    path: String,
+    methods: Vec<String>,
+    params: Vec<RouteParam>,
+}
+
+impl_serialize_struct!(RouteEntry => {
+    path: String,
+    methods: Vec<String>,
+    params: Vec<RouteParam>,
+});
+
+/// Returns a handler that responds with the route table of the scope as `JSON`, sorted by path.
+pub(crate) fn routes_json_handler(scope: &Scope) -> BoxedHandler {
+    let mut entries = scope
+        .method_router
+        .entries()
+        .map(|(route, route_id)| {
+            let methods = scope
+                .route_to_methods
+                .get(route_id)
+                .expect("failed to get route methods");
+
+            let params = route
+                .iter()
+                .filter_map(|segment| match segment {
+                    RouteSegment::Static(_) => None,
+                    RouteSegment::Dynamic(name) => Some(RouteParam {
+                        name: name.clone(),
+                        catch_all: false,
+                    }),
+                    RouteSegment::CatchAll(name) => Some(RouteParam {
+                        name: name.clone(),
+                        catch_all: true,
+                    }),
+                })
+                .collect();
+
+            RouteEntry {
+                path: route.to_string(),
+                methods: sorted_methods(methods)
+                    .iter()
+                    .map(|m| m.as_str().to_owned())
+                    .collect(),
+                params,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    BoxedHandler::new(move || Json(entries.clone()))
+}
@@ -0,0 +1,203 @@
+use std::{convert::Infallible, ops::Deref};
+
+use http1::{
+    body::{body_reader::BodyReader, http_body::HttpBody, Body},
+    payload::Payload,
+    request::Request,
+    response::Response,
+};
+
+use crate::{
+    from_request::{FromRequest, InvalidBodyError},
+    IntoResponse,
+};
+
+/// Extracts the request body as the exact bytes that were received.
+///
+/// Useful when the body must not be parsed before being used, like for verifying the signature of a webhook.
+/// The server-wide body limit applies, larger bodies are rejected with `413 Payload Too Large`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Returns the bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(value: Vec<u8>) -> Self {
+        Bytes(value)
+    }
+}
+
+impl FromRequest for Bytes {
+    type Rejection = InvalidBodyError;
+
+    fn from_request(req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Vec::<u8>::from_request(req, payload).map(Bytes)
+    }
+}
+
+impl IntoResponse for Bytes {
+    fn into_response(self) -> Response<Body> {
+        self.0.into_response()
+    }
+}
+
+/// Extracts the unparsed request body without reading it.
+///
+/// The body is empty if was already taken by other extractor.
+#[derive(Debug)]
+pub struct RawBody(pub Body);
+
+impl RawBody {
+    /// Returns the body.
+    pub fn into_inner(self) -> Body {
+        self.0
+    }
+
+    /// Returns a reader over the bytes of the body.
+    pub fn into_reader(self) -> BodyReader {
+        BodyReader::new(self.0)
+    }
+}
+
+impl HttpBody for RawBody {
+    type Err = <Body as HttpBody>::Err;
+    type Data = <Body as HttpBody>::Data;
+
+    fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+        self.0.read_next()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl FromRequest for RawBody {
+    type Rejection = Infallible;
+
+    fn from_request(_req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Ok(RawBody(payload.take().unwrap_or_default()))
+    }
+}
+
+/// Extracts the request body as a `std::io::Read` that reads the body as it arrives,
+/// useful to stream large uploads to a file without holding them in memory.
+impl FromRequest for BodyReader {
+    type Rejection = Infallible;
+
+    fn from_request(_req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Ok(BodyReader::new(payload.take().unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        io::Read,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use http1::{
+        body::{body_reader::BodyReader, http_body::HttpBody, Body},
+        payload::Payload,
+        request::Request,
+    };
+
+    use crate::from_request::FromRequest;
+
+    use super::{Bytes, RawBody};
+
+    const PAYLOAD: &[u8] = b"{ \"event\":  \"push\",\r\n \"id\": 1 }\n\x00\xff";
+
+    #[test]
+    fn should_extract_body_bytes_unchanged() {
+        let req = Request::builder().body(()).unwrap();
+        let bytes = Bytes::from_request(&req, &mut Payload::Data(Body::from(PAYLOAD))).unwrap();
+        assert_eq!(bytes.as_ref(), PAYLOAD);
+
+        let mut raw = RawBody::from_request(&req, &mut Payload::Data(Body::from(PAYLOAD))).unwrap();
+        assert_eq!(raw.size_hint(), Some(PAYLOAD.len()));
+        assert_eq!(raw.read_all_bytes().unwrap(), PAYLOAD);
+
+        let raw = RawBody::from_request(&req, &mut Payload::Data(Body::from(PAYLOAD))).unwrap();
+        let mut buf = Vec::new();
+        raw.into_reader().read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, PAYLOAD);
+    }
+
+    #[test]
+    fn should_stream_large_body_in_chunks() {
+        // Writes `total` bytes in chunks of 64 KiB
+        struct LargeBody {
+            remaining: usize,
+            chunks: Arc<AtomicUsize>,
+        }
+
+        impl HttpBody for LargeBody {
+            type Err = Infallible;
+            type Data = Vec<u8>;
+
+            fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+                if self.remaining == 0 {
+                    return Ok(None);
+                }
+
+                let len = self.remaining.min(64 * 1024);
+                self.remaining -= len;
+                self.chunks.fetch_add(1, Ordering::SeqCst);
+                Ok(Some(vec![b'a'; len]))
+            }
+        }
+
+        let total = 10 * 1024 * 1024 + 7;
+        let chunks = Arc::new(AtomicUsize::new(0));
+        let body = Body::new(LargeBody {
+            remaining: total,
+            chunks: chunks.clone(),
+        });
+
+        let req = Request::builder().body(()).unwrap();
+        let mut reader = BodyReader::from_request(&req, &mut Payload::Data(body)).unwrap();
+
+        let mut buf = [0; 8192];
+        let mut read = reader.read(&mut buf).unwrap();
+        assert_eq!(read, buf.len());
+        assert_eq!(chunks.load(Ordering::SeqCst), 1);
+
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+
+            assert!(buf[..n].iter().all(|b| *b == b'a'));
+            read += n;
+        }
+
+        assert_eq!(read, total);
+        assert_eq!(chunks.load(Ordering::SeqCst), total.div_ceil(64 * 1024));
+    }
+}
@@ -3,7 +3,11 @@ use std::{
     str::FromStr,
 };
 
-use http1::headers::{self, Headers};
+use http1::{
+    headers::{self, Headers},
+    protocol::connection::Connected,
+    request::Request,
+};
 
 use crate::{conn_info::ConnectionInfo, from_request::FromRequest, ErrorStatusCode};
 
@@ -33,6 +37,16 @@ impl ClientIp {
             Inner::List(vec) => vec.as_slice(),
         }
     }
+
+    /// Gets the client ip from the headers or the connection information, returns `None` if is not available.
+    pub(crate) fn try_from_request<T>(req: &Request<T>) -> Option<Self> {
+        if let Some(inner) = get_ip_from_headers(req.headers()) {
+            return Some(ClientIp(inner));
+        }
+
+        let addr = req.extensions().get::<Connected>()?.peer_addr()?;
+        Some(ClientIp(Inner::Ip(addr.ip())))
+    }
 }
 
 impl FromRequest for ClientIp {
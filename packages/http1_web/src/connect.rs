@@ -0,0 +1,169 @@
+use std::fmt::Display;
+
+use http1::{
+    body::Body,
+    method::Method,
+    protocol::upgrade::{PendingUpgrade, PendingUpgradeError, Upgrade},
+    response::Response,
+    status::StatusCode,
+    uri::authority::Authority,
+};
+
+use crate::{from_request::FromRequest, IntoResponse};
+
+/// Waits for the tunnel connection to be ready.
+#[derive(Debug)]
+pub struct PendingConnect(PendingUpgrade);
+
+impl PendingConnect {
+    /// Waits for the tunnel connection to be ready.
+    /// This blocks the current thread so must be called after sending the response or in another thread.
+    pub fn wait(self) -> Result<Upgrade, PendingUpgradeError> {
+        self.0.wait()
+    }
+}
+
+/// Extracts a `CONNECT` request to establish a tunnel to the target.
+///
+/// The request target of a `CONNECT` request is an authority like `example.com:443` and does not have a path,
+/// so the request is routed to `/`.
+#[derive(Debug)]
+pub struct Connect {
+    target: Authority,
+    pending: PendingUpgrade,
+}
+
+impl Connect {
+    /// Returns the host and port the client wants to connect to.
+    pub fn target(&self) -> &Authority {
+        &self.target
+    }
+
+    /// Accepts the tunnel, returns the `200 OK` response that notifies the client
+    /// and the pending connection which is ready after the response is sent.
+    pub fn accept(self) -> (PendingConnect, Response<Body>) {
+        let response = Response::new(StatusCode::OK, Body::empty());
+        (PendingConnect(self.pending), response)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConnectError {
+    InvalidMethod(Method),
+    MissingTarget,
+    NoConnection,
+}
+
+impl std::error::Error for ConnectError {}
+
+impl Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectError::InvalidMethod(method) => {
+                write!(f, "invalid http method: `{method}` expected CONNECT")
+            }
+            ConnectError::MissingTarget => write!(f, "missing `host:port` request target"),
+            ConnectError::NoConnection => write!(f, "failed to get connection upgrade stream"),
+        }
+    }
+}
+
+impl IntoResponse for ConnectError {
+    fn into_response(self) -> Response<Body> {
+        log::error!("Failed to establish tunnel: {self}");
+
+        match self {
+            ConnectError::InvalidMethod(_) => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+            ConnectError::MissingTarget => StatusCode::BAD_REQUEST.into_response(),
+            ConnectError::NoConnection => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+}
+
+impl FromRequest for Connect {
+    type Rejection = ConnectError;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        _payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        if req.method() != Method::CONNECT {
+            return Err(ConnectError::InvalidMethod(req.method().clone()));
+        }
+
+        let target = req
+            .uri()
+            .authority()
+            .filter(|x| x.port().is_some())
+            .cloned()
+            .ok_or(ConnectError::MissingTarget)?;
+
+        let pending = req
+            .extensions()
+            .get::<PendingUpgrade>()
+            .cloned()
+            .ok_or(ConnectError::NoConnection)?;
+
+        Ok(Connect { target, pending })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpStream,
+    };
+
+    use http1::server::Server;
+
+    use crate::app::App;
+
+    use super::Connect;
+
+    #[test]
+    fn should_upgrade_connect_request_to_tunnel() {
+        let app = App::new().connect("/", |connect: Connect| {
+            assert_eq!(connect.target().to_string(), "example.com:443");
+
+            let (pending, response) = connect.accept();
+            std::thread::spawn(move || {
+                let mut upgrade = pending.wait().unwrap();
+                let mut buf = [0; 4];
+                upgrade.read_exact(&mut buf).unwrap();
+                upgrade.write_all(&buf).unwrap();
+            });
+
+            response
+        });
+
+        let echo = Server::new()
+            .run_scoped(app, |addr| {
+                let mut stream = TcpStream::connect(addr).unwrap();
+                stream
+                    .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n")
+                    .unwrap();
+
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut status_line = String::new();
+                reader.read_line(&mut status_line).unwrap();
+                assert!(status_line.starts_with("HTTP/1.1 200"), "{status_line}");
+
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+
+                stream.write_all(b"ping").unwrap();
+                let mut buf = [0; 4];
+                reader.read_exact(&mut buf).unwrap();
+                buf
+            })
+            .unwrap();
+
+        assert_eq!(&echo, b"ping");
+    }
+}
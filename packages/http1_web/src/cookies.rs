@@ -2,6 +2,7 @@ use std::{convert::Infallible, fmt::Display, str::FromStr};
 
 use http1::{
     body::Body,
+    common::sha256,
     headers::{self, HeaderValue, Headers},
     response::Response,
     status::StatusCode,
@@ -10,7 +11,10 @@ use http1::{
 
 use datetime::DateTime;
 
-use crate::{from_request::FromRequest, IntoResponse, IntoResponseParts};
+use crate::{
+    from_request::FromRequest, ErrorResponse, ErrorStatusCode, IntoResponse, IntoResponseParts,
+    ResponseHeaders,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SameSite {
@@ -387,8 +391,13 @@ impl Cookies {
         let values = headers.get_all(headers::COOKIE);
         let mut cookies = Cookies::new();
 
-        for header_value in values {
-            let raw = header_value.as_str();
+        // A `Cookie` header contains a list of `name=value` pairs separated by `;`
+        let pairs = values
+            .flat_map(|x| x.as_str().split(';'))
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty());
+
+        for raw in pairs {
             match Cookie::from_str(raw) {
                 Ok(cookie) => {
                     cookies.set(cookie);
@@ -464,6 +473,77 @@ impl Cookies {
     pub fn iter(&self) -> CookiesIter<'_> {
         self.cookies.iter().chain(self.removed_cookies.iter())
     }
+
+    /// Returns a view over the cookies that signs the values when set and verifies them when read,
+    /// using the given secret key.
+    pub fn signed<'a>(&'a mut self, key: &'a [u8]) -> SignedCookies<'a> {
+        SignedCookies { cookies: self, key }
+    }
+}
+
+/// A view over the cookies that signs the values using HMAC-SHA256 to prevent the client from tampering them.
+///
+/// The signed value has the form `{value}.{signature}`, the signature includes the cookie name
+/// so a value cannot be moved to other cookie.
+pub struct SignedCookies<'a> {
+    cookies: &'a mut Cookies,
+    key: &'a [u8],
+}
+
+fn sign(key: &[u8], name: &str, value: &str) -> String {
+    let signature = sha256::hmac(key, format!("{name}={value}"));
+    signature.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Returns the cookie with the signature appended to its value.
+fn sign_cookie(key: &[u8], cookie: Cookie) -> Cookie {
+    let signature = sign(key, cookie.name(), cookie.value());
+    let value = format!("{}.{signature}", cookie.value());
+    Builder(cookie).value(value).build()
+}
+
+/// Returns the cookie with its original value if the signature is valid.
+fn verify_cookie(key: &[u8], cookie: &Cookie) -> Option<Cookie> {
+    let (value, signature) = cookie.value().rsplit_once('.')?;
+    let expected = sign(key, cookie.name(), value);
+
+    if !constant_time_eq(expected.as_bytes(), signature.as_bytes()) {
+        log::warn!("Invalid signature for cookie `{}`", cookie.name());
+        return None;
+    }
+
+    Some(Builder(cookie.clone()).value(value).build())
+}
+
+impl SignedCookies<'_> {
+    /// Signs and adds the cookie.
+    pub fn set(&mut self, cookie: impl Into<Cookie>) {
+        self.cookies.set(sign_cookie(self.key, cookie.into()));
+    }
+
+    /// Signs the cookie and replaces any cookie with the same name.
+    pub fn replace(&mut self, cookie: impl Into<Cookie>) {
+        let cookie = cookie.into();
+        self.cookies.cookies.retain(|c| c.name() != cookie.name());
+        self.set(cookie)
+    }
+
+    /// Removes the cookie with the given name.
+    pub fn del(&mut self, name: impl AsRef<str>) -> Option<&Cookie> {
+        self.cookies.del(name)
+    }
+
+    /// Returns the cookie with the given name and its original value,
+    /// or `None` if not found or the signature is invalid.
+    pub fn get(&self, name: impl AsRef<str>) -> Option<Cookie> {
+        self.cookies
+            .get(name)
+            .and_then(|c| verify_cookie(self.key, c))
+    }
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
 }
 
 impl<'a> IntoIterator for &'a Cookies {
@@ -535,6 +615,155 @@ impl FromRequest for Cookies {
     }
 }
 
+/// A type parsed from the value of the cookie named [`NamedCookie::NAME`], extracted with [`TypedCookie`].
+pub trait NamedCookie: FromStr {
+    /// The name of the cookie.
+    const NAME: &'static str;
+}
+
+/// Extracts the value of the cookie with the name of `T` and parses it.
+///
+/// Rejects the request with `400 Bad Request` if the cookie is missing or fails to parse,
+/// use `Option<TypedCookie<T>>` for an optional cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypedCookie<T>(pub T);
+
+impl<T> TypedCookie<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+pub enum TypedCookieRejection {
+    Missing(&'static str),
+    Invalid(&'static str),
+}
+
+impl std::error::Error for TypedCookieRejection {}
+
+impl Display for TypedCookieRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypedCookieRejection::Missing(name) => write!(f, "cookie `{name}` not found"),
+            TypedCookieRejection::Invalid(name) => write!(f, "failed to parse cookie `{name}`"),
+        }
+    }
+}
+
+impl IntoResponse for TypedCookieRejection {
+    fn into_response(self) -> Response<Body> {
+        log::warn!("{self}");
+        ErrorResponse::from_error(ErrorStatusCode::BadRequest, self.to_string()).into_response()
+    }
+}
+
+impl<T: NamedCookie> FromRequest for TypedCookie<T> {
+    type Rejection = TypedCookieRejection;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        _payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let cookies = Cookies::from_headers(req.headers()).unwrap_or_default();
+        let cookie = cookies
+            .get(T::NAME)
+            .ok_or(TypedCookieRejection::Missing(T::NAME))?;
+
+        T::from_str(cookie.value())
+            .map(TypedCookie)
+            .map_err(|_| TypedCookieRejection::Invalid(T::NAME))
+    }
+}
+
+/// The cookies of the request that tracks the changes made by the handler.
+///
+/// The `Set-Cookie` headers of the changed cookies are added to the response after the handler runs,
+/// so the cookies don't need to be returned with the response.
+#[derive(Debug, Clone)]
+pub struct CookieJar {
+    cookies: Cookies,
+    response_headers: ResponseHeaders,
+}
+
+impl CookieJar {
+    /// Returns the cookie with the given name, including the changes made with this jar.
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&Cookie> {
+        self.cookies.get(name)
+    }
+
+    /// Returns an iterator over the current cookies.
+    pub fn iter(&self) -> std::slice::Iter<'_, Cookie> {
+        self.cookies.cookies.iter()
+    }
+
+    /// Sets the cookie, replacing the one with the same name.
+    pub fn set(&mut self, cookie: impl Into<Cookie>) {
+        let cookie = cookie.into();
+        self.response_headers.set_cookie(&cookie);
+        self.cookies.replace(cookie);
+    }
+
+    /// Removes the cookie with the given name from the client by setting an expired cookie.
+    ///
+    /// The browser only removes the cookie if the path and domain also match, the removed cookie has the path `/`,
+    /// use [`CookieJar::set`] with an expired cookie to remove a cookie with other path.
+    pub fn remove(&mut self, name: impl AsRef<str>) {
+        let name = name.as_ref();
+        let cookie = Cookie::new(name, "").expires(DateTime::UNIX_EPOCH).build();
+
+        self.response_headers.set_cookie(&cookie);
+        self.cookies.cookies.retain(|c| c.name() != name);
+    }
+
+    /// Returns a view over the jar that signs the values when set and verifies them when read,
+    /// using the given secret key.
+    pub fn signed<'a>(&'a mut self, key: &'a [u8]) -> SignedCookieJar<'a> {
+        SignedCookieJar { jar: self, key }
+    }
+}
+
+/// A view over a [`CookieJar`] that signs the cookies like [`SignedCookies`].
+pub struct SignedCookieJar<'a> {
+    jar: &'a mut CookieJar,
+    key: &'a [u8],
+}
+
+impl SignedCookieJar<'_> {
+    /// Signs the cookie and replaces the one with the same name.
+    pub fn set(&mut self, cookie: impl Into<Cookie>) {
+        self.jar.set(sign_cookie(self.key, cookie.into()));
+    }
+
+    /// Removes the cookie with the given name from the client.
+    pub fn remove(&mut self, name: impl AsRef<str>) {
+        self.jar.remove(name);
+    }
+
+    /// Returns the cookie with the given name and its original value,
+    /// or `None` if not found or the signature is invalid.
+    pub fn get(&self, name: impl AsRef<str>) -> Option<Cookie> {
+        self.jar.get(name).and_then(|c| verify_cookie(self.key, c))
+    }
+}
+
+impl FromRequest for CookieJar {
+    type Rejection = Infallible;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let cookies = Cookies::from_headers(req.headers())?;
+        let response_headers = ResponseHeaders::from_request(req, payload)?;
+
+        Ok(CookieJar {
+            cookies,
+            response_headers,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -721,6 +950,47 @@ mod cookies_tests {
         assert!(all_cookies.contains(&&cookie2));
     }
 
+    #[test]
+    fn should_verify_signed_cookies() {
+        const KEY: &[u8] = b"super secret key";
+
+        let mut cookies = Cookies::new();
+        cookies
+            .signed(KEY)
+            .set(Builder::new("session_id", "abc123").http_only(true));
+
+        let signed = cookies.get("session_id").unwrap().clone();
+        assert!(signed.value().starts_with("abc123."));
+        assert!(signed.is_http_only());
+
+        let verified = cookies.signed(KEY).get("session_id").unwrap();
+        assert_eq!(verified.value(), "abc123");
+        assert!(cookies.signed(b"other key").get("session_id").is_none());
+
+        // Parse the signed cookie as sent by the client
+        let raw = format!("session_id={}", signed.value());
+        let mut cookies = Cookies::new();
+        cookies.set(Cookie::from_str(&raw).unwrap());
+        assert_eq!(
+            cookies.signed(KEY).get("session_id").unwrap().value(),
+            "abc123"
+        );
+
+        // Flip one byte of the value
+        let mut tampered = signed.value().as_bytes().to_vec();
+        tampered[0] ^= 0x01;
+        let tampered = String::from_utf8(tampered).unwrap();
+
+        let mut cookies = Cookies::new();
+        cookies.set(Builder::new("session_id", tampered));
+        assert!(cookies.signed(KEY).get("session_id").is_none());
+
+        // Moved to other cookie
+        let mut cookies = Cookies::new();
+        cookies.set(Builder::new("user_id", signed.value()));
+        assert!(cookies.signed(KEY).get("user_id").is_none());
+    }
+
     #[test]
     fn should_iterate_over_all_cookies() {
         let mut cookies = Cookies::new();
@@ -736,3 +1006,143 @@ mod cookies_tests {
         assert!(iter.next().is_none());
     }
 }
+
+#[cfg(test)]
+mod extractor_tests {
+    use std::str::FromStr;
+
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        handler::RequestHandler,
+        headers,
+        request::Request,
+        status::StatusCode,
+    };
+
+    use crate::app::App;
+
+    use super::{Cookie, CookieJar, Cookies, NamedCookie, TypedCookie};
+
+    struct Theme(String);
+
+    impl FromStr for Theme {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "light" | "dark" => Ok(Theme(s.to_owned())),
+                _ => Err(()),
+            }
+        }
+    }
+
+    impl NamedCookie for Theme {
+        const NAME: &'static str = "theme";
+    }
+
+    fn get(app: &App, path: &str, cookie: Option<&str>) -> http1::response::Response<Body> {
+        let mut builder = Request::builder().uri(path);
+
+        if let Some(cookie) = cookie {
+            builder = builder.insert_header(headers::COOKIE, cookie.to_owned());
+        }
+
+        app.handle(builder.body(Body::empty()).unwrap())
+    }
+
+    #[test]
+    fn should_parse_multiple_cookies() {
+        let req = Request::builder()
+            .insert_header(headers::COOKIE, "session=abc123; theme=dark;lang=en")
+            .append_header(headers::COOKIE, "count=2")
+            .body(())
+            .unwrap();
+
+        let cookies = Cookies::from_headers(req.headers()).unwrap();
+        assert_eq!(cookies.len(), 4);
+        assert_eq!(cookies.get("session").unwrap().value(), "abc123");
+        assert_eq!(cookies.get("theme").unwrap().value(), "dark");
+        assert_eq!(cookies.get("lang").unwrap().value(), "en");
+        assert_eq!(cookies.get("count").unwrap().value(), "2");
+    }
+
+    #[test]
+    fn should_extract_typed_cookie() {
+        let app = App::new()
+            .get("/", |TypedCookie(theme): TypedCookie<Theme>| theme.0)
+            .get("/optional", |theme: Option<TypedCookie<Theme>>| {
+                theme.map(|x| x.0 .0).unwrap_or_else(|| "light".to_owned())
+            });
+
+        let res = get(&app, "/", Some("session=abc123; theme=dark"));
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"dark");
+
+        let res = get(&app, "/", Some("theme=blue"));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = get(&app, "/", None);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = get(&app, "/optional", None);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"light");
+    }
+
+    #[test]
+    fn should_emit_changed_cookies_from_jar() {
+        let app = App::new().get("/", |mut jar: CookieJar| {
+            let visits = jar
+                .get("visits")
+                .and_then(|x| x.value().parse::<u32>().ok())
+                .unwrap_or(0);
+
+            jar.set(Cookie::new("visits", "0"));
+            jar.set(Cookie::new("visits", (visits + 1).to_string()));
+            jar.remove("session");
+
+            assert_eq!(jar.get("visits").unwrap().value(), (visits + 1).to_string());
+            assert!(jar.get("session").is_none());
+            "ok"
+        });
+
+        let res = get(&app, "/", Some("visits=2; session=abc123; theme=dark"));
+        let set_cookies = res
+            .headers()
+            .get_all(headers::SET_COOKIE)
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+
+        // Only the last value of a cookie is sent and the unchanged cookies are not sent
+        assert_eq!(set_cookies.len(), 2);
+        assert_eq!(set_cookies[0], "visits=3; Path=/");
+        assert_eq!(
+            set_cookies[1],
+            "session=; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn should_sign_and_verify_cookies_from_jar() {
+        const KEY: &[u8] = b"super secret key";
+
+        let app = App::new().get("/", |mut jar: CookieJar| {
+            let mut signed = jar.signed(KEY);
+            let user = signed.get("user").map(|x| x.value().to_owned());
+            signed.set(Cookie::new("user", "Ayaka"));
+            user.unwrap_or_default()
+        });
+
+        let res = get(&app, "/", None);
+        let set_cookie = res.headers().get(headers::SET_COOKIE).unwrap().to_string();
+        let signed = set_cookie.parse::<Cookie>().unwrap();
+        assert!(signed.value().starts_with("Ayaka."));
+
+        // The signed cookie sent by the client is verified
+        let cookie = format!("user={}", signed.value());
+        let res = get(&app, "/", Some(&cookie));
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Ayaka");
+
+        // Tampered value
+        let res = get(&app, "/", Some(&cookie.replace("Ayaka", "Ruby")));
+        assert!(res.into_body().read_all_bytes().unwrap().is_empty());
+    }
+}
@@ -1,19 +1,23 @@
 use std::fmt::Display;
 
 use http1::{
-    body::http_body::HttpBody,
+    body::http_body::{CollectError, HttpBody},
     error::BoxError,
     headers::{self, HeaderValue},
     response::Response,
     status::StatusCode,
-    uri::{
-        path_query::{QueryMap, QueryValue},
-        uri::InvalidUri,
-        url_encoding::{self, InvalidUriComponent},
-    },
+    uri::{path_query::QueryValue, uri::InvalidUri, url_encoding::InvalidUriComponent},
 };
 
-use crate::{from_request::FromRequest, query::QueryDeserializer, IntoResponse};
+use crate::{
+    from_request::{body_size_limit, FromRequest},
+    IntoResponse,
+};
+
+use super::{
+    nested::NestedFormDeserializer,
+    urlencoded::{self, WWW_FORM_URLENCODED},
+};
 use serde::{
     de::Deserialize,
     impossible::Impossible,
@@ -32,14 +36,13 @@ impl<T> Form<T> {
     }
 }
 
-const WWW_FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
-
 #[doc(hidden)]
 #[derive(Debug)]
 pub enum RejectFormError {
     NoContentType,
     InvalidContentType(String),
     FailedReadForm(BoxError),
+    PayloadTooLarge,
     Utf8Error(BoxError),
     InvalidUriComponent(InvalidUriComponent),
     InvalidUri(InvalidUri),
@@ -59,6 +62,7 @@ impl Display for RejectFormError {
                 )
             }
             RejectFormError::FailedReadForm(error) => write!(f, "Failed to read form: {error}"),
+            RejectFormError::PayloadTooLarge => write!(f, "Form body is too large"),
             RejectFormError::Utf8Error(error) => write!(f, "Failed to read uf8 form {error}"),
             RejectFormError::InvalidUriComponent(_) => write!(f, "Failed to decode form"),
             RejectFormError::InvalidUri(invalid_uri) => {
@@ -74,7 +78,10 @@ impl Display for RejectFormError {
 impl IntoResponse for RejectFormError {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
         log::error!("{self}");
-        StatusCode::UNPROCESSABLE_CONTENT.into_response()
+        match self {
+            RejectFormError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+            _ => StatusCode::UNPROCESSABLE_CONTENT.into_response(),
+        }
     }
 }
 
@@ -101,17 +108,22 @@ impl<T: Deserialize> FromRequest for Form<T> {
                 let bytes = payload
                     .take()
                     .unwrap_or_default()
-                    .read_all_bytes()
-                    .map_err(RejectFormError::FailedReadForm)?;
+                    .collect(body_size_limit(req))
+                    .map_err(|err| match err {
+                        CollectError::LimitExceeded => RejectFormError::PayloadTooLarge,
+                        CollectError::Other(err) => RejectFormError::FailedReadForm(err),
+                    })?;
 
                 let s =
                     String::from_utf8(bytes).map_err(|e| RejectFormError::Utf8Error(e.into()))?;
 
-                let q = url_encoding::decode(&s).map_err(RejectFormError::InvalidUriComponent)?;
+                let query_map =
+                    urlencoded::from_str(&s).map_err(RejectFormError::InvalidUriComponent)?;
 
-                let query_map = QueryMap::from_query_str(&q);
+                let deserializer = NestedFormDeserializer::new(query_map)
+                    .map_err(|e| RejectFormError::DeserializationError(e.into()))?;
 
-                T::deserialize(QueryDeserializer(query_map))
+                T::deserialize(deserializer)
                     .map(Form)
                     .map_err(|e| RejectFormError::DeserializationError(e.into()))
             }
@@ -188,8 +200,16 @@ impl<T: Serialize> IntoResponse for Form<T> {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
         match self.0.serialize(FormSerializer) {
             Ok(map) => {
-                let s = QueryMap::new(map).to_string();
-                let body = s.into();
+                let pairs = map.iter().flat_map(|(key, value)| {
+                    let values = match value {
+                        QueryValue::One(x) => std::slice::from_ref(x),
+                        QueryValue::List(list) => list.as_slice(),
+                    };
+
+                    values.iter().map(move |x| (key, x))
+                });
+
+                let body = urlencoded::to_string(pairs).into();
                 Response::builder()
                     .append_header(
                         headers::CONTENT_TYPE,
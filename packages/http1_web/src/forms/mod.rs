@@ -4,5 +4,7 @@ pub mod form_field;
 pub mod form_map;
 pub mod multipart;
 pub mod one_or_many;
+pub mod urlencoded;
 
+mod nested;
 mod stream_reader;
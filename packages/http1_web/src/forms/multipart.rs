@@ -729,3 +729,93 @@ impl<R: std::io::Read> MapAccess for FormFieldAccess<R> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::http_body::HttpBody,
+        client::{Client, MultipartBuilder},
+        headers,
+        payload::Payload,
+        request::Request,
+        server::Server,
+        status::StatusCode,
+    };
+
+    use crate::{app::App, from_request::FromRequest};
+
+    use super::{FormEntry, Multipart};
+
+    struct Upload {
+        title: FormEntry,
+        image: FormEntry,
+    }
+
+    serde::impl_deserialize_struct!(Upload => {
+        title: FormEntry,
+        image: FormEntry
+    });
+
+    #[test]
+    fn should_read_multipart_built_by_client() {
+        let (content_type, body) = MultipartBuilder::new()
+            .text("title", "My cat")
+            .file(
+                "image",
+                "cat.png",
+                "image/png",
+                vec![0x89, b'P', b'N', b'G', 0, 1],
+            )
+            .build();
+
+        let req = Request::builder()
+            .insert_header(headers::CONTENT_TYPE, content_type)
+            .body(())
+            .unwrap();
+
+        let Multipart(upload) =
+            Multipart::<Upload>::from_request(&req, &mut Payload::Data(body)).unwrap();
+
+        assert_eq!(upload.title.text().unwrap(), "My cat");
+        assert_eq!(upload.title.filename(), None);
+        assert_eq!(upload.image.name(), "image");
+        assert_eq!(upload.image.filename(), Some("cat.png"));
+        assert_eq!(upload.image.content_type(), Some("image/png"));
+        assert_eq!(
+            upload.image.bytes().unwrap(),
+            vec![0x89, b'P', b'N', b'G', 0, 1]
+        );
+    }
+
+    #[test]
+    fn should_upload_multipart_form_with_client() {
+        let app = App::new().post("/upload", |Multipart(upload): Multipart<Upload>| {
+            let title = upload.title.text().unwrap();
+            let filename = upload.image.filename().unwrap().to_owned();
+            let size = upload.image.bytes().unwrap().len();
+            format!("{title} {filename} {size}")
+        });
+
+        Server::new()
+            .run_scoped(app, |addr| {
+                let form = MultipartBuilder::new().text("title", "My cat").file(
+                    "image",
+                    "cat.png",
+                    "image/png",
+                    vec![1; 2048],
+                );
+
+                let res = Client::new()
+                    .post(format!("http://{addr}/upload"))
+                    .multipart(form)
+                    .unwrap();
+
+                assert_eq!(res.status(), StatusCode::OK);
+                assert_eq!(
+                    res.into_body().read_all_bytes().unwrap(),
+                    b"My cat cat.png 2048"
+                );
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+use http1::uri::path_query::{QueryMap, QueryValue};
+use orderedmap::OrderedMap;
+use serde::{
+    de::{Deserialize, Deserializer, Error},
+    forward_to_deserialize_any,
+    string::{DeserializeFromStr, DeserializeOnlyString},
+    visitor::{MapAccess, Visitor},
+};
+
+/// A form field, the fields using bracket notation like `user[name]` are grouped into a map.
+enum FormNode {
+    Value(QueryValue),
+    Map(OrderedMap<String, FormNode>),
+}
+
+/// Deserializes a form where keys like `user[name]=Bob&user[age]=3` are nested structs.
+pub(crate) struct NestedFormDeserializer(OrderedMap<String, FormNode>);
+
+impl NestedFormDeserializer {
+    /// Groups the fields of the query map, fails if a field is used both as a value and as a nested map.
+    pub fn new(query_map: QueryMap) -> Result<Self, Error> {
+        let mut root = OrderedMap::new();
+
+        for (key, value) in query_map {
+            match parse_key(&key) {
+                Some(path) => insert_node(&mut root, &key, &path, value)?,
+                None => insert_node(&mut root, &key, &[key.as_str()], value)?,
+            }
+        }
+
+        Ok(NestedFormDeserializer(root))
+    }
+}
+
+/// Splits a key like `user[address][city]` into `["user", "address", "city"]`.
+fn parse_key(key: &str) -> Option<Vec<&str>> {
+    let (head, mut rest) = key.split_once('[')?;
+    let mut path = vec![head];
+
+    loop {
+        let (segment, after) = rest.split_once(']')?;
+        path.push(segment);
+
+        if after.is_empty() {
+            break;
+        }
+
+        rest = after.strip_prefix('[')?;
+    }
+
+    if path.iter().any(|x| x.is_empty()) {
+        return None;
+    }
+
+    Some(path)
+}
+
+fn insert_node(
+    map: &mut OrderedMap<String, FormNode>,
+    key: &str,
+    path: &[&str],
+    value: QueryValue,
+) -> Result<(), Error> {
+    let conflict = || Error::other(format!("conflicting form field `{key}`"));
+    let (name, rest) = path.split_first().expect("form field path cannot be empty");
+
+    if rest.is_empty() {
+        if map.contains_key(*name) {
+            return Err(conflict());
+        }
+
+        map.insert(name.to_string(), FormNode::Value(value));
+        return Ok(());
+    }
+
+    if !map.contains_key(*name) {
+        map.insert(name.to_string(), FormNode::Map(OrderedMap::new()));
+    }
+
+    match map.get_mut(*name) {
+        Some(FormNode::Map(inner)) => insert_node(inner, key, rest, value),
+        _ => Err(conflict()),
+    }
+}
+
+impl Deserializer for NestedFormDeserializer {
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        visitor.visit_map(NestedFormMapAccess {
+            iter: self.0.into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        Err(Error::other("cannot deserialize form to `unit`"))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        visitor.visit_some(self)
+    }
+
+    forward_to_deserialize_any!(
+        deserialize_bool,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_string,
+        deserialize_seq,
+        deserialize_bytes_seq,
+        deserialize_bytes_buf,
+        deserialize_map
+    );
+}
+
+struct NestedFormMapAccess<I> {
+    iter: I,
+    value: Option<FormNode>,
+}
+
+impl<I: Iterator<Item = (String, FormNode)>> MapAccess for NestedFormMapAccess<I> {
+    fn next_key<K: Deserialize>(&mut self) -> Result<Option<K>, Error> {
+        match self.iter.next() {
+            Some((k, v)) => {
+                self.value = Some(v);
+                let key = K::deserialize(DeserializeOnlyString(k))?;
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value<V: Deserialize>(&mut self) -> Result<Option<V>, Error> {
+        let value = match self.value.take() {
+            Some(FormNode::Value(QueryValue::One(val))) => {
+                V::deserialize(DeserializeFromStr::Str(val))?
+            }
+            Some(FormNode::Value(QueryValue::List(vec))) => {
+                V::deserialize(DeserializeFromStr::List(vec))?
+            }
+            Some(FormNode::Map(map)) => V::deserialize(NestedFormDeserializer(map))?,
+            None => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+}
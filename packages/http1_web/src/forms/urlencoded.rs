@@ -0,0 +1,164 @@
+use http1::{
+    body::Body,
+    headers::HeaderValue,
+    uri::{
+        path_query::QueryMap,
+        url_encoding::{self, InvalidUriComponent},
+    },
+};
+use orderedmap::OrderedMap;
+
+/// Mime type of the `application/x-www-form-urlencoded` forms.
+pub const WWW_FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+
+/// Encodes the given key-value pairs as an `application/x-www-form-urlencoded` string.
+pub fn to_string<I, K, V>(pairs: I) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut s = String::new();
+
+    for (idx, (key, value)) in pairs.into_iter().enumerate() {
+        if idx > 0 {
+            s.push('&');
+        }
+
+        s.push_str(&encode(key.as_ref()));
+        s.push('=');
+        s.push_str(&encode(value.as_ref()));
+    }
+
+    s
+}
+
+/// Encodes the given key-value pairs as a body, returns the `Content-Type` to send along with it.
+pub fn to_body<I, K, V>(pairs: I) -> (HeaderValue, Body)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let content_type = HeaderValue::from_static(WWW_FORM_URLENCODED);
+    (content_type, to_string(pairs).into())
+}
+
+/// Decodes an `application/x-www-form-urlencoded` string.
+pub fn from_str(s: &str) -> Result<QueryMap, InvalidUriComponent> {
+    let mut map = QueryMap::new(OrderedMap::new());
+
+    for pair in s.split('&').filter(|x| !x.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = url_encoding::decode(key)?;
+        let value = url_encoding::decode(value)?;
+        map.append(key, value);
+    }
+
+    Ok(map)
+}
+
+fn encode(s: &str) -> String {
+    url_encoding::percent_encode(s, url_encoding::FormASCII)
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{body::http_body::HttpBody, headers, payload::Payload, request::Request};
+
+    use crate::{forms::form::Form, from_request::FromRequest};
+
+    use super::{from_str, to_body, to_string};
+
+    #[test]
+    fn should_encode_pairs() {
+        let s = to_string([("name", "Hoshino Ai"), ("q", "a&b=c/d?"), ("emoji", "ñ")]);
+        assert_eq!(s, "name=Hoshino+Ai&q=a%26b%3Dc%2Fd%3F&emoji=%C3%B1");
+    }
+
+    #[test]
+    fn should_decode_encoded_pairs() {
+        let map = from_str(&to_string([("a b", "1+1=2"), ("a b", "x&y")])).unwrap();
+        let values = map.get_all("a b").collect::<Vec<_>>();
+        assert_eq!(values, vec!["1+1=2", "x&y"]);
+    }
+
+    #[test]
+    fn should_decode_plus_as_space_and_reject_invalid_encoding() {
+        let map = from_str("q=a+b%2Bc%2Fd").unwrap();
+        assert_eq!(map.get("q"), Some("a b+c/d"));
+
+        assert!(from_str("q=%ZZ").is_err());
+    }
+
+    #[test]
+    fn should_round_trip_through_form_extractor() {
+        struct Person {
+            name: String,
+            bio: String,
+        }
+
+        serde::impl_deserialize_struct!(Person => {
+            name: String,
+            bio: String
+        });
+
+        let (content_type, mut body) =
+            to_body([("name", "Hoshino Ai"), ("bio", "100% idol & mother = 16?")]);
+
+        let req = Request::builder()
+            .insert_header(headers::CONTENT_TYPE, content_type)
+            .body(())
+            .unwrap();
+
+        let bytes = body.read_all_bytes().unwrap();
+        let Form(person) =
+            Form::<Person>::from_request(&req, &mut Payload::Data(bytes.into())).unwrap();
+
+        assert_eq!(person.name, "Hoshino Ai");
+        assert_eq!(person.bio, "100% idol & mother = 16?");
+    }
+
+    #[test]
+    fn should_deserialize_nested_struct_from_bracket_keys() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct User {
+            name: String,
+            age: u32,
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Signup {
+            user: User,
+            plan: String,
+        }
+
+        serde::impl_deserialize_struct!(User => { name: String, age: u32 });
+        serde::impl_deserialize_struct!(Signup => { user: User, plan: String });
+
+        let req = Request::builder()
+            .insert_header(headers::CONTENT_TYPE, super::WWW_FORM_URLENCODED)
+            .body(())
+            .unwrap();
+
+        let body = "user[name]=Bob&plan=free&user[age]=3";
+        let Form(signup) =
+            Form::<Signup>::from_request(&req, &mut Payload::Data(body.into())).unwrap();
+
+        assert_eq!(
+            signup,
+            Signup {
+                user: User {
+                    name: String::from("Bob"),
+                    age: 3
+                },
+                plan: String::from("free")
+            }
+        );
+
+        // A field cannot be a value and a nested struct
+        let body = "user=Bob&user[age]=3&plan=free";
+        let result = Form::<Signup>::from_request(&req, &mut Payload::Data(body.into()));
+        assert!(result.is_err());
+    }
+}
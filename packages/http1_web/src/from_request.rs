@@ -1,8 +1,10 @@
 use std::{convert::Infallible, fmt::Display};
 
 use http1::{
-    body::{http_body::HttpBody, Body},
-    error::BoxError,
+    body::{
+        http_body::{CollectError, HttpBody},
+        Body,
+    },
     extensions::Extensions,
     headers::Headers,
     method::Method,
@@ -109,9 +111,17 @@ impl FromRequest for Extensions {
     }
 }
 
+/// Returns the max size in bytes of the request body, the limit is only known if the server config is included in the request.
+pub(crate) fn body_size_limit<T>(req: &Request<T>) -> usize {
+    req.extensions()
+        .get::<http1::server::Config>()
+        .and_then(|config| config.max_body_size)
+        .unwrap_or(usize::MAX)
+}
+
 #[doc(hidden)]
 #[derive(Debug)]
-pub struct InvalidBodyError(BoxError);
+pub struct InvalidBodyError(CollectError);
 
 impl Display for InvalidBodyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -122,18 +132,44 @@ impl Display for InvalidBodyError {
 impl IntoResponse for InvalidBodyError {
     fn into_response(self) -> Response<Body> {
         log::error!("{self}");
-        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        match self.0 {
+            CollectError::LimitExceeded => StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+            CollectError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
     }
 }
 
+/// Combines the rejections of the fields extracted by `impl_from_request!` in a single `400 Bad Request` response.
+#[doc(hidden)]
+pub fn rejections_into_response(rejections: Vec<(&'static str, Response<Body>)>) -> Response<Body> {
+    let mut message = String::new();
+
+    for (field, response) in rejections {
+        let status = response.status();
+        let bytes = response.into_body().read_all_bytes().unwrap_or_default();
+        let reason = String::from_utf8_lossy(&bytes);
+        let reason = reason.trim();
+
+        if reason.is_empty() {
+            message.push_str(&format!("{field}: {status}\n"));
+        } else {
+            message.push_str(&format!("{field}: {reason}\n"));
+        }
+    }
+
+    let mut response = message.into_response();
+    *response.status_mut() = StatusCode::BAD_REQUEST;
+    response
+}
+
 impl FromRequest for Vec<u8> {
     type Rejection = InvalidBodyError;
 
-    fn from_request(_req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+    fn from_request(req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
         payload
             .take()
             .unwrap_or_default()
-            .read_all_bytes()
+            .collect(body_size_limit(req))
             .map_err(InvalidBodyError)
     }
 }
@@ -141,13 +177,14 @@ impl FromRequest for Vec<u8> {
 impl FromRequest for String {
     type Rejection = InvalidBodyError;
 
-    fn from_request(_req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
-        payload
+    fn from_request(req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        let bytes = payload
             .take()
             .unwrap_or_default()
-            .read_all_bytes()
-            .and_then(|bytes| Ok(String::from_utf8(bytes)?))
-            .map_err(InvalidBodyError)
+            .collect(body_size_limit(req))
+            .map_err(InvalidBodyError)?;
+
+        String::from_utf8(bytes).map_err(|err| InvalidBodyError(CollectError::Other(err.into())))
     }
 }
 
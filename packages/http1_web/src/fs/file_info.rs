@@ -0,0 +1,73 @@
+use std::time::UNIX_EPOCH;
+
+use datetime::DateTime;
+use http1::headers::{self, HeaderValue, Headers};
+
+/// The metadata used to validate cached files.
+pub(crate) struct FileInfo {
+    pub len: u64,
+    pub last_modified: Option<DateTime>,
+}
+
+impl FileInfo {
+    pub fn new(metadata: &std::fs::Metadata) -> Self {
+        // `Last-Modified` only have seconds precision
+        let last_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .map(|x| DateTime::with_millis(x.as_secs() as u128 * 1000));
+
+        FileInfo {
+            len: metadata.len(),
+            last_modified,
+        }
+    }
+
+    /// Returns a weak `ETag` computed from the file size and modification time.
+    pub fn etag(&self) -> String {
+        let millis = self
+            .last_modified
+            .map(|x| x.as_millis())
+            .unwrap_or_default();
+        format!("W/\"{:x}-{:x}\"", millis, self.len)
+    }
+
+    /// Whether the client cached version is still valid, using `If-None-Match` or `If-Modified-Since`.
+    pub fn is_not_modified(&self, req_headers: &Headers) -> bool {
+        // `If-None-Match` takes precedence over `If-Modified-Since`
+        if let Some(if_none_match) = req_headers.get(headers::IF_NONE_MATCH) {
+            // Weak comparison, the `W/` prefix is ignored on both sides
+            let etag = self.etag();
+            let etag = etag.strip_prefix("W/").unwrap_or(&etag);
+
+            return if_none_match
+                .as_str()
+                .split(',')
+                .map(|x| x.trim())
+                .map(|x| x.strip_prefix("W/").unwrap_or(x))
+                .any(|x| x == "*" || x == etag);
+        }
+
+        let if_modified_since = req_headers
+            .get(headers::IF_MODIFIED_SINCE)
+            .and_then(|x| DateTime::parse_http_date(x.as_str()).ok());
+
+        match (if_modified_since, self.last_modified) {
+            (Some(since), Some(last_modified)) => last_modified <= since,
+            _ => false,
+        }
+    }
+
+    /// Adds the `ETag` and `Last-Modified` headers.
+    pub fn write_headers(&self, headers: &mut Headers) {
+        headers.insert(headers::ETAG, HeaderValue::from_string(self.etag()));
+
+        if let Some(last_modified) = self.last_modified {
+            headers.insert(
+                headers::LAST_MODIFIED,
+                HeaderValue::from_string(last_modified.to_rfc_1123_string()),
+            );
+        }
+    }
+}
@@ -1,5 +1,9 @@
+mod file_info;
+mod range;
+mod safe_path;
 mod serve_dir;
 mod serve_file;
 
+pub use safe_path::{SafePath, UnsafePathError};
 pub use serve_dir::ServeDir;
 pub use serve_file::{InvalidFile, ServeFile};
@@ -0,0 +1,123 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+};
+
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue, Headers},
+    response::Response,
+    status::StatusCode,
+};
+
+use crate::IntoResponse;
+
+/// The part of a file requested using the `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ByteRange {
+    /// The whole file.
+    Full,
+
+    /// The bytes from `start` to `end`, inclusive.
+    Partial { start: u64, end: u64 },
+
+    /// The range is outside the file.
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Gets the range requested for a file with the given length.
+    ///
+    /// Invalid or multiple ranges are ignored and the whole file is returned.
+    pub fn from_headers(headers: &Headers, len: u64) -> Self {
+        let Some(value) = headers.get(headers::RANGE) else {
+            return ByteRange::Full;
+        };
+
+        let Some(range) = value.as_str().trim().strip_prefix("bytes=") else {
+            return ByteRange::Full;
+        };
+
+        if range.contains(',') {
+            return ByteRange::Full;
+        }
+
+        let Some((start, end)) = range.split_once('-') else {
+            return ByteRange::Full;
+        };
+
+        let (start, end) = match (start.trim(), end.trim()) {
+            // bytes=-500, the last bytes of the file
+            ("", suffix) => match suffix.parse::<u64>() {
+                Ok(0) => return ByteRange::Unsatisfiable,
+                Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+                Err(_) => return ByteRange::Full,
+            },
+            // bytes=500-
+            (start, "") => match start.parse::<u64>() {
+                Ok(start) => (start, len.saturating_sub(1)),
+                Err(_) => return ByteRange::Full,
+            },
+            // bytes=500-999
+            (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+                (Ok(start), Ok(end)) if start <= end => (start, end.min(len.saturating_sub(1))),
+                _ => return ByteRange::Full,
+            },
+        };
+
+        if start >= len {
+            return ByteRange::Unsatisfiable;
+        }
+
+        ByteRange::Partial { start, end }
+    }
+}
+
+/// Creates a response with the bytes of the file in the given range.
+pub(crate) fn file_range_response(
+    mut file: File,
+    len: u64,
+    range: ByteRange,
+    include_body: bool,
+) -> Response<Body> {
+    let (status, start, count) = match range {
+        ByteRange::Full => (StatusCode::OK, 0, len),
+        ByteRange::Partial { start, end } => (StatusCode::PARTIAL_CONTENT, start, end - start + 1),
+        ByteRange::Unsatisfiable => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .insert_header(headers::ACCEPT_RANGES, HeaderValue::from_static("bytes"))
+                .insert_header(
+                    headers::CONTENT_RANGE,
+                    HeaderValue::from_string(format!("bytes */{len}")),
+                )
+                .body(Body::empty());
+        }
+    };
+
+    let body = if include_body {
+        if let Err(err) = file.seek(SeekFrom::Start(start)) {
+            log::error!("Failed to seek file: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+
+        Body::new(BufReader::new(file).take(count))
+    } else {
+        Body::empty()
+    };
+
+    let mut builder = Response::builder()
+        .status(status)
+        .insert_header(headers::ACCEPT_RANGES, HeaderValue::from_static("bytes"))
+        .insert_header(headers::CONTENT_LENGTH, HeaderValue::from(count));
+
+    if status == StatusCode::PARTIAL_CONTENT {
+        let end = start + count - 1;
+        builder = builder.insert_header(
+            headers::CONTENT_RANGE,
+            HeaderValue::from_string(format!("bytes {start}-{end}/{len}")),
+        );
+    }
+
+    builder.body(body)
+}
@@ -0,0 +1,117 @@
+use std::{
+    fmt::Display,
+    ops::Deref,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
+
+/// A relative path that cannot escape the directory it is joined to.
+///
+/// Absolute paths and `..` components are rejected, use it for paths coming from untrusted input
+/// like a requested file name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SafePath(PathBuf);
+
+impl SafePath {
+    /// Constructs a `SafePath`, fails if the path is absolute or contains `..` components.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, UnsafePathError> {
+        let path = path.into();
+
+        for component in path.components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir => {
+                    return Err(UnsafePathError::Absolute(path));
+                }
+                Component::ParentDir => return Err(UnsafePathError::ParentDir(path)),
+                Component::CurDir | Component::Normal(_) => {}
+            }
+        }
+
+        Ok(SafePath(path))
+    }
+
+    /// Returns the path.
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Returns the underlying path.
+    pub fn into_inner(self) -> PathBuf {
+        self.0
+    }
+}
+
+impl Deref for SafePath {
+    type Target = Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for SafePath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// Error returned when a path can escape its base directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsafePathError {
+    /// The path is absolute.
+    Absolute(PathBuf),
+
+    /// The path contains a `..` component.
+    ParentDir(PathBuf),
+}
+
+impl Display for UnsafePathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsafePathError::Absolute(path) => {
+                write!(f, "absolute paths are not allowed: `{}`", path.display())
+            }
+            UnsafePathError::ParentDir(path) => {
+                write!(f, "`..` is not allowed in paths: `{}`", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnsafePathError {}
+
+impl Deserialize for SafePath {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, serde::de::Error> {
+        let path = String::deserialize(deserializer)?;
+        SafePath::new(path).map_err(serde::de::Error::other)
+    }
+}
+
+impl Serialize for SafePath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Err> {
+        serializer.serialize_str(&self.0.to_string_lossy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::SafePath;
+
+    #[test]
+    fn should_deserialize_safe_paths() {
+        let path = serde::json::from_str::<SafePath>(r#""reports/2024.pdf""#).unwrap();
+        assert_eq!(path.as_path(), Path::new("reports/2024.pdf"));
+
+        let path = serde::json::from_str::<SafePath>(r#""./reports/../2024.pdf""#);
+        assert!(path.is_err());
+
+        assert!(serde::json::from_str::<SafePath>(r#""../secret""#).is_err());
+        assert!(serde::json::from_str::<SafePath>(r#""/etc/passwd""#).is_err());
+    }
+}
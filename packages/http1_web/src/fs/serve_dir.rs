@@ -1,19 +1,24 @@
 use std::{
     fmt::Debug,
     fs::File,
-    io::BufReader,
     path::{Path, PathBuf},
     str::FromStr,
     sync::{Arc, Mutex},
     time::UNIX_EPOCH,
 };
 
+use super::{
+    file_info::FileInfo,
+    range::{file_range_response, ByteRange},
+};
 use crate::{
     from_request::FromRequest,
     handler::Handler,
+    header::Vary,
     html::{self, element::HTMLElement},
+    middleware::gzip::{negotiate_encoding, Encoding},
     mime::Mime,
-    routing::route_info::RouteInfo,
+    path::{Wildcard, WildcardRejection},
     ErrorResponse, ErrorStatusCode, IntoResponse,
 };
 use datetime::DateTime;
@@ -44,6 +49,7 @@ pub struct ServeDir<F = DefaultServeDirFallback, R = ResolveIndexHtml> {
     root: PathBuf,
     list_directory: bool,
     use_cache_headers: bool,
+    precompressed_gzip: bool,
     fallback: F,
     on_response: Option<OnResponseHandler>,
     resolve_index: Option<R>,
@@ -75,6 +81,7 @@ impl<F> ServeDir<F> {
             root,
             list_directory: false,
             use_cache_headers: false,
+            precompressed_gzip: false,
             on_response: None,
             fallback,
             resolve_index: None,
@@ -103,6 +110,7 @@ impl<F> ServeDir<F> {
             on_response: self.on_response,
             resolve_index: Some(resolve_index),
             use_cache_headers: self.use_cache_headers,
+            precompressed_gzip: self.precompressed_gzip,
         }
     }
 
@@ -118,6 +126,12 @@ impl<F> ServeDir<F> {
         self
     }
 
+    /// Whether if serve the precompressed `<file>.gz` when the client accepts `gzip`. By default is `false`.
+    pub fn precompressed_gzip(mut self, precompressed_gzip: bool) -> Self {
+        self.precompressed_gzip = precompressed_gzip;
+        self
+    }
+
     /// Add a handler that run each time a response is about to be send.
     pub fn on_response<U>(mut self, on_response: U) -> Self
     where
@@ -134,6 +148,7 @@ impl Debug for ServeDir {
             .field("root", &self.root)
             .field("list_directory", &self.list_directory)
             .field("use_cache_headers", &self.use_cache_headers)
+            .field("precompressed_gzip", &self.precompressed_gzip)
             .finish_non_exhaustive()
     }
 }
@@ -151,9 +166,13 @@ where
         }
 
         let (req, mut payload) = req.map_body(Payload::Data).drop_body();
-        let route_info = RouteInfo::from_request(&req, &mut payload).unwrap();
         let req_path = req.uri().path_and_query().path();
-        let route = get_route(route_info, req_path);
+        let route = match Wildcard::from_request(&req, &mut payload) {
+            Ok(wildcard) => wildcard.into_inner(),
+            // Without catch-all the route only matches the directory itself
+            Err(WildcardRejection::NoCatchAll) => String::new(),
+            Err(err) => return err.into_response(),
+        };
         let mut serve_path = self.root.join(&route);
 
         if let Some(index) = &self.resolve_index {
@@ -183,40 +202,80 @@ where
             return self.fallback.call(req);
         }
 
-        log::debug!("serving path: {serve_path:?}");
-
-        match File::open(&serve_path) {
-            Ok(file) => {
-                let reader = BufReader::new(file);
-                let mut res = Response::builder()
-                    .append_header(
-                        headers::CONTENT_TYPE,
-                        HeaderValue::from_string(mime.to_string()),
-                    )
-                    .body(Body::new(reader));
-
-                if self.use_cache_headers {
-                    res.headers_mut().insert(
-                        headers::CACHE_CONTROL,
-                        HeaderValue::from_static("public, max-age=604800, immutable"),
-                    );
-                }
+        // The `.gz` file is sent with the content type of the original file
+        let gzip_path = Some(gzip_path(&serve_path))
+            .filter(|_| {
+                self.precompressed_gzip && negotiate_encoding(req.headers()) == Some(Encoding::Gzip)
+            })
+            .filter(|path| path.is_file());
 
-                if let Some(mtx) = self.on_response.as_ref() {
-                    let mut on_response = mtx.lock().expect("failed to get on_response");
-                    (on_response)(&mut res);
-                }
+        let file_path = gzip_path.as_deref().unwrap_or(&serve_path);
+        log::debug!("serving path: {file_path:?}");
 
-                res
-            }
+        let metadata = match std::fs::metadata(file_path) {
+            Ok(metadata) => metadata,
             Err(err) => {
-                log::error!("Failed to open file: {err}");
-                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                log::error!("Failed to read file metadata: {err}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
             }
+        };
+
+        let file_info = FileInfo::new(&metadata);
+        let mut res = if file_info.is_not_modified(req.headers()) {
+            Response::new(StatusCode::NOT_MODIFIED, Body::empty())
+        } else {
+            match File::open(file_path) {
+                Ok(file) => {
+                    // For HEAD requests we only need the metadata, so the file is not read
+                    let range = ByteRange::from_headers(req.headers(), file_info.len);
+                    let include_body = req.method() != Method::HEAD;
+                    file_range_response(file, file_info.len, range, include_body)
+                }
+                Err(err) => {
+                    log::error!("Failed to open file: {err}");
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            }
+        };
+
+        let headers = res.headers_mut();
+        headers.insert(
+            headers::CONTENT_TYPE,
+            HeaderValue::from_string(mime.to_string()),
+        );
+
+        file_info.write_headers(headers);
+
+        if gzip_path.is_some() {
+            headers.insert(headers::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+        }
+
+        if self.precompressed_gzip {
+            Vary::add_to(headers, headers::ACCEPT_ENCODING);
+        }
+
+        if self.use_cache_headers {
+            headers.insert(
+                headers::CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=604800, immutable"),
+            );
+        }
+
+        if let Some(mtx) = self.on_response.as_ref() {
+            let mut on_response = mtx.lock().expect("failed to get on_response");
+            (on_response)(&mut res);
         }
+
+        res
     }
 }
 
+fn gzip_path(path: &Path) -> PathBuf {
+    let mut gzip_path = path.as_os_str().to_owned();
+    gzip_path.push(".gz");
+    PathBuf::from(gzip_path)
+}
+
 fn list_directory_html(req_path: &str, dir: &Path) -> Result<HTMLElement, ErrorResponse> {
     let read_dir = std::fs::read_dir(dir).map_err(|err| {
         log::error!("Failed to list directory: {err}");
@@ -336,12 +395,6 @@ fn list_directory_html(req_path: &str, dir: &Path) -> Result<HTMLElement, ErrorR
     }))
 }
 
-fn get_route(route_info: RouteInfo, req_path: &str) -> String {
-    let segments_count = route_info.iter().filter(|x| !x.is_catch_all()).count();
-    let segments = crate::routing::route::get_segments(req_path);
-    interspace("/", segments.skip(segments_count)).collect::<String>()
-}
-
 fn interspace<T, I>(separator: T, iter: I) -> impl Iterator<Item = T>
 where
     I: Iterator<Item = T>,
@@ -511,3 +564,278 @@ impl ResolveIndex for ResolveAnyIndex {
         ResolveAnyIndex::resolve_from_types(serve_path, &types)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use datetime::DateTime;
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        common::gzip,
+        headers::{self, HeaderValue},
+        method::Method,
+        request::Request,
+        response::Response,
+        status::StatusCode,
+    };
+
+    use crate::{
+        handler::Handler,
+        routing::{route::Route, route_info::RouteInfo},
+    };
+
+    use super::ServeDir;
+
+    fn send(serve_dir: &ServeDir, method: Method, etag: Option<&HeaderValue>) -> Response<Body> {
+        let mut builder = Request::builder().method(method).uri("/hello.txt");
+
+        if let Some(etag) = etag {
+            builder = builder.insert_header(headers::IF_NONE_MATCH, etag.clone());
+        }
+
+        let mut req = builder.body(Body::empty()).unwrap();
+        req.extensions_mut().insert(RouteInfo(Route::from("/*")));
+        serve_dir.call(req)
+    }
+
+    fn send_range(serve_dir: &ServeDir, range: &'static str) -> Response<Body> {
+        let mut req = Request::builder()
+            .uri("/hello.txt")
+            .insert_header(headers::RANGE, range)
+            .body(Body::empty())
+            .unwrap();
+
+        req.extensions_mut().insert(RouteInfo(Route::from("/*")));
+        serve_dir.call(req)
+    }
+
+    #[test]
+    fn should_return_requested_byte_range() {
+        let dir = std::env::temp_dir().join(format!("serve_dir_range_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+
+        let serve_dir = ServeDir::new(&dir);
+
+        for (range, content_range, expected) in [
+            ("bytes=0-3", "bytes 0-3/12", "Hell"),
+            ("bytes=4-", "bytes 4-11/12", "o World!"),
+            ("bytes=-6", "bytes 6-11/12", "World!"),
+        ] {
+            let res = send_range(&serve_dir, range);
+            assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(
+                res.headers().get(headers::CONTENT_RANGE).unwrap().as_str(),
+                content_range
+            );
+            assert_eq!(
+                res.headers().get(headers::ACCEPT_RANGES).unwrap().as_str(),
+                "bytes"
+            );
+            assert_eq!(
+                res.into_body().read_all_bytes().unwrap(),
+                expected.as_bytes()
+            );
+        }
+
+        let res = send_range(&serve_dir, "bytes=20-");
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_RANGE).unwrap().as_str(),
+            "bytes */12"
+        );
+
+        // Multiple ranges return the whole file
+        let res = send_range(&serve_dir, "bytes=0-1,4-5");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_return_same_metadata_on_head_and_get() {
+        let dir = std::env::temp_dir().join(format!("serve_dir_head_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+
+        let serve_dir = ServeDir::new(&dir);
+        let get = send(&serve_dir, Method::GET, None);
+        let head = send(&serve_dir, Method::HEAD, None);
+
+        assert_eq!(get.status(), StatusCode::OK);
+        assert_eq!(head.status(), StatusCode::OK);
+
+        for name in [
+            headers::ETAG,
+            headers::CONTENT_LENGTH,
+            headers::LAST_MODIFIED,
+        ] {
+            assert!(get.headers().get(name.clone()).is_some());
+            assert_eq!(get.headers().get(name.clone()), head.headers().get(name));
+        }
+
+        let etag = head.headers().get(headers::ETAG).cloned().unwrap();
+        assert_eq!(
+            get.headers().get(headers::CONTENT_LENGTH).unwrap().as_str(),
+            "12"
+        );
+        assert_eq!(head.into_body().read_all_bytes().unwrap(), b"");
+        assert_eq!(get.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        // Conditional request
+        let not_modified = send(&serve_dir, Method::HEAD, Some(&etag));
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(not_modified.headers().get(headers::ETAG), Some(&etag));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_return_not_modified_for_conditional_get() {
+        let dir = std::env::temp_dir().join(format!("serve_dir_etag_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+
+        let serve_dir = ServeDir::new(&dir);
+        let res = send(&serve_dir, Method::GET, None);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let etag = res.headers().get(headers::ETAG).cloned().unwrap();
+        assert!(etag.as_str().starts_with("W/\""));
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        let res = send(&serve_dir, Method::GET, Some(&etag));
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(headers::ETAG), Some(&etag));
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"");
+
+        // A strong validator with the same value also matches
+        let strong = HeaderValue::from_string(etag.as_str().trim_start_matches("W/").to_owned());
+        let res = send(&serve_dir, Method::GET, Some(&strong));
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        let res = send(
+            &serve_dir,
+            Method::GET,
+            Some(&HeaderValue::from_static("\"other\"")),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_return_not_modified_if_modified_since_is_newer() {
+        let dir = std::env::temp_dir().join(format!("serve_dir_since_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+
+        let serve_dir = ServeDir::new(&dir);
+        let send_since = |since: DateTime| {
+            let mut req = Request::builder()
+                .uri("/hello.txt")
+                .insert_header(headers::IF_MODIFIED_SINCE, since.to_rfc_1123_string())
+                .body(Body::empty())
+                .unwrap();
+
+            req.extensions_mut().insert(RouteInfo(Route::from("/*")));
+            serve_dir.call(req)
+        };
+
+        let newer = DateTime::now_utc() + Duration::from_secs(60);
+        let res = send_since(newer);
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"");
+
+        let older = DateTime::with_millis(0);
+        let res = send_since(older);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_serve_precompressed_gzip_file() {
+        let dir = std::env::temp_dir().join(format!("serve_dir_gzip_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+        std::fs::write(dir.join("other.txt"), "No gzip").unwrap();
+        std::fs::write(dir.join("hello.txt.gz"), gzip::compress(b"Hello World!")).unwrap();
+
+        let serve_dir = ServeDir::new(&dir).precompressed_gzip(true);
+        let send_gzip = |path: &str, accept_encoding: Option<&'static str>| {
+            let mut builder = Request::builder().uri(path);
+
+            if let Some(accept_encoding) = accept_encoding {
+                builder = builder.insert_header(headers::ACCEPT_ENCODING, accept_encoding);
+            }
+
+            let mut req = builder.body(Body::empty()).unwrap();
+            req.extensions_mut().insert(RouteInfo(Route::from("/*")));
+            serve_dir.call(req)
+        };
+
+        let res = send_gzip("/hello.txt", Some("gzip, deflate"));
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()
+                .get(headers::CONTENT_ENCODING)
+                .unwrap()
+                .as_str(),
+            "gzip"
+        );
+        assert_eq!(
+            res.headers().get(headers::CONTENT_TYPE).unwrap().as_str(),
+            "text/plain"
+        );
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(gzip::decompress(&bytes).unwrap(), b"Hello World!");
+
+        // gzip is not accepted
+        let res = send_gzip("/hello.txt", None);
+        assert!(res.headers().get(headers::CONTENT_ENCODING).is_none());
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        // No `.gz` sibling
+        let res = send_gzip("/other.txt", Some("gzip"));
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(headers::CONTENT_ENCODING).is_none());
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"No gzip");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_reject_path_traversal() {
+        let root = std::env::temp_dir().join(format!("serve_dir_traversal_{}", std::process::id()));
+        let dir = root.join("public");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "Hello World!").unwrap();
+        std::fs::write(root.join("secret.txt"), "Secret").unwrap();
+
+        let serve_dir = ServeDir::new(&dir);
+        let send_path = |path: &str| {
+            let mut req = Request::builder().uri(path).body(Body::empty()).unwrap();
+            req.extensions_mut()
+                .insert(RouteInfo(Route::from("/static/*")));
+            serve_dir.call(req)
+        };
+
+        let res = send_path("/static/hello.txt");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello World!");
+
+        let res = send_path("/static/../secret.txt");
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let secret = root.join("secret.txt");
+        let res = send_path(&format!("/static/{}", secret.display()));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -5,7 +5,8 @@ use std::{
 
 use http1::{
     body::Body,
-    headers::{self, HeaderValue},
+    headers::{self, HeaderValue, Headers},
+    method::Method,
     request::Request,
     response::Response,
     status::StatusCode,
@@ -13,6 +14,11 @@ use http1::{
 
 use crate::{handler::Handler, mime::Mime, IntoResponse};
 
+use super::{
+    file_info::FileInfo,
+    range::{file_range_response, ByteRange},
+};
+
 #[derive(Debug)]
 pub enum InvalidFile {
     CurrentDirectoryNotFound,
@@ -39,18 +45,58 @@ impl ServeFile {
 impl Handler<Request<Body>> for ServeFile {
     type Output = Response<Body>;
 
-    fn call(&self, _: Request<Body>) -> Self::Output {
-        create_file_response(&self.0)
+    fn call(&self, req: Request<Body>) -> Self::Output {
+        let include_body = req.method() != Method::HEAD;
+        create_file_response(&self.0, req.headers(), include_body)
     }
 }
 
 impl IntoResponse for ServeFile {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
-        create_file_response(&self.0)
+        create_file_response(&self.0, &Headers::new(), true)
+    }
+}
+
+/// Serves the file at the path, or `404` if the file does not exist.
+impl IntoResponse for PathBuf {
+    fn into_response(self) -> Response<Body> {
+        self.as_path().into_response()
+    }
+}
+
+/// Serves the file at the path, or `404` if the file does not exist.
+impl IntoResponse for &Path {
+    fn into_response(self) -> Response<Body> {
+        if !self.is_file() {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+
+        create_file_response(self, &Headers::new(), true)
     }
 }
 
-fn create_file_response(file_path: &Path) -> Response<Body> {
+fn create_file_response(
+    file_path: &Path,
+    req_headers: &Headers,
+    include_body: bool,
+) -> Response<Body> {
+    let file_info = match std::fs::metadata(file_path) {
+        Ok(metadata) => FileInfo::new(&metadata),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return StatusCode::NOT_FOUND.into_response()
+        }
+        Err(err) => {
+            log::error!("Error serving file: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if file_info.is_not_modified(req_headers) {
+        let mut res = Response::new(StatusCode::NOT_MODIFIED, Body::empty());
+        file_info.write_headers(res.headers_mut());
+        return res;
+    }
+
     match std::fs::File::open(file_path) {
         Ok(file) => {
             let mime = file_path
@@ -59,14 +105,17 @@ fn create_file_response(file_path: &Path) -> Response<Body> {
                 .and_then(|x| Mime::guess_mime(x).ok())
                 .unwrap_or(Mime::APPLICATION_OCTET_STREAM);
 
-            let body = Body::new(file);
+            let len = file_info.len;
+            let range = ByteRange::from_headers(req_headers, len);
+            let mut res = file_range_response(file, len, range, include_body);
+            let headers = res.headers_mut();
+            headers.insert(
+                headers::CONTENT_TYPE,
+                HeaderValue::from_string(mime.to_string()),
+            );
+            file_info.write_headers(headers);
 
-            Response::builder()
-                .insert_header(
-                    headers::CONTENT_TYPE,
-                    HeaderValue::from_string(mime.to_string()),
-                )
-                .body(body)
+            res
         }
         Err(err) if err.kind() == ErrorKind::NotFound => StatusCode::NOT_FOUND.into_response(),
         Err(err) => {
@@ -75,3 +124,54 @@ fn create_file_response(file_path: &Path) -> Response<Body> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers,
+        request::Request,
+        status::StatusCode,
+    };
+
+    use crate::handler::BoxedHandler;
+
+    #[test]
+    fn should_serve_file_returned_as_path() {
+        let dir = std::env::temp_dir().join(format!("serve_path_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("data.json"), r#"{"hello":"world"}"#).unwrap();
+
+        let handler = {
+            let dir = dir.clone();
+            BoxedHandler::new(move || -> PathBuf { dir.join("data.json") })
+        };
+
+        let res = handler.call(Request::builder().body(Body::empty()).unwrap());
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_TYPE).unwrap().as_str(),
+            "application/json"
+        );
+        assert_eq!(
+            res.headers().get(headers::CONTENT_LENGTH).unwrap().as_str(),
+            "17"
+        );
+        assert_eq!(
+            res.into_body().read_all_bytes().unwrap(),
+            br#"{"hello":"world"}"#
+        );
+
+        let handler = {
+            let dir = dir.clone();
+            BoxedHandler::new(move || dir.join("missing.json"))
+        };
+
+        let res = handler.call(Request::builder().body(Body::empty()).unwrap());
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,7 +2,11 @@ use std::{fmt::Debug, sync::Arc};
 
 use http1::{body::Body, request::Request, response::Response};
 
-use crate::{from_request::FromRequest, IntoResponse};
+use crate::{
+    from_request::FromRequest,
+    response::{accepts_problem_json, rejection_into_problem_json},
+    IntoResponse, ResponseHeaders,
+};
 
 pub trait Handler<Args> {
     type Output: IntoResponse;
@@ -45,12 +49,27 @@ impl BoxedHandler {
         R: IntoResponse,
     {
         BoxedHandler {
-            inner: Arc::new(move |req| match Args::from_whole_request(req) {
-                Ok(args) => {
-                    let result = handler.call(args);
-                    result.into_response()
+            inner: Arc::new(move |mut req: Request<Body>| {
+                if !req.extensions().contains::<ResponseHeaders>() {
+                    req.extensions_mut().insert(ResponseHeaders::default());
                 }
-                Err(err) => err.into_response(),
+
+                let response_headers = req.extensions().get::<ResponseHeaders>().cloned();
+                let problem_json = accepts_problem_json(req.headers());
+                let mut response = match Args::from_whole_request(req) {
+                    Ok(args) => {
+                        let result = handler.call(args);
+                        result.into_response()
+                    }
+                    Err(err) if problem_json => rejection_into_problem_json(err.into_response()),
+                    Err(err) => err.into_response(),
+                };
+
+                if let Some(response_headers) = response_headers {
+                    response_headers.drain_into(response.headers_mut());
+                }
+
+                response
             }),
 
             #[cfg(debug_assertions)]
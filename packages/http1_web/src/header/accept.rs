@@ -6,39 +6,255 @@ use crate::{mime::Mime, ErrorStatusCode};
 
 use super::FromHeaders;
 
+/// A media range of the `Accept` header with its quality value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaRange {
+    mime: Mime,
+    quality: f32,
+}
+
+impl MediaRange {
+    /// Returns the mime of this media range, it can contain wildcards like `text/*` or `*/*`.
+    pub fn mime(&self) -> &Mime {
+        &self.mime
+    }
+
+    /// Returns the quality value of this media range, defaults to `1.0`.
+    pub fn quality(&self) -> f32 {
+        self.quality
+    }
+
+    /// Whether if this media range matches the given mime, parameters are ignored.
+    pub fn matches(&self, mime: &Mime) -> bool {
+        let ty = self.mime.ty();
+        let subtype = self.mime.subtype();
+
+        (ty == "*" || ty.eq_ignore_ascii_case(mime.ty()))
+            && (subtype == "*" || subtype.eq_ignore_ascii_case(mime.subtype()))
+    }
+
+    /// How specific is this media range, more specific ranges take precedence over wildcards.
+    fn specificity(&self) -> u8 {
+        match (self.mime.ty(), self.mime.subtype()) {
+            ("*", _) => 0,
+            (_, "*") => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl FromStr for MediaRange {
+    type Err = InvalidMediaRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(';').map(|x| x.trim());
+        let media_type = parts.next().unwrap_or_default();
+        let mime = Mime::from_str(media_type).map_err(|_| InvalidMediaRange)?;
+
+        let quality = match parts.find_map(|x| x.strip_prefix("q=")) {
+            Some(q) => q
+                .parse::<f32>()
+                .ok()
+                .filter(|q| (0.0..=1.0).contains(q))
+                .ok_or(InvalidMediaRange)?,
+            None => 1.0,
+        };
+
+        Ok(MediaRange { mime, quality })
+    }
+}
+
+/// An error when parsing a media range of the `Accept` header.
+#[derive(Debug)]
+pub struct InvalidMediaRange;
+
+impl std::fmt::Display for InvalidMediaRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid media range")
+    }
+}
+
+impl std::error::Error for InvalidMediaRange {}
+
 /// Represents the `Accept` request header: [`https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept`].
-pub struct Accept(pub Mime);
+///
+/// The media ranges are ordered by descending quality, media ranges with the same quality keep the header order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accept(Vec<MediaRange>);
 
 impl Accept {
-    pub fn into_inner(self) -> Mime {
+    pub fn into_inner(self) -> Vec<MediaRange> {
+        self.0
+    }
+
+    /// Returns the quality the client gives to the mime, taken from the most specific media range that matches it.
+    ///
+    /// Returns `0.0` if no media range matches the mime.
+    pub fn quality_of(&self, mime: &Mime) -> f32 {
+        self.best_match(mime).map(|(_, x)| x.quality).unwrap_or(0.0)
+    }
+
+    /// Returns the mime the client prefers among the given ones, or `None` if none is acceptable.
+    ///
+    /// If the client accepts several mimes equally, the one that appears first in the header is returned,
+    /// and if they match the same media range the first of the given mimes is returned.
+    pub fn preferred(&self, offered: &[Mime]) -> Option<Mime> {
+        let mut preferred: Option<(&Mime, f32, usize)> = None;
+
+        for mime in offered {
+            let Some((idx, range)) = self.best_match(mime) else {
+                continue;
+            };
+
+            if range.quality <= 0.0 {
+                continue;
+            }
+
+            let is_better = match preferred {
+                None => true,
+                Some((_, quality, pos)) => {
+                    range.quality > quality || (range.quality == quality && idx < pos)
+                }
+            };
+
+            if is_better {
+                preferred = Some((mime, range.quality, idx));
+            }
+        }
+
+        preferred.map(|(mime, _, _)| mime.clone())
+    }
+
+    fn best_match(&self, mime: &Mime) -> Option<(usize, &MediaRange)> {
         self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| range.matches(mime))
+            .rev()
+            .max_by_key(|(_, range)| range.specificity())
     }
 }
 
 impl Deref for Accept {
-    type Target = Mime;
+    type Target = [MediaRange];
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
+impl FromStr for Accept {
+    type Err = InvalidMediaRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = s
+            .split(',')
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .map(MediaRange::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ranges.is_empty() {
+            return Err(InvalidMediaRange);
+        }
+
+        // The sort is stable so the ranges with the same quality keep the header order
+        ranges.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+        Ok(Accept(ranges))
+    }
+}
+
 impl FromHeaders for Accept {
     type Rejection = ErrorStatusCode;
 
     fn from_headers(headers: &http1::headers::Headers) -> Result<Self, Self::Rejection> {
-        match headers.get(headers::ACCEPT) {
-            Some(value) => match Mime::from_str(value.as_str()) {
-                Ok(mime) => Ok(Accept(mime)),
-                Err(err) => {
-                    log::warn!("Failed to parse `Accept` header: {err}");
-                    Err(ErrorStatusCode::BadRequest)
-                }
-            },
-            None => {
-                log::warn!("`Accept` header not found");
+        let values = headers
+            .get_all(headers::ACCEPT)
+            .map(|x| x.as_str())
+            .collect::<Vec<_>>();
+
+        if values.is_empty() {
+            log::warn!("`Accept` header not found");
+            return Err(ErrorStatusCode::BadRequest);
+        }
+
+        match Accept::from_str(&values.join(",")) {
+            Ok(accept) => Ok(accept),
+            Err(err) => {
+                log::warn!("Failed to parse `Accept` header: {err}");
                 Err(ErrorStatusCode::BadRequest)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::mime::Mime;
+
+    use super::Accept;
+
+    #[test]
+    fn should_order_media_ranges_by_quality() {
+        let accept =
+            Accept::from_str("text/plain;q=0.5, text/html;q=0.9, application/json;q=0.8").unwrap();
+        let mimes = accept.iter().map(|x| x.mime().clone()).collect::<Vec<_>>();
+
+        assert_eq!(
+            mimes,
+            vec![Mime::TEXT_HTML, Mime::APPLICATION_JSON, Mime::TEXT_PLAIN]
+        );
+        assert_eq!(
+            accept.preferred(&[Mime::APPLICATION_JSON, Mime::TEXT_HTML]),
+            Some(Mime::TEXT_HTML)
+        );
+        assert_eq!(
+            accept.preferred(&[Mime::TEXT_PLAIN, Mime::APPLICATION_JSON]),
+            Some(Mime::APPLICATION_JSON)
+        );
+        assert_eq!(accept.preferred(&[Mime::IMAGE_PNG]), None);
+    }
+
+    #[test]
+    fn should_default_quality_to_one_and_keep_header_order() {
+        let accept =
+            Accept::from_str("application/xml, application/json, text/html;q=0.9").unwrap();
+
+        assert_eq!(accept[0].quality(), 1.0);
+        assert_eq!(accept.quality_of(&Mime::APPLICATION_JSON), 1.0);
+        assert_eq!(
+            accept.preferred(&[
+                Mime::TEXT_HTML,
+                Mime::APPLICATION_JSON,
+                Mime::APPLICATION_XML
+            ]),
+            Some(Mime::APPLICATION_XML)
+        );
+        assert_eq!(
+            accept.preferred(&[Mime::TEXT_HTML, Mime::APPLICATION_JSON]),
+            Some(Mime::APPLICATION_JSON)
+        );
+    }
+
+    #[test]
+    fn should_match_wildcards() {
+        let accept = Accept::from_str("*/*").unwrap();
+        assert_eq!(
+            accept.preferred(&[Mime::APPLICATION_JSON, Mime::TEXT_HTML]),
+            Some(Mime::APPLICATION_JSON)
+        );
+
+        // The most specific media range takes precedence
+        let accept = Accept::from_str("text/*;q=0.8, */*;q=0.1, text/html;q=0").unwrap();
+        assert_eq!(accept.quality_of(&Mime::TEXT_PLAIN), 0.8);
+        assert_eq!(accept.quality_of(&Mime::TEXT_HTML), 0.0);
+        assert_eq!(accept.quality_of(&Mime::IMAGE_PNG), 0.1);
+        assert_eq!(
+            accept.preferred(&[Mime::TEXT_HTML, Mime::IMAGE_PNG, Mime::TEXT_PLAIN]),
+            Some(Mime::TEXT_PLAIN)
+        );
+        assert_eq!(accept.preferred(&[Mime::TEXT_HTML]), None);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{fmt::Display, ops::Deref};
+
+use http1::headers;
+
+use crate::ErrorStatusCode;
+
+use super::FromHeaders;
+
+/// Represents the `Last-Event-ID` request header, sent by a client reconnecting to a server-sent events stream: [`https://html.spec.whatwg.org/multipage/server-sent-events.html#last-event-id`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LastEventId(pub String);
+
+impl LastEventId {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for LastEventId {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for LastEventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromHeaders for LastEventId {
+    type Rejection = ErrorStatusCode;
+
+    fn from_headers(headers: &http1::headers::Headers) -> Result<Self, Self::Rejection> {
+        match headers.get(headers::LAST_EVENT_ID) {
+            Some(value) => Ok(LastEventId(value.as_str().to_owned())),
+            None => {
+                log::warn!("`Last-Event-ID` header not found");
+                Err(ErrorStatusCode::BadRequest)
+            }
+        }
+    }
+}
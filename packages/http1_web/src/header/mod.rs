@@ -13,11 +13,14 @@ mod authorization;
 mod basic_auth;
 mod entity;
 mod host;
+mod last_event_id;
 mod referer;
 mod user_agent;
+mod vary;
 
 pub use {
-    accept::*, authorization::*, basic_auth::*, entity::*, host::*, referer::*, user_agent::*,
+    accept::*, authorization::*, basic_auth::*, entity::*, host::*, last_event_id::*, referer::*,
+    user_agent::*, vary::*,
 };
 
 /// Allow to create a value from the request headers.
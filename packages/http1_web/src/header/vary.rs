@@ -0,0 +1,144 @@
+use std::{convert::Infallible, fmt::Display};
+
+use http1::headers::{self, HeaderName, HeaderValue, Headers};
+
+use super::FromHeaders;
+
+/// Represents the `Vary` response header, the request headers used to select a response: [`https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Vary`].
+///
+/// The names are compared case-insensitively and only added once.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Vary(Vec<HeaderName>);
+
+impl Vary {
+    /// Constructs an empty `Vary`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header name if is not already present.
+    pub fn add(&mut self, name: HeaderName) {
+        if !self.contains(&name) {
+            self.0.push(name);
+        }
+    }
+
+    /// Whether if contains the given header name.
+    pub fn contains(&self, name: &HeaderName) -> bool {
+        self.0.iter().any(|x| x == name)
+    }
+
+    /// Returns an iterator over the header names.
+    pub fn iter(&self) -> impl Iterator<Item = &HeaderName> {
+        self.0.iter()
+    }
+
+    /// Replaces the `Vary` headers with a single header containing all the names.
+    pub fn write_to(&self, headers: &mut Headers) {
+        headers.remove(headers::VARY);
+
+        if !self.0.is_empty() {
+            headers.insert(headers::VARY, HeaderValue::from_string(self.to_string()));
+        }
+    }
+
+    /// Adds a header name to the `Vary` header of the response, without duplicating it.
+    pub fn add_to(headers: &mut Headers, name: HeaderName) {
+        let Ok(mut vary) = Vary::from_headers(headers);
+        vary.add(name);
+        vary.write_to(headers);
+    }
+}
+
+impl Display for Vary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, name) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{name}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromHeaders for Vary {
+    type Rejection = Infallible;
+
+    fn from_headers(headers: &Headers) -> Result<Self, Self::Rejection> {
+        let mut vary = Vary::new();
+
+        for name in headers.get_split(headers::VARY) {
+            if let Ok(name) = HeaderName::from_checked_string(name.to_owned()) {
+                vary.add(name);
+            }
+        }
+
+        Ok(vary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use http1::{
+        body::Body,
+        handler::RequestHandler,
+        headers::{self, HeaderValue},
+        method::Method,
+        request::Request,
+        response::Response,
+        uri::uri::Uri,
+    };
+
+    use crate::{
+        app::App,
+        handler::BoxedHandler,
+        middleware::{cors::Cors, gzip::Gzip},
+    };
+
+    use super::Vary;
+    use crate::header::FromHeaders;
+
+    #[test]
+    fn should_deduplicate_vary_from_multiple_middlewares() {
+        let app = App::new()
+            .middleware(Cors::with_origins(["http://example.com"]))
+            .middleware(Gzip::new().min_size(0))
+            .middleware(|req: Request<Body>, next: &BoxedHandler| {
+                // Content negotiation
+                let mut res = next.call(req);
+                Vary::add_to(res.headers_mut(), headers::ACCEPT);
+                res
+            })
+            .get("/", || {
+                Response::builder()
+                    .append_header(headers::VARY, HeaderValue::from_static("accept-encoding"))
+                    .body(Body::from("Hello World!"))
+            });
+
+        let mut req = Request::new(Method::GET, Uri::from_str("/").unwrap(), Body::empty());
+        req.headers_mut()
+            .insert(headers::ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        req.headers_mut().insert(
+            headers::ORIGIN,
+            HeaderValue::from_static("http://example.com"),
+        );
+
+        let res = app.handle(req);
+        assert_eq!(res.headers().get_all(headers::VARY).count(), 1);
+        assert_eq!(
+            res.headers().get(headers::VARY).unwrap().as_str(),
+            "Accept-Encoding, Origin, Accept"
+        );
+
+        let Ok(vary) = Vary::from_headers(res.headers());
+        assert!(vary.contains(&headers::ACCEPT_ENCODING));
+        assert!(vary.contains(&headers::ORIGIN));
+        assert!(vary.contains(&headers::ACCEPT));
+        assert_eq!(vary.iter().count(), 3);
+    }
+}
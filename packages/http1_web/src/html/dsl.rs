@@ -295,6 +295,8 @@ pub fn html_void_element<T: IntoChildren>(tag: impl Into<String>, content: T) ->
 }
 
 /// Declare a text node for the current html element.
+///
+/// The text is stored as it is and escaped when the element is rendered, see [`text`].
 pub fn content(text: impl Into<String>) {
     ROOT.with_borrow_mut(|global: &mut Global| {
         if let Some(parent) = global.elements.last_mut() {
@@ -304,6 +306,13 @@ pub fn content(text: impl Into<String>) {
     })
 }
 
+/// Declare a text node for the current html element, the characters `& < > " '` are escaped when rendered.
+///
+/// Prefer this for user provided strings like query params.
+pub fn text(text: impl Into<String>) {
+    content(text)
+}
+
 /// Sets an attribute in the current html element.
 pub fn attr(name: impl Into<String>, value: impl IntoAttrValue) {
     ROOT.with_borrow_mut(|global: &mut Global| {
@@ -433,7 +442,7 @@ pub fn get_context<T: 'static + Clone>() -> Option<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::{attr, content, get_context, html_element, html_void_element, set_context};
+    use super::{attr, content, get_context, html_element, html_void_element, set_context, text};
 
     #[test]
     fn should_build_1_level_html() {
@@ -529,4 +538,26 @@ mod tests {
             "<div>\n<p>Hello World!</p>\n</div>\n"
         );
     }
+
+    #[test]
+    fn should_escape_text_node() {
+        let html = super::p(|| {
+            text("<script>");
+        })
+        .into_element()
+        .unwrap();
+
+        assert_eq!(html.to_plain_string(), "<p>&lt;script&gt;</p>\n");
+
+        let html = super::p(|| {
+            text(r#"Tom & "Jerry's""#);
+        })
+        .into_element()
+        .unwrap();
+
+        assert_eq!(
+            html.to_plain_string(),
+            "<p>Tom &amp; &quot;Jerry&apos;s&quot;</p>\n"
+        );
+    }
 }
@@ -186,6 +186,38 @@ impl Element {
         write_element(self, &mut buf, "", 0).expect("failed to write string");
         buf
     }
+
+    /// Writes the html incrementally to the writer, the output is the same as `to_string`.
+    ///
+    /// Many small writes are made, consider wrapping the writer in a `BufWriter`.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut adapter = IoWriteAdapter {
+            writer,
+            error: None,
+        };
+
+        match write_element(self, &mut adapter, "  ", 0) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(adapter
+                .error
+                .unwrap_or_else(|| std::io::Error::other("failed to write html"))),
+        }
+    }
+}
+
+/// Forwards a `std::fmt::Write` to a `std::io::Write` keeping the io error.
+struct IoWriteAdapter<'a, W> {
+    writer: &'a mut W,
+    error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> std::fmt::Write for IoWriteAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            std::fmt::Error
+        })
+    }
 }
 
 impl IntoResponse for Element {
@@ -215,6 +247,14 @@ impl HTMLElement {
             HTMLElement::None => None,
         }
     }
+
+    /// Writes the html incrementally to the writer, nothing is written if empty.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            HTMLElement::Element(element) => element.write_to(writer),
+            HTMLElement::None => Ok(()),
+        }
+    }
 }
 
 impl From<Element> for HTMLElement {
@@ -445,4 +485,28 @@ mod tests {
 "#
         )
     }
+
+    #[test]
+    fn should_write_to_same_as_to_string() {
+        let list = (0..100).fold(Element::builder("ul"), |ul, i| {
+            ul.child(
+                Element::builder("li")
+                    .attribute(Attribute::with_value("data-index", i.to_string()))
+                    .child(format!("Item <{i}> & more")),
+            )
+        });
+
+        let el = Element::builder("html")
+            .child(Element::builder("body").child(list))
+            .build();
+
+        let mut buf = Vec::new();
+        el.write_to(&mut buf).unwrap();
+        assert_eq!(buf, el.to_string().into_bytes());
+
+        // Errors from the writer are returned
+        let mut small = [0u8; 16];
+        let err = el.write_to(&mut small.as_mut_slice()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+    }
 }
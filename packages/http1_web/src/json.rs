@@ -1,24 +1,90 @@
 use std::fmt::Display;
 
 use http1::{
-    body::{http_body::HttpBody, Body},
+    body::{
+        body_reader::BodyReader,
+        http_body::{CollectError, HttpBody},
+        Body,
+    },
     error::BoxError,
     headers::CONTENT_TYPE,
     response::Response,
     status::StatusCode,
 };
 
-use crate::{from_request::FromRequest, mime::Mime, IntoResponse};
+use crate::{
+    from_request::{body_size_limit, FromRequest},
+    mime::Mime,
+    IntoResponse,
+};
 
-use serde::{self, de::Deserialize, ser::Serialize};
+use serde::{self, de::Deserialize, json::StreamDeserializer, ser::Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Json<T>(pub T);
 
+/// Extracts a `JSON` body of at most `MAX_BYTES` bytes.
+///
+/// Bodies larger than the limit are rejected with `413 Payload Too Large` before being parsed,
+/// the server-wide body limit still applies if is lower. For example `LimitedJson<Order, 4096>` accepts up to 4 KiB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LimitedJson<T, const MAX_BYTES: usize>(pub T);
+
+impl<T, const MAX_BYTES: usize> LimitedJson<T, MAX_BYTES> {
+    /// Returns the parsed value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Extracts a `JSON` body and the raw bytes it was parsed from.
+///
+/// Useful when the exact bytes are needed, like for verifying a signature of the body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithRaw<T>(pub T, pub Vec<u8>);
+
+impl<T> WithRaw<T> {
+    /// Returns the parsed value and the raw bytes.
+    pub fn into_inner(self) -> (T, Vec<u8>) {
+        (self.0, self.1)
+    }
+}
+
+/// Extracts a `JSON` body as an iterator of values that are parsed while the body is read.
+///
+/// The body can be a JSON array or newline delimited JSON, only one element is held in memory at a time,
+/// so large imports are not buffered. The iterator ends after the first error.
+///
+/// # Example
+/// ```rust,ignore
+/// app.post("/import", |items: JsonSeq<Item>| {
+///     for item in items {
+///         db.insert(item?)?;
+///     }
+///     Ok(StatusCode::NO_CONTENT)
+/// });
+/// ```
+pub struct JsonSeq<T>(StreamDeserializer<BodyReader, T>);
+
+impl<T> std::fmt::Debug for JsonSeq<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonSeq").finish_non_exhaustive()
+    }
+}
+
+impl<T: Deserialize> Iterator for JsonSeq<T> {
+    type Item = Result<T, serde::de::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
 #[doc(hidden)]
 #[derive(Debug)]
 pub enum InvalidJsonError {
     NoBody,
+    PayloadTooLarge,
     Other(BoxError),
 }
 
@@ -26,6 +92,7 @@ impl Display for InvalidJsonError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             InvalidJsonError::NoBody => write!(f, "request body was already taken"),
+            InvalidJsonError::PayloadTooLarge => write!(f, "json body is too large"),
             InvalidJsonError::Other(error) => write!(f, "failed to parse json: {error:?}"),
         }
     }
@@ -38,29 +105,84 @@ impl IntoResponse for InvalidJsonError {
         log::error!("{self}");
         match self {
             InvalidJsonError::NoBody => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            InvalidJsonError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE.into_response(),
             InvalidJsonError::Other(..) => StatusCode::UNPROCESSABLE_CONTENT.into_response(),
         }
     }
 }
 
+fn read_json_bytes(
+    req: &http1::request::Request<()>,
+    payload: &mut http1::payload::Payload,
+    max_bytes: usize,
+) -> Result<Vec<u8>, InvalidJsonError> {
+    if payload.is_empty() {
+        return Err(InvalidJsonError::NoBody);
+    }
+
+    payload
+        .collect(body_size_limit(req).min(max_bytes))
+        .map_err(|err| match err {
+            CollectError::LimitExceeded => InvalidJsonError::PayloadTooLarge,
+            CollectError::Other(err) => InvalidJsonError::Other(err),
+        })
+}
+
 impl<T: Deserialize> FromRequest for Json<T> {
     type Rejection = InvalidJsonError;
 
     fn from_request(
-        _req: &http1::request::Request<()>,
+        req: &http1::request::Request<()>,
         payload: &mut http1::payload::Payload,
     ) -> Result<Self, Self::Rejection> {
-        if payload.is_empty() {
-            return Err(InvalidJsonError::NoBody);
-        }
-
-        let bytes = payload.read_all_bytes().map_err(InvalidJsonError::Other)?;
+        let bytes = read_json_bytes(req, payload, usize::MAX)?;
         let value =
             serde::json::from_bytes::<T>(bytes).map_err(|e| InvalidJsonError::Other(e.into()))?;
         Ok(Json(value))
     }
 }
 
+impl<T: Deserialize, const MAX_BYTES: usize> FromRequest for LimitedJson<T, MAX_BYTES> {
+    type Rejection = InvalidJsonError;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let bytes = read_json_bytes(req, payload, MAX_BYTES)?;
+        let value =
+            serde::json::from_bytes::<T>(bytes).map_err(|e| InvalidJsonError::Other(e.into()))?;
+        Ok(LimitedJson(value))
+    }
+}
+
+impl<T: Deserialize> FromRequest for JsonSeq<T> {
+    type Rejection = InvalidJsonError;
+
+    fn from_request(
+        _req: &http1::request::Request<()>,
+        payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let body = payload.take().ok_or(InvalidJsonError::NoBody)?;
+        let reader = BodyReader::new(body);
+        Ok(JsonSeq(StreamDeserializer::new(reader)))
+    }
+}
+
+impl<T: Deserialize> FromRequest for WithRaw<T> {
+    type Rejection = InvalidJsonError;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let bytes = read_json_bytes(req, payload, usize::MAX)?;
+        let value =
+            serde::json::from_bytes::<T>(&bytes).map_err(|e| InvalidJsonError::Other(e.into()))?;
+        Ok(WithRaw(value, bytes))
+    }
+}
+
 impl<T: Serialize> IntoResponse for Json<T> {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
         match serde::json::to_string(&self.0) {
@@ -74,3 +196,122 @@ impl<T: Serialize> IntoResponse for Json<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        payload::Payload,
+        request::Request,
+    };
+
+    use crate::from_request::FromRequest;
+
+    use super::{InvalidJsonError, JsonSeq, LimitedJson, WithRaw};
+
+    #[test]
+    fn should_extract_json_with_raw_bytes() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Order {
+            id: u32,
+            item: String,
+        }
+
+        serde::impl_deserialize_struct!(Order => { id: u32, item: String });
+
+        let body = r#"{ "id": 12, "item": "Hoshimachi Suisei CD" }"#;
+        let req = Request::builder().body(()).unwrap();
+        let WithRaw(order, bytes) =
+            WithRaw::<Order>::from_request(&req, &mut Payload::Data(body.into())).unwrap();
+
+        assert_eq!(
+            order,
+            Order {
+                id: 12,
+                item: String::from("Hoshimachi Suisei CD")
+            }
+        );
+        assert_eq!(bytes, body.as_bytes());
+    }
+
+    #[test]
+    fn should_reject_json_over_limit_before_parsing() {
+        let req = Request::builder().body(()).unwrap();
+
+        let LimitedJson(values) = LimitedJson::<Vec<u32>, 16>::from_request(
+            &req,
+            &mut Payload::Data(Body::from("[1, 2, 3]")),
+        )
+        .unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        // Not valid json, a parse error would be `Other`
+        let body = format!("[1, 2, 3{}", ", 4".repeat(1000));
+        let result =
+            LimitedJson::<Vec<u32>, 16>::from_request(&req, &mut Payload::Data(body.into()));
+        assert!(matches!(result, Err(InvalidJsonError::PayloadTooLarge)));
+
+        // Unknown size
+        let reader = std::io::Cursor::new("[1, 2, 3, 4, 5, 6, 7, 8, 9]");
+        let body = Body::new(std::io::BufReader::new(reader));
+        let result = LimitedJson::<Vec<u32>, 16>::from_request(&req, &mut Payload::Data(body));
+        assert!(matches!(result, Err(InvalidJsonError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn should_stream_json_array_without_buffering() {
+        // Writes a JSON array of `total` numbers, one chunk per element
+        struct NumbersBody {
+            total: usize,
+            written: Arc<AtomicUsize>,
+        }
+
+        impl HttpBody for NumbersBody {
+            type Err = Infallible;
+            type Data = Vec<u8>;
+
+            fn read_next(&mut self) -> Result<Option<Self::Data>, Self::Err> {
+                let idx = self.written.fetch_add(1, Ordering::SeqCst);
+                let chunk = match idx {
+                    0 => "[0".to_owned(),
+                    _ if idx < self.total => format!(",{idx}"),
+                    _ if idx == self.total => "]".to_owned(),
+                    _ => return Ok(None),
+                };
+
+                Ok(Some(chunk.into_bytes()))
+            }
+        }
+
+        let total = 100_000;
+        let written = Arc::new(AtomicUsize::new(0));
+        let body = Body::new(NumbersBody {
+            total,
+            written: written.clone(),
+        });
+
+        let req = Request::builder().body(()).unwrap();
+        let mut items = JsonSeq::<usize>::from_request(&req, &mut Payload::Data(body)).unwrap();
+
+        assert_eq!(items.next().unwrap().unwrap(), 0);
+        assert_eq!(items.next().unwrap().unwrap(), 1);
+        // Only the chunks that fit in the read buffer were consumed
+        assert!(written.load(Ordering::SeqCst) < total / 10);
+
+        let mut count = 2;
+        for (idx, item) in items.enumerate() {
+            assert_eq!(item.unwrap(), idx + 2);
+            count += 1;
+        }
+
+        assert_eq!(count, total);
+    }
+}
@@ -1,12 +1,18 @@
 /// Provides a default server handler for `http1`.
 pub mod app;
 
+/// Raw body extractors.
+pub mod bytes;
+
 /// Client ip extractors.
 pub mod client_ip;
 
 /// Connection info extractors.
 pub mod conn_info;
 
+/// `CONNECT` tunnel extractor.
+pub mod connect;
+
 /// Cookies response and extractors.
 pub mod cookies;
 
@@ -19,6 +25,9 @@ pub mod from_request;
 /// File responses.
 pub mod fs;
 
+/// Gzip compression.
+pub use http1::common::gzip;
+
 /// Router request handler.
 pub mod handler;
 
@@ -46,6 +55,9 @@ pub mod query;
 /// Redirect response.
 pub mod redirect;
 
+/// Request time extractor.
+pub mod request_time;
+
 /// Server router.
 pub mod routing;
 
@@ -62,6 +74,12 @@ mod request;
 mod response;
 pub use {request::*, response::*};
 
+/// The `http1` paths used by the macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use http1::{body::Body, payload::Payload, request::Request, response::Response};
+}
+
 mod macros {
     /// Checks a `Result<T, impl IntoResponse>` and returns the error response if is an error.
     #[macro_export]
@@ -75,4 +93,52 @@ mod macros {
             }
         };
     }
+
+    /// Implements `FromRequest` for a struct by extracting each of its fields.
+    ///
+    /// The fields are extracted in order, if any fail the request is rejected with `400 Bad Request`
+    /// listing the rejection of each failed field.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// struct CreateUser {
+    ///     db: State<Db>,
+    ///     id: Path<u64>,
+    ///     body: Json<Input>,
+    /// }
+    ///
+    /// http1_web::impl_from_request!(CreateUser => { db: State<Db>, id: Path<u64>, body: Json<Input> });
+    /// ```
+    #[macro_export]
+    macro_rules! impl_from_request {
+        ($struct:ident => { $($field:ident : $ty:ty),* $(,)? }) => {
+            impl $crate::from_request::FromRequest for $struct {
+                type Rejection = $crate::__private::Response<$crate::__private::Body>;
+
+                #[allow(unused_mut)]
+                fn from_request(
+                    req: &$crate::__private::Request<()>,
+                    payload: &mut $crate::__private::Payload,
+                ) -> Result<Self, Self::Rejection> {
+                    let mut rejections = Vec::new();
+
+                    $(
+                        let $field = match <$ty as $crate::from_request::FromRequest>::from_request(req, payload) {
+                            Ok(x) => Some(x),
+                            Err(err) => {
+                                rejections.push((stringify!($field), $crate::IntoResponse::into_response(err)));
+                                None
+                            }
+                        };
+                    )*
+
+                    if !rejections.is_empty() {
+                        return Err($crate::from_request::rejections_into_response(rejections));
+                    }
+
+                    Ok($struct { $($field: $field.unwrap()),* })
+                }
+            }
+        };
+    }
 }
@@ -0,0 +1,159 @@
+use std::sync::{Condvar, Mutex};
+
+use http1::{body::Body, request::Request, response::Response, status::StatusCode};
+
+use crate::{handler::BoxedHandler, IntoResponse};
+
+use super::Middleware;
+
+#[derive(Debug, Default)]
+struct Permits {
+    active: usize,
+    queued: usize,
+}
+
+/// Limits the number of requests being processed at the same time.
+///
+/// Requests beyond the limit wait in a queue until a request completes,
+/// if the queue is also full the request is rejected with `503 Service Unavailable`.
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    max_concurrent: usize,
+    max_queued: usize,
+    permits: Mutex<Permits>,
+    released: Condvar,
+}
+
+impl ConcurrencyLimit {
+    /// Constructs a new `ConcurrencyLimit` that process at most `max_concurrent` requests at the same time
+    /// and rejects the rest.
+    pub fn new(max_concurrent: usize) -> Self {
+        assert!(max_concurrent > 0, "max concurrent requests cannot be 0");
+
+        ConcurrencyLimit {
+            max_concurrent,
+            max_queued: 0,
+            permits: Mutex::new(Permits::default()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Sets the max number of requests that can wait for other requests to complete.
+    pub fn queue(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    fn acquire(&self) -> Option<PermitGuard<'_>> {
+        let mut permits = self.permits.lock().expect("failed to lock permits");
+
+        if permits.active >= self.max_concurrent {
+            if permits.queued >= self.max_queued {
+                return None;
+            }
+
+            permits.queued += 1;
+            permits = self
+                .released
+                .wait_while(permits, |p| p.active >= self.max_concurrent)
+                .expect("failed to wait for permits");
+            permits.queued -= 1;
+        }
+
+        permits.active += 1;
+        Some(PermitGuard(self))
+    }
+}
+
+struct PermitGuard<'a>(&'a ConcurrencyLimit);
+
+impl Drop for PermitGuard<'_> {
+    fn drop(&mut self) {
+        let mut permits = self.0.permits.lock().expect("failed to lock permits");
+        permits.active -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+impl Middleware for ConcurrencyLimit {
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        match self.acquire() {
+            Some(_permit) => next.call(req),
+            None => {
+                log::warn!("Concurrency limit reached, rejecting request");
+                StatusCode::SERVICE_UNAVAILABLE.into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use http1::{body::Body, request::Request, status::StatusCode};
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::ConcurrencyLimit;
+
+    fn wait_until(f: impl Fn() -> bool) {
+        while !f() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn should_reject_requests_beyond_limit_and_queue() {
+        let limit = Arc::new(ConcurrencyLimit::new(2).queue(1));
+        let entered = Arc::new(AtomicUsize::new(0));
+        let release = Arc::new(AtomicBool::new(false));
+
+        let next = {
+            let entered = entered.clone();
+            let release = release.clone();
+            BoxedHandler::new(move || {
+                entered.fetch_add(1, Ordering::SeqCst);
+                wait_until(|| release.load(Ordering::SeqCst));
+                "Done"
+            })
+        };
+
+        let requests = (0..3)
+            .map(|_| {
+                let limit = limit.clone();
+                let next = next.clone();
+                std::thread::spawn(move || {
+                    let req = Request::builder().body(Body::empty()).unwrap();
+                    limit.on_request(req, &next).status()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // 2 requests being processed and 1 in the queue
+        wait_until(|| {
+            let permits = limit.permits.lock().unwrap();
+            permits.active == 2 && permits.queued == 1
+        });
+
+        assert_eq!(entered.load(Ordering::SeqCst), 2);
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = limit.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        release.store(true, Ordering::SeqCst);
+
+        for request in requests {
+            assert_eq!(request.join().unwrap(), StatusCode::OK);
+        }
+
+        assert_eq!(entered.load(Ordering::SeqCst), 3);
+    }
+}
@@ -14,6 +14,8 @@ use http1::{
     status::StatusCode,
 };
 
+use crate::header::Vary;
+
 use super::Middleware;
 
 pub enum CorsOrigin {
@@ -255,7 +257,14 @@ impl Middleware for Cors {
             next.call(req)
         };
 
-        outgoing_response.headers_mut().extend(cors_headers);
+        let headers = outgoing_response.headers_mut();
+        headers.extend(cors_headers);
+
+        // The allowed origin depends on the request `Origin`
+        if !matches!(self.allowed_origins, CorsOrigin::Any) {
+            Vary::add_to(headers, headers::ORIGIN);
+        }
+
         outgoing_response
     }
 }
@@ -0,0 +1,370 @@
+use std::convert::Infallible;
+
+use http1::{
+    body::{
+        http_body::{CollectError, HttpBody},
+        Body,
+    },
+    headers::{self, HeaderName, HeaderValue},
+    method::Method,
+    payload::Payload,
+    request::Request,
+    response::Response,
+    status::StatusCode,
+};
+
+use crate::{
+    cookies::{constant_time_eq, Cookie, Cookies, SameSite},
+    forms::urlencoded::{self, WWW_FORM_URLENCODED},
+    from_request::{body_size_limit, FromRequest},
+    handler::BoxedHandler,
+    IntoResponse,
+};
+
+use super::{https_redirect::is_https, Middleware};
+
+const DEFAULT_COOKIE_NAME: &str = "csrf_token";
+const DEFAULT_HEADER_NAME: &str = "X-CSRF-Token";
+const DEFAULT_FIELD_NAME: &str = "csrf_token";
+const TOKEN_LENGTH: usize = 32;
+
+/// The CSRF token of the current request, used to include the token in forms or in the requests of the client.
+///
+/// Returns an empty token if the [`Csrf`] middleware is not used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    /// Returns the token value.
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromRequest for CsrfToken {
+    type Rejection = Infallible;
+
+    fn from_request(req: &Request<()>, _payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Ok(req
+            .extensions()
+            .get::<CsrfToken>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// A middleware that protects against cross-site request forgery using double-submit cookies.
+///
+/// The safe requests without the cookie get a random token in a `Secure` cookie if the request is `https`,
+/// the token is reused until the cookie is gone. The `POST`, `PUT`, `PATCH` and `DELETE` requests
+/// must send the same token in the `X-CSRF-Token` header or in the `csrf_token` field of an urlencoded form,
+/// otherwise are rejected with `403 Forbidden`.
+/// The handlers can get the token with the [`CsrfToken`] extractor.
+#[derive(Debug)]
+pub struct Csrf {
+    cookie_name: String,
+    header_name: HeaderName,
+    field_name: String,
+}
+
+impl Csrf {
+    /// Constructs a new `Csrf` middleware.
+    pub fn new() -> Self {
+        Csrf {
+            cookie_name: DEFAULT_COOKIE_NAME.to_owned(),
+            header_name: HeaderName::from_static(DEFAULT_HEADER_NAME),
+            field_name: DEFAULT_FIELD_NAME.to_owned(),
+        }
+    }
+
+    /// Sets the name of the cookie that stores the token.
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the name of the header used to submit the token.
+    pub fn header_name(mut self, name: HeaderName) -> Self {
+        self.header_name = name;
+        self
+    }
+
+    /// Sets the name of the form field used to submit the token.
+    pub fn field_name(mut self, name: impl Into<String>) -> Self {
+        self.field_name = name.into();
+        self
+    }
+
+    /// Returns the token submitted in the header or the form, the body is read and restored for the handler.
+    fn submitted_token(&self, req: &mut Request<Body>) -> Result<Option<String>, CollectError> {
+        if let Some(value) = req.headers().get(&self.header_name) {
+            return Ok(Some(value.as_str().to_owned()));
+        }
+
+        let is_form = req
+            .headers()
+            .get(headers::CONTENT_TYPE)
+            .and_then(|x| x.as_str().split(';').next())
+            .is_some_and(|mime| mime.trim() == WWW_FORM_URLENCODED);
+
+        if !is_form {
+            return Ok(None);
+        }
+
+        let max = body_size_limit(req);
+        let mut body = std::mem::replace(req.body_mut(), Body::empty());
+        let bytes = body.collect(max)?;
+
+        let token = std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|s| urlencoded::from_str(s).ok())
+            .and_then(|form| form.get(&self.field_name).map(|x| x.to_owned()));
+
+        *req.body_mut() = Body::from(bytes);
+        Ok(token)
+    }
+
+    fn cookie_token(&self, req: &Request<Body>) -> Option<String> {
+        let cookies = Cookies::from_headers(req.headers()).unwrap_or_default();
+        cookies
+            .get(&self.cookie_name)
+            .map(|x| x.value().to_owned())
+            .filter(|x| !x.is_empty())
+    }
+}
+
+impl Default for Csrf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_unsafe_method(method: &Method) -> bool {
+    matches!(
+        method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+fn generate_token() -> String {
+    rng::sequence::<rng::Alphanumeric>()
+        .take(TOKEN_LENGTH)
+        .collect::<String>()
+}
+
+impl Middleware for Csrf {
+    fn on_request(&self, mut req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        let cookie_token = self.cookie_token(&req);
+
+        if is_unsafe_method(req.method()) {
+            let Some(expected) = cookie_token else {
+                log::warn!(
+                    "Request rejected, csrf cookie `{}` not found",
+                    self.cookie_name
+                );
+                return StatusCode::FORBIDDEN.into_response();
+            };
+
+            let submitted = match self.submitted_token(&mut req) {
+                Ok(submitted) => submitted,
+                Err(CollectError::LimitExceeded) => {
+                    log::warn!("Request rejected, form is larger than the body limit");
+                    return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+                }
+                Err(err) => {
+                    log::error!("Failed to read form for csrf token: {err}");
+                    return StatusCode::BAD_REQUEST.into_response();
+                }
+            };
+
+            let is_valid = submitted
+                .is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()));
+
+            if !is_valid {
+                log::warn!("Request rejected, csrf token is missing or does not match");
+                return StatusCode::FORBIDDEN.into_response();
+            }
+
+            req.extensions_mut().insert(CsrfToken(expected));
+            return next.call(req);
+        }
+
+        if let Some(token) = cookie_token {
+            req.extensions_mut().insert(CsrfToken(token));
+            return next.call(req);
+        }
+
+        let token = generate_token();
+        let secure = is_https(&req);
+        req.extensions_mut().insert(CsrfToken(token.clone()));
+
+        let mut response = next.call(req);
+
+        // Not `HttpOnly`, the client may need to read the token to send it in the header
+        let cookie = Cookie::new(self.cookie_name.as_str(), token)
+            .same_site(SameSite::Strict)
+            .secure(secure)
+            .build();
+
+        response.headers_mut().append(
+            headers::SET_COOKIE,
+            HeaderValue::from_string(cookie.to_string()),
+        );
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers,
+        method::Method,
+        request::Request,
+        status::StatusCode,
+    };
+
+    use crate::{
+        cookies::Cookie,
+        forms::{form::Form, urlencoded::WWW_FORM_URLENCODED},
+        handler::BoxedHandler,
+        middleware::Middleware,
+    };
+
+    use super::{Csrf, CsrfToken};
+
+    fn issue_token(csrf: &Csrf) -> String {
+        let next = BoxedHandler::new(|token: CsrfToken| token.value().to_owned());
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = csrf.on_request(req, &next);
+
+        let set_cookie = res.headers().get(headers::SET_COOKIE).unwrap().to_string();
+        let cookie = set_cookie.parse::<Cookie>().unwrap();
+        assert_eq!(cookie.name(), "csrf_token");
+        assert!(!cookie.is_secure());
+
+        // The handler gets the same token
+        let body = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(body, cookie.value().as_bytes());
+
+        cookie.value().to_owned()
+    }
+
+    #[test]
+    fn should_accept_matching_token() {
+        let csrf = Csrf::new();
+        let token = issue_token(&csrf);
+        assert_ne!(issue_token(&csrf), token);
+
+        let next = BoxedHandler::new(|| "ok");
+        let req = Request::builder()
+            .method(Method::POST)
+            .insert_header(headers::COOKIE, format!("csrf_token={token}"))
+            .insert_header("X-CSRF-Token", token.clone())
+            .body(Body::empty())
+            .unwrap();
+
+        let res = csrf.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // The form is still readable by the handler
+        let next = BoxedHandler::new(
+            |Form(form): Form<std::collections::HashMap<String, String>>| {
+                form.get("title").cloned().unwrap_or_default()
+            },
+        );
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .insert_header(headers::COOKIE, format!("csrf_token={token}"))
+            .insert_header(headers::CONTENT_TYPE, WWW_FORM_URLENCODED)
+            .body(Body::from(format!("title=Hello&csrf_token={token}")))
+            .unwrap();
+
+        let res = csrf.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"Hello");
+    }
+
+    #[test]
+    fn should_reject_missing_or_mismatched_token() {
+        let csrf = Csrf::new();
+        let token = issue_token(&csrf);
+        let next = BoxedHandler::new(|| "ok");
+
+        let requests = [
+            // No cookie
+            Request::builder()
+                .method(Method::POST)
+                .insert_header("X-CSRF-Token", token.clone()),
+            // No submitted token
+            Request::builder()
+                .method(Method::DELETE)
+                .insert_header(headers::COOKIE, format!("csrf_token={token}")),
+            // Mismatch
+            Request::builder()
+                .method(Method::PUT)
+                .insert_header(headers::COOKIE, format!("csrf_token={token}"))
+                .insert_header("X-CSRF-Token", "other"),
+        ];
+
+        for builder in requests {
+            let res = csrf.on_request(builder.body(Body::empty()).unwrap(), &next);
+            assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[test]
+    fn should_reuse_token_from_cookie() {
+        let csrf = Csrf::new();
+        let next = BoxedHandler::new(|token: CsrfToken| token.value().to_owned());
+        let req = Request::builder()
+            .insert_header(headers::COOKIE, "csrf_token=existing")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = csrf.on_request(req, &next);
+        assert!(res.headers().get(headers::SET_COOKIE).is_none());
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"existing");
+    }
+
+    #[test]
+    fn should_issue_secure_cookie_over_https() {
+        let csrf = Csrf::new();
+        let next = BoxedHandler::new(|| "ok");
+        let req = Request::builder()
+            .insert_header(headers::X_FORWARDED_PROTO, "https")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = csrf.on_request(req, &next);
+        let set_cookie = res.headers().get(headers::SET_COOKIE).unwrap().to_string();
+        assert!(set_cookie.parse::<Cookie>().unwrap().is_secure());
+    }
+
+    #[test]
+    fn should_reject_form_larger_than_body_limit() {
+        let csrf = Csrf::new();
+        let token = issue_token(&csrf);
+        let next = BoxedHandler::new(|| "ok");
+
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .insert_header(headers::COOKIE, format!("csrf_token={token}"))
+            .insert_header(headers::CONTENT_TYPE, WWW_FORM_URLENCODED)
+            .body(Body::from(format!(
+                "title={}&csrf_token={token}",
+                "a".repeat(64)
+            )))
+            .unwrap();
+
+        req.extensions_mut().insert(http1::server::Config {
+            max_body_size: Some(32),
+            ..Default::default()
+        });
+
+        let res = csrf.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
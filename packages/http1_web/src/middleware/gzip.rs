@@ -0,0 +1,315 @@
+use http1::{
+    body::{http_body::HttpBody, Body},
+    common::gzip,
+    headers::{self, HeaderValue, Headers},
+    request::Request,
+    response::Response,
+    status::StatusCode,
+};
+
+use crate::{handler::BoxedHandler, header::Vary, IntoResponse};
+
+use super::Middleware;
+
+/// Min size in bytes a body should have to be compressed.
+const DEFAULT_MIN_SIZE: usize = 1024;
+
+/// A middleware that compresses the responses using `gzip` or `deflate` if the client accepts it.
+///
+/// The encoding is selected with [`negotiate_encoding`], if the client does not accept any encoding,
+/// not even `identity`, it responds with `406 Not Acceptable`.
+///
+/// Only the bodies with a known size are compressed, streaming responses are sent as they are.
+#[derive(Debug)]
+pub struct Gzip {
+    min_size: usize,
+}
+
+impl Gzip {
+    /// Constructs a new `Gzip` middleware.
+    pub fn new() -> Self {
+        Gzip {
+            min_size: DEFAULT_MIN_SIZE,
+        }
+    }
+
+    /// Sets the min size in bytes the body should have to be compressed.
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    fn should_compress(&self, response: &Response<Body>) -> bool {
+        let status = response.status();
+
+        if status == StatusCode::NO_CONTENT || status == StatusCode::NOT_MODIFIED {
+            return false;
+        }
+
+        if response.headers().contains_key(headers::CONTENT_ENCODING) {
+            return false;
+        }
+
+        response
+            .body()
+            .size_hint()
+            .is_some_and(|size| size >= self.min_size)
+    }
+}
+
+impl Default for Gzip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A content encoding supported by the compression middleware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// The `gzip` format.
+    Gzip,
+
+    /// The zlib format.
+    Deflate,
+
+    /// No compression.
+    Identity,
+}
+
+impl Encoding {
+    /// Returns the name of the encoding used in the `Content-Encoding` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Identity => "identity",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Encoding::Gzip, Encoding::Deflate, Encoding::Identity]
+            .into_iter()
+            .find(|x| x.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Selects the encoding of the response from the `Accept-Encoding` header.
+///
+/// Picks the supported encoding with the highest quality, or the first in the header if several have the same quality.
+/// Falls back to `identity` if none is acceptable, and returns `None` only if `identity` is forbidden
+/// with `identity;q=0` or `*;q=0`.
+pub fn negotiate_encoding(headers: &Headers) -> Option<Encoding> {
+    let mut codings = headers
+        .get_split(headers::ACCEPT_ENCODING)
+        .map(|coding| {
+            let mut parts = coding.split(';').map(|x| x.trim());
+            let name = parts.next().unwrap_or_default();
+            // An invalid quality is treated as not acceptable
+            let quality = match parts.find_map(|x| x.strip_prefix("q=")) {
+                Some(q) => q
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|q| (0.0..=1.0).contains(q))
+                    .unwrap_or(0.0),
+                None => 1.0,
+            };
+
+            (name, quality)
+        })
+        .collect::<Vec<_>>();
+
+    // The sort is stable so the codings with the same quality keep the header order
+    codings.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let find = |name: &str| codings.iter().find(|(x, _)| x.eq_ignore_ascii_case(name));
+
+    for (name, quality) in &codings {
+        if *quality <= 0.0 {
+            break;
+        }
+
+        // The wildcard matches the encodings not listed in the header
+        if *name == "*" {
+            let unlisted = [Encoding::Gzip, Encoding::Deflate]
+                .into_iter()
+                .find(|x| find(x.as_str()).is_none());
+
+            match unlisted {
+                Some(encoding) => return Some(encoding),
+                None => continue,
+            }
+        }
+
+        if let Some(encoding) = Encoding::from_name(name) {
+            return Some(encoding);
+        }
+    }
+
+    let identity_forbidden = find("identity")
+        .or_else(|| find("*"))
+        .is_some_and(|(_, quality)| *quality <= 0.0);
+
+    (!identity_forbidden).then_some(Encoding::Identity)
+}
+
+impl Middleware for Gzip {
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        let encoding = match negotiate_encoding(req.headers()) {
+            Some(Encoding::Identity) => return next.call(req),
+            Some(encoding) => encoding,
+            None => {
+                log::warn!("No acceptable encoding found in `Accept-Encoding`");
+                return StatusCode::NOT_ACCEPTABLE.into_response();
+            }
+        };
+
+        let mut response = next.call(req);
+
+        if !self.should_compress(&response) {
+            return response;
+        }
+
+        let mut body = std::mem::replace(response.body_mut(), Body::empty());
+        let bytes = match body.read_all_bytes() {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("Failed to read response body for compression: {err}");
+                return Response::new(StatusCode::INTERNAL_SERVER_ERROR, Body::empty());
+            }
+        };
+
+        let compressed = match encoding {
+            Encoding::Deflate => gzip::compress_zlib(&bytes),
+            _ => gzip::compress(&bytes),
+        };
+
+        *response.body_mut() = Body::from(compressed);
+
+        let headers = response.headers_mut();
+        headers.remove(headers::CONTENT_LENGTH);
+        headers.insert(
+            headers::CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        Vary::add_to(headers, headers::ACCEPT_ENCODING);
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        common::gzip,
+        headers::{self, HeaderValue, Headers},
+        request::Request,
+        status::StatusCode,
+    };
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::{negotiate_encoding, Encoding, Gzip};
+
+    fn send(
+        accept_encoding: Option<&'static str>,
+        next: &BoxedHandler,
+    ) -> http1::response::Response<Body> {
+        let mut builder = Request::builder();
+
+        if let Some(accept_encoding) = accept_encoding {
+            builder = builder.insert_header(headers::ACCEPT_ENCODING, accept_encoding);
+        }
+
+        let req = builder.body(Body::empty()).unwrap();
+        Gzip::new().on_request(req, next)
+    }
+
+    #[test]
+    fn should_compress_response_when_gzip_is_accepted() {
+        let html = "<p>Hello World!</p>".repeat(200);
+        let next = {
+            let html = html.clone();
+            BoxedHandler::new(move || html.clone())
+        };
+
+        let res = send(Some("br, gzip;q=0.8"), &next);
+        assert_eq!(
+            res.headers()
+                .get(headers::CONTENT_ENCODING)
+                .unwrap()
+                .as_str(),
+            "gzip"
+        );
+        assert!(res.headers().get(headers::CONTENT_LENGTH).is_none());
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert!(bytes.len() < html.len());
+        assert_eq!(gzip::decompress(&bytes).unwrap(), html.as_bytes());
+
+        // Not accepted
+        for accept_encoding in [None, Some("gzip;q=0"), Some("br")] {
+            let res = send(accept_encoding, &next);
+            assert!(res.headers().get(headers::CONTENT_ENCODING).is_none());
+            assert_eq!(res.into_body().read_all_bytes().unwrap(), html.as_bytes());
+        }
+    }
+
+    fn negotiate(accept_encoding: &'static str) -> Option<Encoding> {
+        let mut headers = Headers::new();
+        headers.insert(
+            headers::ACCEPT_ENCODING,
+            HeaderValue::from_static(accept_encoding),
+        );
+        negotiate_encoding(&headers)
+    }
+
+    #[test]
+    fn should_negotiate_encoding() {
+        assert_eq!(negotiate("gzip, deflate"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("gzip;q=0.5, deflate"), Some(Encoding::Deflate));
+        assert_eq!(negotiate("br, *;q=0.8"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("gzip;q=0, *"), Some(Encoding::Deflate));
+
+        // Fallback to identity
+        assert_eq!(negotiate(""), Some(Encoding::Identity));
+        assert_eq!(negotiate("br"), Some(Encoding::Identity));
+        assert_eq!(
+            negotiate_encoding(&Headers::new()),
+            Some(Encoding::Identity)
+        );
+
+        // Identity is forbidden
+        assert_eq!(negotiate("identity;q=0"), None);
+        assert_eq!(negotiate("br, *;q=0"), None);
+        assert_eq!(negotiate("deflate, identity;q=0"), Some(Encoding::Deflate));
+
+        // Invalid quality is not acceptable
+        assert_eq!(negotiate("gzip;q=abc, deflate"), Some(Encoding::Deflate));
+        assert_eq!(negotiate("gzip;q=2"), Some(Encoding::Identity));
+    }
+
+    #[test]
+    fn should_compress_with_deflate_or_reject_when_not_acceptable() {
+        let html = "<p>Hello World!</p>".repeat(200);
+        let next = {
+            let html = html.clone();
+            BoxedHandler::new(move || html.clone())
+        };
+
+        let res = send(Some("deflate, gzip;q=0.8"), &next);
+        assert_eq!(
+            res.headers()
+                .get(headers::CONTENT_ENCODING)
+                .unwrap()
+                .as_str(),
+            "deflate"
+        );
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(gzip::decompress_zlib(&bytes).unwrap(), html.as_bytes());
+
+        let res = send(Some("identity;q=0"), &next);
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+}
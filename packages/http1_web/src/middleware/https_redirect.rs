@@ -0,0 +1,197 @@
+use std::str::FromStr;
+
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue, Headers},
+    request::Request,
+    response::Response,
+    status::StatusCode,
+    uri::{
+        authority::Authority, percent_encode, scheme::Scheme, uri::Uri,
+        url_encoding::PathSegmentASCII,
+    },
+};
+
+use crate::{handler::BoxedHandler, IntoResponse};
+
+use super::Middleware;
+
+/// A middleware that redirects the `http` requests to `https` with `308 Permanent Redirect`.
+///
+/// The scheme is taken from the `X-Forwarded-Proto` header set by a TLS-terminating proxy,
+/// otherwise from the request uri, the requests received by the server connection are `http`.
+/// The redirect keeps the host of the `Host` header, the path and the query.
+#[derive(Debug, Default)]
+pub struct HttpsRedirect {
+    https_port: Option<u16>,
+}
+
+impl HttpsRedirect {
+    /// Constructs a new `HttpsRedirect` middleware that redirects to the default `https` port.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the port of the `https` redirect location.
+    pub fn https_port(mut self, port: u16) -> Self {
+        self.https_port = Some(port);
+        self
+    }
+}
+
+/// Whether the client request was sent over `https`, directly or through a proxy.
+pub(crate) fn is_https<T>(req: &Request<T>) -> bool {
+    match forwarded_proto(req.headers()) {
+        Some(proto) => proto.eq_ignore_ascii_case("https"),
+        None => req.uri().scheme() == Some(&Scheme::Https),
+    }
+}
+
+/// Returns the protocol of the client request, the first value if it passed through several proxies.
+fn forwarded_proto(headers: &Headers) -> Option<&str> {
+    headers.get_split(headers::X_FORWARDED_PROTO).next()
+}
+
+impl Middleware for HttpsRedirect {
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        if is_https(&req) {
+            return next.call(req);
+        }
+
+        let host = req
+            .headers()
+            .get(headers::HOST)
+            .and_then(|host| Authority::from_str(host.as_str()).ok())
+            .or_else(|| req.uri().authority().cloned());
+
+        let Some(host) = host else {
+            log::warn!("Failed to redirect to https, the request `Host` was not found");
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+
+        let authority = match self.https_port {
+            Some(port) if port != 443 => format!("{}:{port}", host.host()),
+            _ => host.host().to_owned(),
+        };
+
+        let location = format!("https://{authority}{}", encode_path_and_query(req.uri()));
+
+        // The query is sent as received, it may still contain invalid characters
+        let location = HeaderValue::from_checked_string(location)
+            .ok()
+            .filter(|x| !x.as_str().bytes().any(|b| b.is_ascii_control()));
+
+        let Some(location) = location else {
+            log::warn!("Failed to redirect to https, the request path is not a valid location");
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+
+        Response::builder()
+            .status(StatusCode::PERMANENT_REDIRECT)
+            .insert_header(headers::LOCATION, location)
+            .body(Body::empty())
+    }
+}
+
+/// Encodes again the path, which is decoded by the server, the query is kept encoded.
+fn encode_path_and_query(uri: &Uri) -> String {
+    let path_and_query = uri.path_and_query();
+    let mut encoded = path_and_query
+        .path()
+        .split('/')
+        .map(|segment| percent_encode(segment, PathSegmentASCII))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if let Some(query) = path_and_query.query_raw() {
+        encoded.push('?');
+        encoded.push_str(query);
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{body::Body, headers, request::Request, status::StatusCode};
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::HttpsRedirect;
+
+    #[test]
+    fn should_redirect_http_to_https() {
+        let next = BoxedHandler::new(|| "Hello World!");
+        let req = Request::builder()
+            .uri("/users?page=2")
+            .insert_header(headers::HOST, "example.com:8080")
+            .insert_header(headers::X_FORWARDED_PROTO, "http")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = HttpsRedirect::new().on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(headers::LOCATION).unwrap().as_str(),
+            "https://example.com/users?page=2"
+        );
+
+        // Requests from the connection are http
+        let req = Request::builder()
+            .uri("/")
+            .insert_header(headers::HOST, "localhost:8080")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = HttpsRedirect::new().https_port(8443).on_request(req, &next);
+        assert_eq!(
+            res.headers().get(headers::LOCATION).unwrap().as_str(),
+            "https://localhost:8443/"
+        );
+    }
+
+    #[test]
+    fn should_pass_through_https_requests() {
+        let next = BoxedHandler::new(|| "Hello World!");
+        let req = Request::builder()
+            .uri("/")
+            .insert_header(headers::HOST, "example.com")
+            .insert_header(headers::X_FORWARDED_PROTO, "https")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = HttpsRedirect::new().on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(headers::LOCATION).is_none());
+    }
+
+    #[test]
+    fn should_encode_path_of_location() {
+        let next = BoxedHandler::new(|| "Hello World!");
+
+        // The server decodes the path of the request target
+        let paths = [
+            (
+                "/a\r\nSet-Cookie: x=1",
+                "https://h/a%0D%0ASet-Cookie:%20x=1",
+            ),
+            ("/café?name=%C3%A9", "https://h/caf%C3%A9?name=%C3%A9"),
+        ];
+
+        for (path, expected) in paths {
+            let req = Request::builder()
+                .uri(path)
+                .insert_header(headers::HOST, "h")
+                .body(Body::empty())
+                .unwrap();
+
+            let res = HttpsRedirect::new().on_request(req, &next);
+            assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+            assert_eq!(
+                res.headers().get(headers::LOCATION).unwrap().as_str(),
+                expected
+            );
+            assert!(res.headers().get(headers::SET_COOKIE).is_none());
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use http1::{
+    body::{
+        http_body::{CollectError, HttpBody},
+        Body,
+    },
+    common::sha256,
+    headers::{self, Headers},
+    method::Method,
+    protocol::connection::Connected,
+    request::Request,
+    response::Response,
+    status::StatusCode,
+};
+
+use crate::{from_request::body_size_limit, handler::BoxedHandler, IntoResponse};
+
+use super::Middleware;
+
+/// Default time a response is kept for replay.
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default max number of keys kept in the store.
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+type IdentityFn = Box<dyn Fn(&Request<Body>) -> Option<String> + Send + Sync>;
+
+#[derive(Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: Headers,
+    body: Vec<u8>,
+}
+
+impl CachedResponse {
+    fn to_response(&self) -> Response<Body> {
+        let mut response = Response::new(self.status, Body::from(self.body.clone()));
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+enum State {
+    /// The first request with the key is still being processed.
+    InFlight,
+
+    /// The response of the first request with the key.
+    Completed {
+        response: CachedResponse,
+        expires_at: Instant,
+    },
+}
+
+struct Entry {
+    /// Hash of the body of the first request with the key.
+    body_hash: Vec<u8>,
+    state: State,
+}
+
+/// Replays the response of a request when a request with the same `Idempotency-Key` is received again.
+///
+/// Only applies to `POST`, `PUT`, `PATCH` and `DELETE` requests with an `Idempotency-Key` header,
+/// the key is scoped to the client identity, method and path of the request.
+/// By default the client is identified by the `Authorization` header, the `Cookie` header or else the peer address,
+/// the requests of unidentified clients are not tracked.
+///
+/// A duplicate received while the first request is still processing is rejected with `409 Conflict`,
+/// a key reused with a different body is rejected with `422 Unprocessable Content`,
+/// and server errors or responses of unknown size are not stored.
+/// The `Set-Cookie` headers are never replayed.
+pub struct Idempotency {
+    ttl: Duration,
+    max_entries: usize,
+    identity: IdentityFn,
+    store: Mutex<HashMap<String, Entry>>,
+}
+
+impl Idempotency {
+    /// Constructs a new `Idempotency` middleware that keeps up to 10000 responses for 24 hours.
+    pub fn new() -> Self {
+        Idempotency {
+            ttl: DEFAULT_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            identity: Box::new(default_identity),
+            store: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long a response is kept for replay.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the max number of keys kept, when full the responses closest to expire are removed.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        assert!(max_entries > 0, "max entries must be greater than 0");
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Sets the function used to identify the client that sends the request, like a user id or session id.
+    ///
+    /// The requests are not tracked if the function returns `None`.
+    pub fn identity<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request<Body>) -> Option<String> + Send + Sync + 'static,
+    {
+        self.identity = Box::new(f);
+        self
+    }
+}
+
+fn default_identity(req: &Request<Body>) -> Option<String> {
+    let credentials = req
+        .headers()
+        .get(headers::AUTHORIZATION)
+        .or_else(|| req.headers().get(headers::COOKIE));
+
+    if let Some(credentials) = credentials {
+        return Some(credentials.to_string());
+    }
+
+    let addr = req.extensions().get::<Connected>()?.peer_addr()?;
+    Some(addr.ip().to_string())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+impl Default for Idempotency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_unsafe_method(method: &Method) -> bool {
+    matches!(
+        method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// Removes the completed entry closest to expire, returns `false` if all the entries are in-flight.
+fn evict_oldest(store: &mut HashMap<String, Entry>) -> bool {
+    let oldest = store
+        .iter()
+        .filter_map(|(key, entry)| match entry.state {
+            State::Completed { expires_at, .. } => Some((key, expires_at)),
+            State::InFlight => None,
+        })
+        .min_by_key(|(_, expires_at)| *expires_at)
+        .map(|(key, _)| key.clone());
+
+    match oldest {
+        Some(key) => store.remove(&key).is_some(),
+        None => false,
+    }
+}
+
+/// Removes the in-flight entry if the request did not complete, for example if the handler panics.
+struct InFlightGuard<'a> {
+    store: &'a Mutex<HashMap<String, Entry>>,
+    key: Option<String>,
+}
+
+impl InFlightGuard<'_> {
+    fn complete(mut self, entry: Option<Entry>) {
+        let key = self.key.take().expect("key was already taken");
+        let mut store = self.store.lock().expect("failed to lock idempotency store");
+
+        match entry {
+            Some(entry) => store.insert(key, entry),
+            None => store.remove(&key),
+        };
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            if let Ok(mut store) = self.store.lock() {
+                store.remove(&key);
+            }
+        }
+    }
+}
+
+impl Middleware for Idempotency {
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        if !is_unsafe_method(req.method()) {
+            return next.call(req);
+        }
+
+        let Some(idempotency_key) = req
+            .headers()
+            .get(headers::IDEMPOTENCY_KEY)
+            .map(|x| x.to_string())
+        else {
+            return next.call(req);
+        };
+
+        // Without an identity the stored response could be replayed to other clients
+        let Some(identity) = (self.identity)(&req) else {
+            log::debug!("Client of idempotency key `{idempotency_key}` is unknown, the request is not tracked");
+            return next.call(req);
+        };
+
+        // The identity is hashed to not keep the credentials in memory
+        let identity = to_hex(&sha256::hash(identity));
+
+        let key = format!(
+            "{} {} {} {}",
+            identity,
+            req.method().as_str(),
+            req.uri().path_and_query().path(),
+            idempotency_key
+        );
+
+        let max_body_size = body_size_limit(&req);
+        let (mut body, parts) = req.into_parts();
+        let body = match body.collect(max_body_size) {
+            Ok(bytes) => bytes,
+            Err(CollectError::LimitExceeded) => {
+                log::warn!("Request body is larger than the body limit");
+                return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+            }
+            Err(err) => {
+                log::error!("Failed to read request body for idempotency: {err}");
+                return StatusCode::BAD_REQUEST.into_response();
+            }
+        };
+
+        let body_hash = sha256::hash(&body);
+
+        {
+            let now = Instant::now();
+            let mut store = self.store.lock().expect("failed to lock idempotency store");
+            store.retain(|_, entry| match entry.state {
+                State::InFlight => true,
+                State::Completed { expires_at, .. } => expires_at > now,
+            });
+
+            match store.get(&key) {
+                Some(entry) if entry.body_hash != body_hash => {
+                    log::warn!("Idempotency key `{idempotency_key}` was reused with a different request body");
+                    return StatusCode::UNPROCESSABLE_CONTENT.into_response();
+                }
+                Some(Entry {
+                    state: State::Completed { response, .. },
+                    ..
+                }) => return response.to_response(),
+                Some(Entry {
+                    state: State::InFlight,
+                    ..
+                }) => {
+                    log::warn!("Request with idempotency key `{idempotency_key}` is already being processed");
+                    return StatusCode::CONFLICT.into_response();
+                }
+                None => {
+                    if store.len() >= self.max_entries && !evict_oldest(&mut store) {
+                        log::warn!("Idempotency store is full, the request is not tracked");
+                        return next.call(Request::from_parts(parts, Body::from(body)));
+                    }
+
+                    store.insert(
+                        key.clone(),
+                        Entry {
+                            body_hash: body_hash.clone(),
+                            state: State::InFlight,
+                        },
+                    );
+                }
+            }
+        }
+
+        let guard = InFlightGuard {
+            store: &self.store,
+            key: Some(key),
+        };
+
+        let response = next.call(Request::from_parts(parts, Body::from(body)));
+
+        // Streamed responses are not stored
+        if response.status().is_server_error() || response.body().size_hint().is_none() {
+            guard.complete(None);
+            return response;
+        }
+
+        let (status, mut headers, mut body, version) = response.into_parts();
+        let body = match body.read_all_bytes() {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("Failed to read response body for idempotency: {err}");
+                guard.complete(None);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let mut response = Response::new(status, Body::from(body.clone()));
+        *response.headers_mut() = headers.clone();
+        *response.version_mut() = version;
+
+        // The cookies are only for the client that sent the first request
+        headers.remove(headers::SET_COOKIE);
+
+        let cached = CachedResponse {
+            status,
+            headers,
+            body,
+        };
+
+        guard.complete(Some(Entry {
+            body_hash,
+            state: State::Completed {
+                response: cached,
+                expires_at: Instant::now() + self.ttl,
+            },
+        }));
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers::{self, HeaderValue},
+        method::Method,
+        request::Request,
+        response::Response,
+        status::StatusCode,
+    };
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::Idempotency;
+
+    fn request(method: Method, key: Option<&'static str>) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri("/payments")
+            .insert_header(headers::AUTHORIZATION, "Bearer ai");
+
+        if let Some(key) = key {
+            builder = builder.insert_header(headers::IDEMPOTENCY_KEY, key);
+        }
+
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn user_request(user: &'static str, key: &'static str, body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/payments")
+            .insert_header(headers::IDEMPOTENCY_KEY, key)
+            .insert_header(headers::AUTHORIZATION, user)
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[test]
+    fn should_replay_response_for_same_idempotency_key() {
+        let idempotency = Idempotency::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let next = {
+            let calls = calls.clone();
+            BoxedHandler::new(move || {
+                let count = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                format!("payment #{count}")
+            })
+        };
+
+        let first = idempotency.on_request(request(Method::POST, Some("abc")), &next);
+        let second = idempotency.on_request(request(Method::POST, Some("abc")), &next);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.status(), second.status());
+        assert_eq!(
+            first.headers().get(headers::CONTENT_TYPE),
+            second.headers().get(headers::CONTENT_TYPE)
+        );
+
+        let first = first.into_body().read_all_bytes().unwrap();
+        let second = second.into_body().read_all_bytes().unwrap();
+        assert_eq!(first, b"payment #1");
+        assert_eq!(first, second);
+
+        // Different key, no key or safe method
+        idempotency.on_request(request(Method::POST, Some("xyz")), &next);
+        idempotency.on_request(request(Method::POST, None), &next);
+        idempotency.on_request(request(Method::GET, Some("abc")), &next);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn should_scope_key_to_client_and_body() {
+        let idempotency = Idempotency::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let next = {
+            let calls = calls.clone();
+            BoxedHandler::new(move || {
+                let count = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                let cookie = format!("session=user{count}");
+                Response::builder()
+                    .insert_header(headers::SET_COOKIE, HeaderValue::from_string(cookie))
+                    .body(Body::from(format!("payment #{count}")))
+            })
+        };
+
+        let first = idempotency.on_request(user_request("Bearer ai", "abc", "10"), &next);
+        assert!(first.headers().get(headers::SET_COOKIE).is_some());
+
+        // The replayed response does not include the cookies
+        let replay = idempotency.on_request(user_request("Bearer ai", "abc", "10"), &next);
+        assert!(replay.headers().get(headers::SET_COOKIE).is_none());
+        assert_eq!(replay.into_body().read_all_bytes().unwrap(), b"payment #1");
+
+        // Other client with the same key
+        let other = idempotency.on_request(user_request("Bearer ruby", "abc", "10"), &next);
+        assert_eq!(other.into_body().read_all_bytes().unwrap(), b"payment #2");
+
+        // Same key with a different body
+        let res = idempotency.on_request(user_request("Bearer ai", "abc", "20"), &next);
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_CONTENT);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn should_evict_oldest_entry_when_full() {
+        let idempotency = Idempotency::new().max_entries(2);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let next = {
+            let calls = calls.clone();
+            BoxedHandler::new(move || calls.fetch_add(1, Ordering::SeqCst).to_string())
+        };
+
+        for key in ["a", "b", "c"] {
+            idempotency.on_request(user_request("Bearer ai", key, ""), &next);
+        }
+
+        assert_eq!(idempotency.store.lock().unwrap().len(), 2);
+
+        // `a` was evicted so the request is processed again
+        idempotency.on_request(user_request("Bearer ai", "a", ""), &next);
+        idempotency.on_request(user_request("Bearer ai", "c", ""), &next);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn should_not_store_streamed_response() {
+        let idempotency = Idempotency::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let next = {
+            let calls = calls.clone();
+            BoxedHandler::new(move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let reader = std::io::Cursor::new("data: hello\n\n");
+                Response::new(StatusCode::OK, Body::new(std::io::BufReader::new(reader)))
+            })
+        };
+
+        for _ in 0..2 {
+            let res = idempotency.on_request(user_request("Bearer ai", "abc", ""), &next);
+            assert!(res.body().size_hint().is_none());
+            assert_eq!(
+                res.into_body().read_all_bytes().unwrap(),
+                b"data: hello\n\n"
+            );
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn should_reject_body_larger_than_body_limit() {
+        let idempotency = Idempotency::new();
+        let next = BoxedHandler::new(|| "ok");
+
+        let mut req = user_request("Bearer ai", "abc", "a large request body");
+        req.extensions_mut().insert(http1::server::Config {
+            max_body_size: Some(8),
+            ..Default::default()
+        });
+
+        let res = idempotency.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn should_not_track_requests_of_unknown_clients() {
+        let idempotency = Idempotency::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let next = {
+            let calls = calls.clone();
+            BoxedHandler::new(move || calls.fetch_add(1, Ordering::SeqCst).to_string())
+        };
+
+        for _ in 0..2 {
+            let req = Request::builder()
+                .method(Method::POST)
+                .insert_header(headers::IDEMPOTENCY_KEY, "abc")
+                .body(Body::empty())
+                .unwrap();
+
+            idempotency.on_request(req, &next);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
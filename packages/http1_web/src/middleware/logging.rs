@@ -1,10 +1,18 @@
+use std::fmt::Write;
+use std::net::IpAddr;
 use std::time::Instant;
 
+use crate::client_ip::ClientIp;
 use crate::handler::BoxedHandler;
 use crate::middleware::Middleware;
+use datetime::DateTime;
+use http1::body::http_body::HttpBody;
 use http1::body::Body;
+use http1::headers::{self, Headers};
 use http1::request::Request;
 use http1::response::Response;
+use http1::status::StatusCode;
+
 /// A middleware that logs each request.
 pub struct Logging;
 
@@ -33,3 +41,217 @@ impl Middleware for Logging {
         response
     }
 }
+
+/// The format of the lines logged by the `AccessLog` middleware.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Apache Common Log Format: `ip - - [date] "METHOD path HTTP/1.1" status bytes`.
+    #[default]
+    Common,
+
+    /// Apache Combined Log Format, the Common Log Format followed by the `"referer" "user-agent"`.
+    Combined,
+}
+
+/// A middleware that logs each request as an access log line.
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    format: LogFormat,
+}
+
+impl AccessLog {
+    /// Constructs an `AccessLog` middleware using the given format.
+    pub fn new(format: LogFormat) -> Self {
+        AccessLog { format }
+    }
+
+    fn call(&self, req: Request<Body>, next: &BoxedHandler) -> (Response<Body>, String) {
+        let combined = self.format == LogFormat::Combined;
+        let time = DateTime::now_utc();
+        let ip = ClientIp::try_from_request(&req).map(|x| x.ip());
+        let request_line = format!(
+            "{} {} {}",
+            req.method(),
+            req.uri().path_and_query(),
+            req.version()
+        );
+        let (referer, user_agent) = if combined {
+            (
+                header_value(req.headers(), headers::REFERER),
+                header_value(req.headers(), headers::USER_AGENT),
+            )
+        } else {
+            (None, None)
+        };
+
+        let response = next.call(req);
+
+        let line = AccessLogLine {
+            ip,
+            time,
+            request_line,
+            status: response.status(),
+            bytes: response.body().size_hint(),
+            referer,
+            user_agent,
+        };
+
+        (response, line.to_line(combined))
+    }
+}
+
+impl Middleware for AccessLog {
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        let (response, line) = self.call(req, next);
+        log::info!("{line}");
+        response
+    }
+}
+
+fn header_value(headers: &Headers, name: headers::HeaderName) -> Option<String> {
+    headers.get(name).map(|x| x.as_str().to_owned())
+}
+
+/// The fields of an access log line.
+struct AccessLogLine {
+    ip: Option<IpAddr>,
+    time: DateTime,
+    request_line: String,
+    status: StatusCode,
+    bytes: Option<usize>,
+    referer: Option<String>,
+    user_agent: Option<String>,
+}
+
+impl AccessLogLine {
+    fn to_line(&self, combined: bool) -> String {
+        let mut line = String::new();
+
+        match self.ip {
+            Some(ip) => write!(line, "{ip}").unwrap(),
+            None => line.push('-'),
+        }
+
+        let time = &self.time;
+        write!(
+            line,
+            " - - [{:02}/{}/{}:{:02}:{:02}:{:02} +0000] ",
+            time.day_of_month(),
+            time.month().as_short_str(),
+            time.year(),
+            time.hours(),
+            time.minutes(),
+            time.secs()
+        )
+        .unwrap();
+
+        write_quoted(&mut line, Some(&self.request_line));
+        write!(line, " {}", self.status.as_u16()).unwrap();
+
+        // Apache logs `-` instead of `0` when no bytes were sent
+        match self.bytes {
+            Some(bytes) if bytes > 0 => write!(line, " {bytes}").unwrap(),
+            _ => line.push_str(" -"),
+        }
+
+        if combined {
+            line.push(' ');
+            write_quoted(&mut line, self.referer.as_deref());
+            line.push(' ');
+            write_quoted(&mut line, self.user_agent.as_deref());
+        }
+
+        line
+    }
+}
+
+fn write_quoted(line: &mut String, value: Option<&str>) {
+    let Some(value) = value else {
+        line.push_str("\"-\"");
+        return;
+    };
+
+    line.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            c => line.push(c),
+        }
+    }
+
+    line.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use datetime::{DateTime, Month};
+    use http1::{body::Body, headers, request::Request, status::StatusCode};
+
+    use crate::handler::BoxedHandler;
+
+    use super::{AccessLog, AccessLogLine, LogFormat};
+
+    #[test]
+    fn should_format_access_log_in_combined_format() {
+        let access_log = AccessLogLine {
+            ip: Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            time: DateTime::builder()
+                .year(2000)
+                .month(Month::October)
+                .day(10)
+                .hours(13)
+                .minutes(55)
+                .secs(36)
+                .build(),
+            request_line: "GET /apache_pb.gif?lang=en HTTP/1.1".to_owned(),
+            status: StatusCode::OK,
+            bytes: Some(2326),
+            referer: Some("http://www.example.com/start.html".to_owned()),
+            user_agent: Some("Mozilla/4.08 [en] (Win98; I ;Nav)".to_owned()),
+        };
+
+        assert_eq!(
+            access_log.to_line(true),
+            r#"127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif?lang=en HTTP/1.1" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)""#
+        );
+
+        let access_log = AccessLogLine {
+            ip: None,
+            bytes: Some(0),
+            referer: None,
+            ..access_log
+        };
+
+        assert_eq!(
+            access_log.to_line(true),
+            r#"- - - [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif?lang=en HTTP/1.1" 200 - "-" "Mozilla/4.08 [en] (Win98; I ;Nav)""#
+        );
+        assert_eq!(
+            access_log.to_line(false),
+            r#"- - - [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif?lang=en HTTP/1.1" 200 -"#
+        );
+    }
+
+    #[test]
+    fn should_log_request_through_middleware() {
+        let next = BoxedHandler::new(|| "Hello World!");
+        let req = Request::builder()
+            .uri("/hello?lang=en")
+            .insert_header(headers::X_FORWARDED_FOR, "10.0.0.1")
+            .insert_header(headers::USER_AGENT, "curl/8.0")
+            .body(Body::empty())
+            .unwrap();
+
+        let (res, line) = AccessLog::new(LogFormat::Combined).call(req, &next);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(line.starts_with("10.0.0.1 - - ["), "{line}");
+        assert!(
+            line.ends_with(r#"] "GET /hello?lang=en HTTP/1.1" 200 12 "-" "curl/8.0""#),
+            "{line}"
+        );
+    }
+}
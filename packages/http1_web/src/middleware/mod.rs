@@ -1,14 +1,25 @@
+pub mod concurrency_limit;
 pub mod cors;
+pub mod csrf;
 pub mod extensions;
+pub mod gzip;
+pub mod https_redirect;
+pub mod idempotency;
 pub mod logging;
 pub mod redirection;
+pub mod server_timing;
 pub mod sessions;
 pub mod timeout;
 
-use http1::{body::Body, request::Request, response::Response};
+use http1::{
+    body::Body,
+    headers::{HeaderName, HeaderValue},
+    request::Request,
+    response::Response,
+};
 use std::sync::Arc;
 
-use crate::handler::BoxedHandler;
+use crate::{handler::BoxedHandler, IntoResponse};
 
 pub trait Middleware {
     fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body>;
@@ -23,6 +34,68 @@ where
     }
 }
 
+/// The result of a middleware that either continues to the next handler or responds early.
+#[derive(Debug)]
+pub enum MiddlewareResult {
+    /// Calls the next handler with the request.
+    Next(Request<Body>),
+
+    /// Returns the response without calling the next handler.
+    Respond(Response<Body>),
+}
+
+impl MiddlewareResult {
+    /// Returns a `Respond` result with the given response.
+    pub fn respond(response: impl IntoResponse) -> Self {
+        MiddlewareResult::Respond(response.into_response())
+    }
+}
+
+/// A middleware that runs a function returning a [`MiddlewareResult`], making clear when the next handler is not called.
+///
+/// The headers added with [`ShortCircuit::header`] are only included in the `Respond` responses.
+pub struct ShortCircuit<F> {
+    f: F,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl<F> ShortCircuit<F>
+where
+    F: Fn(Request<Body>) -> MiddlewareResult,
+{
+    /// Constructs a new `ShortCircuit` middleware.
+    pub fn new(f: F) -> Self {
+        ShortCircuit {
+            f,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a header to the responses returned without calling the next handler.
+    pub fn header(mut self, name: HeaderName, value: impl Into<HeaderValue>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+impl<F> Middleware for ShortCircuit<F>
+where
+    F: Fn(Request<Body>) -> MiddlewareResult,
+{
+    fn on_request(&self, req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        match (self.f)(req) {
+            MiddlewareResult::Next(req) => next.call(req),
+            MiddlewareResult::Respond(mut response) => {
+                for (name, value) in &self.headers {
+                    response.headers_mut().insert(name.clone(), value.clone());
+                }
+
+                response
+            }
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 #[derive(Clone)]
 pub struct BoxedMiddleware {
@@ -61,3 +134,67 @@ impl std::fmt::Debug for BoxedMiddleware {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use http1::{
+        body::Body,
+        headers::{self, HeaderValue},
+        request::Request,
+        status::StatusCode,
+    };
+
+    use crate::handler::BoxedHandler;
+
+    use super::{Middleware, MiddlewareResult, ShortCircuit};
+
+    #[test]
+    fn should_not_call_next_on_respond() {
+        let called = Arc::new(AtomicBool::new(false));
+        let next = {
+            let called = called.clone();
+            BoxedHandler::new(move || {
+                called.store(true, Ordering::SeqCst);
+                "secret"
+            })
+        };
+
+        let auth = ShortCircuit::new(|req: Request<Body>| {
+            if req.headers().contains_key(headers::AUTHORIZATION) {
+                MiddlewareResult::Next(req)
+            } else {
+                MiddlewareResult::respond(StatusCode::UNAUTHORIZED)
+            }
+        })
+        .header(
+            headers::WWW_AUTHENTICATE,
+            HeaderValue::from_static("Bearer"),
+        );
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = auth.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            res.headers()
+                .get(headers::WWW_AUTHENTICATE)
+                .unwrap()
+                .as_str(),
+            "Bearer"
+        );
+        assert!(!called.load(Ordering::SeqCst));
+
+        let req = Request::builder()
+            .insert_header(headers::AUTHORIZATION, "Bearer token")
+            .body(Body::empty())
+            .unwrap();
+        let res = auth.on_request(req, &next);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(headers::WWW_AUTHENTICATE).is_none());
+        assert!(called.load(Ordering::SeqCst));
+    }
+}
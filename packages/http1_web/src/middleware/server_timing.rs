@@ -0,0 +1,140 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue},
+    payload::Payload,
+    request::Request,
+    response::Response,
+};
+
+use crate::{from_request::FromRequest, handler::BoxedHandler};
+
+use super::Middleware;
+
+#[derive(Debug, Clone)]
+struct Metric {
+    name: String,
+    duration: Duration,
+}
+
+/// The measurements of a request sent in the `Server-Timing` header.
+///
+/// Extract it in a handler to add measurements, if the `ServerTiming` middleware is not installed
+/// the measurements are discarded.
+#[derive(Debug, Default, Clone)]
+pub struct ServerTimings(Arc<Mutex<Vec<Metric>>>);
+
+impl ServerTimings {
+    /// Adds a measurement with the given name and duration.
+    pub fn add(&self, name: impl Into<String>, duration: Duration) {
+        let mut metrics = self.0.lock().expect("failed to lock server timings");
+        metrics.push(Metric {
+            name: name.into(),
+            duration,
+        });
+    }
+
+    /// Runs the given function and adds a measurement with the time it took.
+    pub fn measure<R>(&self, name: impl Into<String>, f: impl FnOnce() -> R) -> R {
+        let now = Instant::now();
+        let result = f();
+        self.add(name, now.elapsed());
+        result
+    }
+
+    fn to_header_value(&self, total: Duration) -> String {
+        let metrics = self.0.lock().expect("failed to lock server timings");
+        let mut value = String::new();
+
+        for metric in metrics.iter() {
+            value.push_str(&format_metric(&metric.name, metric.duration));
+            value.push_str(", ");
+        }
+
+        value.push_str(&format_metric("total", total));
+        value
+    }
+}
+
+fn format_metric(name: &str, duration: Duration) -> String {
+    let ms = duration.as_secs_f64() * 1000.0;
+    format!("{name};dur={ms:.3}")
+}
+
+impl FromRequest for ServerTimings {
+    type Rejection = Infallible;
+
+    fn from_request(req: &Request<()>, _payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Ok(req
+            .extensions()
+            .get::<ServerTimings>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// A middleware that adds the `Server-Timing` header with the time it took to handle the request.
+pub struct ServerTiming;
+
+impl Middleware for ServerTiming {
+    fn on_request(&self, mut req: Request<Body>, next: &BoxedHandler) -> Response<Body> {
+        let timings = ServerTimings::default();
+        req.extensions_mut().insert(timings.clone());
+
+        let now = Instant::now();
+        let mut response = next.call(req);
+        let value = timings.to_header_value(now.elapsed());
+
+        response
+            .headers_mut()
+            .append(headers::SERVER_TIMING, HeaderValue::from_string(value));
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http1::{body::Body, headers, request::Request};
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::{ServerTiming, ServerTimings};
+
+    fn get_duration(metric: &str) -> f64 {
+        let (_, dur) = metric.split_once(";dur=").unwrap();
+        dur.parse().unwrap()
+    }
+
+    #[test]
+    fn should_add_server_timing_header() {
+        let next = BoxedHandler::new(|timings: ServerTimings| {
+            timings.measure("db", || std::thread::sleep(Duration::from_millis(10)));
+            "Hello World!"
+        });
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = ServerTiming.on_request(req, &next);
+
+        let value = res.headers().get(headers::SERVER_TIMING).unwrap();
+        let metrics = value.as_str().split(", ").collect::<Vec<_>>();
+
+        assert_eq!(metrics.len(), 2);
+        assert!(metrics[0].starts_with("db;dur="));
+        assert!(metrics[1].starts_with("total;dur="));
+
+        let db = get_duration(metrics[0]);
+        let total = get_duration(metrics[1]);
+
+        assert!(db >= 10.0);
+        assert!(total >= db);
+        assert!(total < 10_000.0);
+    }
+}
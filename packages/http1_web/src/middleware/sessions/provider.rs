@@ -3,6 +3,7 @@ use std::{
     time::Duration,
 };
 
+use datetime::DateTime;
 use http1::{
     body::Body,
     headers::{self, HeaderValue},
@@ -23,11 +24,16 @@ use super::{
 
 pub type IdGenerator = fn(&Request<Body>) -> String;
 
+/// Returns the current date, used to check the sessions expiration.
+pub type Clock = fn() -> DateTime;
+
 pub struct SessionProvider<S> {
     store: Arc<Mutex<S>>,
     cookie_name: String,
     id_generator: IdGenerator,
-    max_age: u64,
+    clock: Clock,
+    ttl: Duration,
+    is_sliding: bool,
     path: String,
     is_http_only: bool,
 }
@@ -45,7 +51,9 @@ impl SessionProvider<()> {
 pub struct Builder {
     cookie_name: String,
     id_generator: IdGenerator,
-    max_age: u64,
+    clock: Clock,
+    ttl: Duration,
+    is_sliding: bool,
     is_http_only: bool,
     path: String,
 }
@@ -57,7 +65,9 @@ impl Builder {
             cookie_name: "session_id".into(),
             path: "/".into(),
             is_http_only: true,
-            max_age: 60,
+            clock: DateTime::now_utc,
+            ttl: Duration::from_secs(60),
+            is_sliding: false,
         }
     }
 
@@ -71,8 +81,26 @@ impl Builder {
         self
     }
 
-    pub fn max_age(mut self, max_age: u64) -> Self {
-        self.max_age = max_age;
+    /// Sets the session duration in seconds, same as `with_ttl`.
+    pub fn max_age(self, max_age: u64) -> Self {
+        self.with_ttl(Duration::from_secs(max_age))
+    }
+
+    /// Sets how long a session lives after it was created, or after the last request if is sliding.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Whether if renew the session expiration on each request.
+    pub fn sliding(mut self, is_sliding: bool) -> Self {
+        self.is_sliding = is_sliding;
+        self
+    }
+
+    /// Sets the function used to get the current date.
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
         self
     }
 
@@ -85,7 +113,9 @@ impl Builder {
         SessionProvider {
             id_generator: self.id_generator,
             cookie_name: self.cookie_name,
-            max_age: self.max_age,
+            clock: self.clock,
+            ttl: self.ttl,
+            is_sliding: self.is_sliding,
             path: self.path,
             is_http_only: self.is_http_only,
             store: Arc::new(Mutex::new(store)),
@@ -94,33 +124,41 @@ impl Builder {
 }
 
 impl<S: SessionStore> SessionProvider<S> {
-    fn get_or_create_session(&self, req: &Request<Body>) -> Result<(Session, bool), ErrorResponse> {
+    fn get_or_create_session(
+        &self,
+        req: &Request<Body>,
+        now: DateTime,
+    ) -> Result<(Session, bool), ErrorResponse> {
         let mut store = self.store.lock().map_err(|_| {
             log::error!("Failed to lock session store");
             ErrorResponse::from(ErrorStatusCode::InternalServerError)
         })?;
 
         let cookies = Cookies::from_headers(req.headers()).unwrap_or_default();
-
-        let mut is_new_session = false;
-        let session_id = match cookies.get(&self.cookie_name) {
-            Some(x) => x.value().to_owned(),
-            None => {
-                is_new_session = true;
-                (self.id_generator)(req)
-            }
-        };
-
         let config = LoadSessionConfig {
-            duration: Duration::from_secs(self.max_age),
+            duration: self.ttl,
+            now,
         };
 
-        let session = store.load_session(&session_id, &config).map_err(|err| {
-            log::error!("Failed to load session: {err}");
-            ErrorResponse::from(ErrorStatusCode::InternalServerError)
-        })?;
+        if let Some(cookie) = cookies.get(&self.cookie_name) {
+            let session = load_session(&mut *store, cookie.value(), &config)?;
 
-        Ok((session, is_new_session))
+            if session.status() != SessionStatus::New {
+                return Ok((session, false));
+            }
+
+            // The session is unknown or expired, the id sent by the client is not reused
+            let stale = session.clone();
+            session.destroy();
+            store.destroy_session(stale).map_err(|err| {
+                log::error!("Failed to destroy session: {err}");
+                ErrorResponse::from(ErrorStatusCode::InternalServerError)
+            })?;
+        }
+
+        let session_id = (self.id_generator)(req);
+        let session = load_session(&mut *store, &session_id, &config)?;
+        Ok((session, true))
     }
 }
 
@@ -130,25 +168,32 @@ impl<S: SessionStore> Middleware for SessionProvider<S> {
         mut req: Request<Body>,
         next: &crate::handler::BoxedHandler,
     ) -> http1::response::Response<Body> {
-        let (mut session, is_new_session) = match self.get_or_create_session(&req) {
+        let now = (self.clock)();
+        let (mut session, is_new_session) = match self.get_or_create_session(&req, now) {
             Ok(s) => s,
             Err(err) => {
                 return err.into_response();
             }
         };
 
+        // Extends the expiration of existing sessions
+        let is_renewed = self.is_sliding && !is_new_session;
+        if is_renewed {
+            session.renew(now + self.ttl);
+        }
+
         // Add the session to extensions
         req.extensions_mut().insert(session.clone());
 
         // Get the response
         let mut response = next.call(req);
 
-        // Sets the session cookie for new sessions
-        if is_new_session {
+        // Sets the session cookie for new or renewed sessions
+        if is_new_session || is_renewed {
             let cookie = Cookie::new(self.cookie_name.as_str(), session.id())
                 .path(self.path.as_str())
                 .http_only(self.is_http_only)
-                .max_age(self.max_age)
+                .max_age(self.ttl.as_secs())
                 .build();
 
             response.headers_mut().append(
@@ -157,25 +202,23 @@ impl<S: SessionStore> Middleware for SessionProvider<S> {
             );
         }
 
-        // If the session is destroyed or expired, destroy it
-        if session.is_expired() || session.is_destroyed() {
-            if session.is_expired() || session.is_destroyed() {
-                let mut store = match self.store.lock() {
-                    Ok(s) => s,
-                    Err(_) => {
-                        log::error!("Failed to lock session store");
-                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-                    }
-                };
-
-                if let Err(err) = store.destroy_session(session) {
-                    log::error!("Failed to destroy session: {err}");
+        // If the session is destroyed, remove it from the store
+        if session.is_destroyed() {
+            let mut store = match self.store.lock() {
+                Ok(s) => s,
+                Err(_) => {
+                    log::error!("Failed to lock session store");
                     return StatusCode::INTERNAL_SERVER_ERROR.into_response();
                 }
+            };
+
+            if let Err(err) = store.destroy_session(session) {
+                log::error!("Failed to destroy session: {err}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
             }
         }
-        // If modified save the new session
-        else if session.status() == SessionStatus::Modified {
+        // If new, modified or renewed save the session, new sessions are saved so they are not new on the next request
+        else if is_new_session || is_renewed || session.status() == SessionStatus::Modified {
             let mut store = match self.store.lock() {
                 Ok(s) => s,
                 Err(_) => {
@@ -198,8 +241,148 @@ impl<S: SessionStore> Middleware for SessionProvider<S> {
     }
 }
 
+fn load_session<S: SessionStore>(
+    store: &mut S,
+    session_id: &str,
+    config: &LoadSessionConfig,
+) -> Result<Session, ErrorResponse> {
+    store.load_session(session_id, config).map_err(|err| {
+        log::error!("Failed to load session: {err}");
+        ErrorResponse::from(ErrorStatusCode::InternalServerError)
+    })
+}
+
 fn generate_session_id(_req: &Request<Body>) -> String {
     rng::sequence::<rng::Alphanumeric>()
         .take(36)
         .collect::<String>()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    };
+
+    use datetime::DateTime;
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers,
+        request::Request,
+    };
+
+    use crate::{
+        handler::BoxedHandler,
+        middleware::{
+            sessions::{session::Session, store::MemoryStore},
+            Middleware,
+        },
+    };
+
+    use super::SessionProvider;
+
+    fn counter() -> BoxedHandler {
+        BoxedHandler::new(|mut session: Session| {
+            session
+                .update_or_insert("count", 1, |x: u32| x + 1)
+                .unwrap();
+
+            session.get::<u32>("count").unwrap().unwrap().to_string()
+        })
+    }
+
+    fn send(provider: &SessionProvider<MemoryStore>, session_id: Option<&str>) -> (String, String) {
+        let mut builder = Request::builder();
+
+        if let Some(session_id) = session_id {
+            builder = builder.insert_header(headers::COOKIE, format!("session_id={session_id}"));
+        }
+
+        let req = builder.body(Body::empty()).unwrap();
+        let res = provider.on_request(req, &counter());
+        let session_id = res
+            .headers()
+            .get(headers::SET_COOKIE)
+            .map(|x| x.as_str().split_once(';').unwrap().0)
+            .and_then(|x| x.strip_prefix("session_id="))
+            .map(|x| x.to_owned())
+            .unwrap_or_default();
+
+        let body = res.into_body().read_all_bytes().unwrap();
+        (session_id, String::from_utf8(body).unwrap())
+    }
+
+    #[test]
+    fn should_expire_session_after_ttl() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+
+        let provider = SessionProvider::builder()
+            .clock(|| DateTime::with_millis(NOW.load(Ordering::Relaxed) as u128))
+            .max_age(60)
+            .store(MemoryStore::new());
+
+        let (session_id, count) = send(&provider, None);
+        assert_eq!(count, "1");
+
+        NOW.store(30_000, Ordering::Relaxed);
+        assert_eq!(send(&provider, Some(&session_id)).1, "2");
+
+        // Not renewed, expires 60 seconds after created and a new id is generated
+        NOW.store(61_000, Ordering::Relaxed);
+        let (new_session_id, count) = send(&provider, Some(&session_id));
+        assert_eq!(count, "1");
+        assert!(!new_session_id.is_empty());
+        assert_ne!(new_session_id, session_id);
+    }
+
+    #[test]
+    fn should_renew_sliding_session_on_each_request() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+
+        let provider = SessionProvider::builder()
+            .clock(|| DateTime::with_millis(NOW.load(Ordering::Relaxed) as u128))
+            .with_ttl(Duration::from_secs(60))
+            .sliding(true)
+            .store(MemoryStore::new());
+
+        let (session_id, count) = send(&provider, None);
+        assert_eq!(count, "1");
+
+        for (now, expected) in [(40_000, "2"), (80_000, "3"), (120_000, "4")] {
+            NOW.store(now, Ordering::Relaxed);
+            let (renewed_session_id, count) = send(&provider, Some(&session_id));
+            assert_eq!(renewed_session_id, session_id);
+            assert_eq!(count, expected);
+        }
+
+        // Expires 60 seconds after the last request
+        NOW.store(181_000, Ordering::Relaxed);
+        assert_eq!(send(&provider, Some(&session_id)).1, "1");
+    }
+
+    #[test]
+    fn should_only_send_cookie_once_for_unmodified_session() {
+        let provider = SessionProvider::new(MemoryStore::new());
+        let handler = BoxedHandler::new(|_: Session| "Hello");
+        let send = |cookie: Option<String>| {
+            let mut builder = Request::builder();
+            if let Some(cookie) = cookie {
+                builder = builder.insert_header(headers::COOKIE, cookie);
+            }
+
+            let res = provider.on_request(builder.body(Body::empty()).unwrap(), &handler);
+            res.headers()
+                .get(headers::SET_COOKIE)
+                .map(|x| x.as_str().split_once(';').unwrap().0.to_owned())
+        };
+
+        let cookie = send(None).unwrap();
+        assert_eq!(send(Some(cookie.clone())), None);
+        assert_eq!(send(Some(cookie)), None);
+
+        // Unknown ids are not reused
+        let cookie = send(Some(String::from("session_id=forged"))).unwrap();
+        assert_ne!(cookie, "session_id=forged");
+    }
+}
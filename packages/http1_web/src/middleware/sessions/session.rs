@@ -212,9 +212,24 @@ impl Session {
         Ok(())
     }
 
+    /// Returns the date this session expires.
+    pub fn expires_at(&self) -> DateTime {
+        self.expires_at
+    }
+
+    /// Sets a new expiration date for this session.
+    pub fn renew(&mut self, expires_at: DateTime) {
+        self.expires_at = expires_at;
+    }
+
     /// Whether if this session is expired.
     pub fn is_expired(&self) -> bool {
-        DateTime::now_utc() > self.expires_at
+        self.is_expired_at(DateTime::now_utc())
+    }
+
+    /// Whether if this session is expired at the given date.
+    pub fn is_expired_at(&self, now: DateTime) -> bool {
+        now > self.expires_at
     }
 
     /// Remove this session from this user.
@@ -230,16 +245,25 @@ impl Session {
 
     /// Whether if this session is expired or destroyed.
     pub fn is_valid(&self) -> bool {
-        !self.is_expired() && !self.is_destroyed()
+        self.is_valid_at(DateTime::now_utc())
+    }
+
+    /// Whether if this session is expired at the given date or destroyed.
+    pub fn is_valid_at(&self, now: DateTime) -> bool {
+        !self.is_expired_at(now) && !self.is_destroyed()
     }
 }
 
+/// Rejection returned when the `Session` is extracted without the `SessionProvider` middleware.
 #[derive(Debug)]
 pub struct SessionError;
 
 impl Display for SessionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to retrieve session")
+        write!(
+            f,
+            "Failed to retrieve session, `SessionProvider` middleware not installed"
+        )
     }
 }
 
@@ -265,3 +289,25 @@ impl FromRequest for Session {
             .ok_or(SessionError)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http1::{payload::Payload, request::Request};
+
+    use crate::from_request::FromRequest;
+
+    use super::Session;
+
+    #[test]
+    fn should_fail_to_extract_session_without_provider() {
+        let req = Request::builder().body(()).unwrap();
+        let err = Session::from_request(&req, &mut Payload::None)
+            .err()
+            .expect("session should not be available");
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to retrieve session, `SessionProvider` middleware not installed"
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
 
 use super::session::Session;
 use datetime::DateTime;
@@ -8,6 +13,9 @@ use http1::error::BoxError;
 pub struct LoadSessionConfig {
     /// The duration for a new created session.
     pub duration: Duration,
+
+    /// The current date, sessions that expired before it are discarded.
+    pub now: DateTime,
 }
 
 /// Provides a way to create, update, read and delete sessions.
@@ -42,13 +50,18 @@ impl Default for MemoryStore {
 }
 
 impl MemoryStore {
-    fn get_session(&self, session_id: &str) -> Option<&Session> {
-        self.0.get(session_id).filter(|x| x.is_valid())
+    fn get_session(&mut self, session_id: &str, now: DateTime) -> Option<&Session> {
+        // Evict the session if is no longer valid
+        if self.0.get(session_id).is_some_and(|x| !x.is_valid_at(now)) {
+            self.0.remove(session_id);
+        }
+
+        self.0.get(session_id)
     }
 
     fn create_session(&mut self, session_id: &str, config: &LoadSessionConfig) -> Session {
         let session_id: Arc<str> = session_id.into();
-        let expires_at = DateTime::now_utc() + config.duration;
+        let expires_at = config.now + config.duration;
         let session = Session::new(session_id.as_ref(), expires_at);
         self.0.insert(session_id.clone(), session.clone());
         session
@@ -61,7 +74,7 @@ impl SessionStore for MemoryStore {
         session_id: &str,
         config: &LoadSessionConfig,
     ) -> Result<Session, BoxError> {
-        match self.get_session(session_id) {
+        match self.get_session(session_id, config.now) {
             Some(session) => Ok(session.clone()),
             None => Ok(self.create_session(session_id, config)),
         }
@@ -90,3 +103,257 @@ impl SessionStore for MemoryStore {
         Ok(())
     }
 }
+
+/// Min time between writes to the sessions file.
+const DEFAULT_WRITE_DELAY: Duration = Duration::from_secs(1);
+
+/// A store that persists the sessions to a `json` file.
+///
+/// The writes are debounced, changes made before the write delay elapses are written by a background
+/// thread once it elapses, on [`FileStore::flush`] or when the store is dropped.
+pub struct FileStore {
+    path: PathBuf,
+    write_delay: Duration,
+    state: Arc<Mutex<FileStoreState>>,
+}
+
+struct FileStoreState {
+    sessions: HashMap<String, Session>,
+    last_write: Option<Instant>,
+    is_dirty: bool,
+    is_write_scheduled: bool,
+}
+
+impl FileStore {
+    /// Constructs a store using the given file, loading the sessions that are not expired.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, BoxError> {
+        let path = path.into();
+        let mut sessions = match std::fs::read(&path) {
+            Ok(bytes) if bytes.is_empty() => HashMap::new(),
+            Ok(bytes) => serde::json::from_bytes::<HashMap<String, Session>>(bytes)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        sessions.retain(|_, session| session.is_valid());
+
+        Ok(FileStore {
+            path,
+            write_delay: DEFAULT_WRITE_DELAY,
+            state: Arc::new(Mutex::new(FileStoreState {
+                sessions,
+                last_write: None,
+                is_dirty: false,
+                is_write_scheduled: false,
+            })),
+        })
+    }
+
+    /// Sets the min time between writes to the file.
+    pub fn write_delay(mut self, write_delay: Duration) -> Self {
+        self.write_delay = write_delay;
+        self
+    }
+
+    /// Writes any pending changes to the file.
+    pub fn flush(&self) -> Result<(), BoxError> {
+        let mut state = self.lock()?;
+        if state.is_dirty {
+            write_sessions(&self.path, &mut state)?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, FileStoreState>, BoxError> {
+        self.state
+            .lock()
+            .map_err(|_| String::from("failed to lock file session store").into())
+    }
+
+    fn on_change(&self, state: &mut FileStoreState) -> Result<(), BoxError> {
+        state.is_dirty = true;
+
+        let elapsed = state.last_write.map(|last_write| last_write.elapsed());
+        match elapsed {
+            Some(elapsed) if elapsed < self.write_delay => {
+                if !state.is_write_scheduled {
+                    state.is_write_scheduled = true;
+                    self.schedule_write(self.write_delay - elapsed);
+                }
+            }
+            _ => write_sessions(&self.path, state)?,
+        }
+
+        Ok(())
+    }
+
+    /// Writes the pending changes after the given delay, unless the store was dropped before.
+    fn schedule_write(&self, delay: Duration) {
+        let path = self.path.clone();
+        let state = Arc::downgrade(&self.state);
+
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+
+            state.is_write_scheduled = false;
+            if !state.is_dirty {
+                return;
+            }
+
+            if let Err(err) = write_sessions(&path, &mut state) {
+                log::error!("Failed to write sessions to `{}`: {err}", path.display());
+            }
+        });
+    }
+}
+
+fn write_sessions(path: &Path, state: &mut FileStoreState) -> Result<(), BoxError> {
+    state.sessions.retain(|_, session| session.is_valid());
+
+    // Write to a temporary file first to avoid leaving a partially written file
+    let bytes = serde::json::to_bytes(&state.sessions)?;
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, path)?;
+
+    state.last_write = Some(Instant::now());
+    state.is_dirty = false;
+    Ok(())
+}
+
+impl SessionStore for FileStore {
+    fn load_session(
+        &mut self,
+        session_id: &str,
+        config: &LoadSessionConfig,
+    ) -> Result<Session, BoxError> {
+        let mut state = self.lock()?;
+
+        match state.sessions.get(session_id) {
+            Some(session) if session.is_valid_at(config.now) => return Ok(session.clone()),
+            Some(_) => {
+                // Evict the session if is no longer valid
+                state.sessions.remove(session_id);
+            }
+            None => {}
+        }
+
+        let expires_at = config.now + config.duration;
+        let session = Session::new(session_id, expires_at);
+        state
+            .sessions
+            .insert(session_id.to_owned(), session.clone());
+        self.on_change(&mut state)?;
+        Ok(session)
+    }
+
+    fn save_session(&mut self, session: Session) -> Result<(), BoxError> {
+        let mut state = self.lock()?;
+        state.sessions.insert(session.id().to_owned(), session);
+        self.on_change(&mut state)
+    }
+
+    fn destroy_session(&mut self, session: Session) -> Result<(), BoxError> {
+        if !session.is_destroyed() {
+            return Err(String::from("Session was not marked as destroyed").into());
+        }
+
+        let mut state = self.lock()?;
+        state.sessions.remove(session.id());
+        self.on_change(&mut state)
+    }
+}
+
+impl Drop for FileStore {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            log::error!(
+                "Failed to write sessions to `{}`: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use datetime::DateTime;
+
+    use super::{FileStore, LoadSessionConfig, SessionStore};
+
+    #[test]
+    fn should_keep_sessions_after_recreating_file_store() {
+        let path = std::env::temp_dir().join(format!("sessions_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let config = LoadSessionConfig {
+            duration: Duration::from_secs(60),
+            now: DateTime::now_utc(),
+        };
+
+        {
+            let mut store = FileStore::new(&path).unwrap();
+            let mut session = store.load_session("abc", &config).unwrap();
+            session.insert("user", String::from("Ayaka")).unwrap();
+            store.save_session(session).unwrap();
+        }
+
+        let mut store = FileStore::new(&path).unwrap();
+        let session = store.load_session("abc", &config).unwrap();
+        assert_eq!(
+            session.get::<String>("user").unwrap(),
+            Some(String::from("Ayaka"))
+        );
+
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn should_write_debounced_changes_after_delay() {
+        let path = std::env::temp_dir().join(format!("sessions_delay_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let config = LoadSessionConfig {
+            duration: Duration::from_secs(60),
+            now: DateTime::now_utc(),
+        };
+
+        let mut store = FileStore::new(&path)
+            .unwrap()
+            .write_delay(Duration::from_millis(50));
+
+        // The first change is written, the second is debounced
+        let mut session = store.load_session("abc", &config).unwrap();
+        session.insert("user", String::from("Ayaka")).unwrap();
+        store.save_session(session).unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            if contents.contains("\"Modified\"") {
+                break;
+            }
+
+            assert!(
+                std::time::Instant::now() < deadline,
+                "the debounced change was not written: {contents}"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
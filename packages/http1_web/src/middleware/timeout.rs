@@ -6,11 +6,18 @@ use crate::{handler::BoxedHandler, IntoResponse};
 
 use super::Middleware;
 
+/// Responds with `503 Service Unavailable` if the handler takes longer than the timeout.
+///
+/// Handlers are synchronous, so each request runs in its own thread while the middleware waits for the response.
+/// When the timeout is reached the thread is abandoned, it keeps running until the handler returns
+/// but its response is discarded.
+#[derive(Debug, Clone)]
 pub struct Timeout {
     timeout: Duration,
 }
 
 impl Timeout {
+    /// Constructs a `Timeout` that waits at most `timeout` for the response of the handler.
     pub fn new(timeout: Duration) -> Self {
         Timeout { timeout }
     }
@@ -33,9 +40,48 @@ impl Middleware for Timeout {
                 log::error!("Timeout sender disconnected");
                 StatusCode::INTERNAL_SERVER_ERROR.into_response()
             }
-            Err(RecvTimeoutError::Timeout) => Response::builder()
-                .status(StatusCode::REQUEST_TIMEOUT)
-                .body(Body::empty()),
+            Err(RecvTimeoutError::Timeout) => {
+                log::warn!("Request timed out after {timeout:?}");
+                StatusCode::SERVICE_UNAVAILABLE.into_response()
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use http1::{body::Body, request::Request, status::StatusCode};
+
+    use crate::{handler::BoxedHandler, middleware::Middleware};
+
+    use super::Timeout;
+
+    #[test]
+    fn should_respond_503_when_handler_is_too_slow() {
+        let timeout = Timeout::new(Duration::from_millis(50));
+        let next = BoxedHandler::new(|| {
+            std::thread::sleep(Duration::from_millis(200));
+            "Done"
+        });
+
+        let start = Instant::now();
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = timeout.on_request(req, &next);
+
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(start.elapsed() < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn should_return_response_of_fast_handler() {
+        let timeout = Timeout::new(Duration::from_millis(500));
+        let next = BoxedHandler::new(|| "Done");
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let res = timeout.on_request(req, &next);
+
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
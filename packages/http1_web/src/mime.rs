@@ -104,23 +104,22 @@ impl Mime {
     }
 
     /// Check if this mime matches the other.
+    ///
+    /// The type and subtype of this mime can be a `*` wildcard, like `text/*` or `*/*`.
+    /// The parameter is only compared if this mime has one, so `text/plain` matches `text/plain;charset=utf-8`.
     pub fn matches(&self, other: &Mime) -> bool {
-        match (self.ty(), other.ty()) {
-            ("*", "*") | ("*", _) | (_, "*") => {}
-            (t1, t2) if t1 != t2 => return false,
-            _ => {}
+        fn matches_part(pattern: &str, value: &str) -> bool {
+            pattern == "*" || value == "*" || pattern.eq_ignore_ascii_case(value)
         }
 
-        match (self.subtype(), other.subtype()) {
-            ("*", "*") | ("*", _) | (_, "*") => {}
-            (s1, s2) if s1 != s2 => return false,
-            _ => {}
+        if !matches_part(self.ty(), other.ty()) || !matches_part(self.subtype(), other.subtype()) {
+            return false;
         }
 
-        match (&self.parameter, &other.parameter) {
-            (None, None) => true,
-            (Some(p1), Some(p2)) => p1 == p2,
-            _ => false,
+        match (self.parameter(), other.parameter()) {
+            (None, _) => true,
+            (Some(p1), Some(p2)) => p1.eq_ignore_ascii_case(p2),
+            (Some(_), None) => false,
         }
     }
 }
@@ -146,6 +145,7 @@ impl Mime {
             ("*", "*", None) => Some(Mime::ANY),
             ("image", "*", None) => Some(Mime::ANY_IMAGE),
             ("audio", "*", None) => Some(Mime::ANY_AUDIO),
+            ("video", "*", None) => Some(Mime::ANY_VIDEO),
             ("text", "*", None) => Some(Mime::ANY_TEXT),
             _ => None,
         }
@@ -276,6 +276,7 @@ declare_mime_types! {
     APPLICATION_OCTET_STREAM => ["bin", "application", "octet-stream", None],
     APPLICATION_JSON => ["json", "application", "json", None],
     APPLICATION_JSON_UTF8 => ["json", "application", "json", Some("charset=utf-8")],
+    APPLICATION_PROBLEM_JSON => ["json", "application", "problem+json", None],
     APPLICATION_JAVASCRIPT => ["js", "application", "javascript", None],
     APPLICATION_XML => ["xml", "application", "xml", None],
     APPLICATION_PDF => ["pdf", "application", "pdf", None],
@@ -552,6 +553,36 @@ mod tests {
         assert!(mime21.matches(&mime22));
     }
 
+    #[test]
+    fn should_match_wildcard_mime_types() {
+        let text_plain = Mime::from_str("text/plain").unwrap();
+        let text_html_utf8 = Mime::from_str("text/html;charset=utf-8").unwrap();
+        let image_png = Mime::from_str("image/png").unwrap();
+
+        assert!(Mime::from_str("text/*").unwrap().matches(&text_plain));
+        assert!(Mime::ANY_TEXT.matches(&text_html_utf8));
+        assert!(!Mime::ANY_TEXT.matches(&image_png));
+        assert!(Mime::ANY.matches(&image_png));
+        assert!(Mime::from_str("*/*").unwrap().matches(&text_html_utf8));
+        assert!(Mime::ANY_IMAGE.matches(&image_png));
+        assert!(!Mime::ANY_AUDIO.matches(&image_png));
+        assert!(Mime::ANY_VIDEO.matches(&Mime::from_str("video/mp4").unwrap()));
+        assert!(!Mime::ANY_VIDEO.matches(&Mime::from_str("audio/mp4").unwrap()));
+        assert!(Mime::from_str("TEXT/Plain").unwrap().matches(&text_plain));
+    }
+
+    #[test]
+    fn should_compare_parameter_only_if_present() {
+        let text_html = Mime::from_str("text/html").unwrap();
+        let text_html_utf8 = Mime::from_str("text/html;charset=utf-8").unwrap();
+        let text_html_ascii = Mime::from_str("text/html;charset=us-ascii").unwrap();
+
+        assert!(text_html.matches(&text_html_utf8));
+        assert!(text_html_utf8.matches(&text_html_utf8));
+        assert!(!text_html_utf8.matches(&text_html));
+        assert!(!text_html_utf8.matches(&text_html_ascii));
+    }
+
     #[test]
     fn should_return_correct_extension() {
         assert_eq!(Mime::APPLICATION_OCTET_STREAM.extension(), Some("bin"));
@@ -1,12 +1,18 @@
 use http1::{error::BoxError, status::StatusCode};
+use orderedmap::OrderedMap;
 
-use crate::{from_request::FromRequest, routing::params::ParamsMap, IntoResponse};
+use crate::{
+    from_request::FromRequest,
+    fs::{SafePath, UnsafePathError},
+    routing::{params::ParamsMap, route::get_segments, route_info::RouteInfo},
+    ErrorResponse, ErrorStatusCode, IntoResponse,
+};
 use serde::{
     de::{Deserialize, Deserializer},
     string::{DeserializeFromStr, DeserializeOnlyString},
     visitor::{MapAccess, SeqAccess},
 };
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, ops::Deref, str::FromStr};
 
 /// Represents the path params in a route.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,8 +47,10 @@ impl IntoResponse for PathRejectionError {
         log::error!("{self}");
         match self {
             PathRejectionError::NotParamsMap => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
-            PathRejectionError::DeserializationError(_) => {
-                StatusCode::UNPROCESSABLE_CONTENT.into_response()
+            // The route matched but a param is malformed, so is not a `404 Not Found`
+            PathRejectionError::DeserializationError(error) => {
+                ErrorResponse::from_error(ErrorStatusCode::BadRequest, error.to_string())
+                    .into_response()
             }
         }
     }
@@ -70,8 +78,158 @@ impl<T: Deserialize> FromRequest for Path<T> {
     }
 }
 
+/// All the params captured by the matched route by name, in the order they appear in the route.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PathParams(pub OrderedMap<String, String>);
+
+impl PathParams {
+    pub fn into_inner(self) -> OrderedMap<String, String> {
+        self.0
+    }
+}
+
+impl Deref for PathParams {
+    type Target = OrderedMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for PathParams {
+    type Rejection = PathRejectionError;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        _payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let params_map = req
+            .extensions()
+            .get::<ParamsMap>()
+            .cloned()
+            .ok_or(PathRejectionError::NotParamsMap)?;
+
+        Ok(PathParams(params_map.0))
+    }
+}
+
+/// Extracts the rest of the path matched by the catch-all segment of a route like `/static/*` or `/static/:rest*`.
+///
+/// The request path is already percent-decoded by the server, so `/static/%2e%2e/secret` is rejected like `/static/../secret`.
+/// Paths that contain `..` or that are absolute are rejected with `400 Bad Request`,
+/// so the path can be safely joined to a directory.
+///
+/// # Example
+/// ```rust,ignore
+/// app.get("/files/*", |wildcard: Wildcard| ServeFile::new(Path::new("files").join(wildcard.as_path())));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Wildcard(String);
+
+impl Wildcard {
+    /// Returns the rest of the path, without leading `/`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the rest of the path as a relative file system path.
+    pub fn as_path(&self) -> &std::path::Path {
+        std::path::Path::new(&self.0)
+    }
+
+    /// Returns the rest of the path.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Display for Wildcard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum WildcardRejection {
+    NoCatchAll,
+    UnsafePath(UnsafePathError),
+}
+
+impl Display for WildcardRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WildcardRejection::NoCatchAll => {
+                write!(f, "the route does not have a catch-all segment")
+            }
+            WildcardRejection::UnsafePath(error) => write!(f, "invalid path: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for WildcardRejection {}
+
+impl IntoResponse for WildcardRejection {
+    fn into_response(self) -> http1::response::Response<http1::body::Body> {
+        log::error!("{self}");
+        match self {
+            WildcardRejection::NoCatchAll => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            WildcardRejection::UnsafePath(error) => {
+                ErrorResponse::from_error(ErrorStatusCode::BadRequest, error.to_string())
+                    .into_response()
+            }
+        }
+    }
+}
+
+impl FromRequest for Wildcard {
+    type Rejection = WildcardRejection;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        _payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        let route = req
+            .extensions()
+            .get::<RouteInfo>()
+            .filter(|route| route.iter().any(|x| x.is_catch_all()))
+            .ok_or(WildcardRejection::NoCatchAll)?;
+
+        // The catch-all is the last segment, so the rest starts after the other segments
+        let skip = route.iter().filter(|x| !x.is_catch_all()).count();
+        let rest = get_segments(req.uri().path_and_query().path())
+            .skip(skip)
+            .collect::<Vec<_>>()
+            .join("/");
+
+        SafePath::new(&rest).map_err(WildcardRejection::UnsafePath)?;
+        Ok(Wildcard(rest))
+    }
+}
+
 pub struct PathDeserializer(ParamsMap);
 
+impl PathDeserializer {
+    /// Parses the first param, the error includes the name of the param.
+    fn parse_first<T>(&self) -> Result<T, serde::de::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (name, value) = self
+            .0
+            .iter()
+            .next()
+            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
+
+        T::from_str(value).map_err(|err| invalid_param(name, err))
+    }
+}
+
+fn invalid_param(name: &str, error: impl Display) -> serde::de::Error {
+    serde::de::Error::other(format!("invalid path param `{name}`: {error}"))
+}
+
 impl Deserializer for PathDeserializer {
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, serde::de::Error>
     where
@@ -95,12 +253,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = bool::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<bool>()?;
         visitor.visit_bool(value)
     }
 
@@ -108,12 +261,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = u8::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<u8>()?;
         visitor.visit_u8(value)
     }
 
@@ -121,12 +269,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = u16::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<u16>()?;
         visitor.visit_u16(value)
     }
 
@@ -134,12 +277,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = u32::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<u32>()?;
         visitor.visit_u32(value)
     }
 
@@ -147,12 +285,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = u64::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<u64>()?;
         visitor.visit_u64(value)
     }
 
@@ -160,12 +293,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = u128::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<u128>()?;
         visitor.visit_u128(value)
     }
 
@@ -173,12 +301,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = i8::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<i8>()?;
         visitor.visit_i8(value)
     }
 
@@ -186,12 +309,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = i16::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<i16>()?;
         visitor.visit_i16(value)
     }
 
@@ -199,12 +317,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = i32::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<i32>()?;
         visitor.visit_i32(value)
     }
 
@@ -212,12 +325,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = i64::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<i64>()?;
         visitor.visit_i64(value)
     }
 
@@ -225,12 +333,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = i128::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<i128>()?;
         visitor.visit_i128(value)
     }
 
@@ -238,12 +341,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = f32::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<f32>()?;
         visitor.visit_f32(value)
     }
 
@@ -251,12 +349,7 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        let value = f64::from_str(s).map_err(serde::de::Error::other)?;
+        let value = self.parse_first::<f64>()?;
         visitor.visit_f64(value)
     }
 
@@ -264,23 +357,8 @@ impl Deserializer for PathDeserializer {
     where
         V: serde::visitor::Visitor,
     {
-        let s = self
-            .0
-            .get_index(0)
-            .ok_or_else(|| serde::de::Error::other("cannot get first param of the path"))?;
-
-        if s.is_empty() {
-            return Err(serde::de::Error::other("expected char but was empty"));
-        }
-
-        if s.len() != 1 {
-            return Err(serde::de::Error::other(format!(
-                "cannot deserialize `{s}` to char"
-            )));
-        }
-
-        let char = s.chars().next().expect("unable to get char");
-        visitor.visit_char(char)
+        let value = self.parse_first::<char>()?;
+        visitor.visit_char(value)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, serde::de::Error>
@@ -345,8 +423,9 @@ struct ParamsSeqAccess<I>(I);
 impl<I: Iterator<Item = (String, String)>> SeqAccess for ParamsSeqAccess<I> {
     fn next_element<D: serde::de::Deserialize>(&mut self) -> Result<Option<D>, serde::de::Error> {
         match self.0.next() {
-            Some((_, value)) => {
-                let v = D::deserialize(DeserializeFromStr::Str(value))?;
+            Some((name, value)) => {
+                let v = D::deserialize(DeserializeFromStr::Str(value))
+                    .map_err(|err| invalid_param(&name, err))?;
                 Ok(Some(v))
             }
             None => Ok(None),
@@ -356,14 +435,14 @@ impl<I: Iterator<Item = (String, String)>> SeqAccess for ParamsSeqAccess<I> {
 
 struct ParamsMapAccess<I> {
     iter: I,
-    value: Option<String>,
+    value: Option<(String, String)>,
 }
 
 impl<I: Iterator<Item = (String, String)>> MapAccess for ParamsMapAccess<I> {
     fn next_key<K: serde::de::Deserialize>(&mut self) -> Result<Option<K>, serde::de::Error> {
         match self.iter.next() {
             Some((k, v)) => {
-                self.value = Some(v);
+                self.value = Some((k.clone(), v));
                 let key = K::deserialize(DeserializeOnlyString(k))?;
                 Ok(Some(key))
             }
@@ -373,11 +452,117 @@ impl<I: Iterator<Item = (String, String)>> MapAccess for ParamsMapAccess<I> {
 
     fn next_value<V: serde::de::Deserialize>(&mut self) -> Result<Option<V>, serde::de::Error> {
         match self.value.take() {
-            Some(x) => {
-                let value = V::deserialize(DeserializeFromStr::Str(x))?;
+            Some((name, x)) => {
+                let value = V::deserialize(DeserializeFromStr::Str(x))
+                    .map_err(|err| invalid_param(&name, err))?;
                 Ok(Some(value))
             }
             None => Ok(None),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        handler::RequestHandler,
+        method::Method,
+        request::Request,
+        status::StatusCode,
+        uri::uri::Uri,
+    };
+
+    use crate::app::App;
+
+    use super::{Path, PathParams, Wildcard};
+
+    #[test]
+    fn should_respond_bad_request_for_malformed_param() {
+        let app = App::new().get("/users/:id", |Path(id): Path<u64>| format!("user {id}"));
+
+        let get = |path: &str| {
+            let res = app.handle(Request::new(
+                Method::GET,
+                Uri::from_str(path).unwrap(),
+                Body::empty(),
+            ));
+
+            let status = res.status();
+            let body = res.into_body().read_all_bytes().unwrap();
+            (status, String::from_utf8(body).unwrap())
+        };
+
+        assert_eq!(get("/users/12"), (StatusCode::OK, String::from("user 12")));
+
+        let (status, body) = get("/users/abc");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("`id`"), "{body}");
+
+        let (status, _) = get("/unknown");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn should_extract_all_path_params() {
+        let extracted = Arc::new(Mutex::new(None));
+
+        let app = {
+            let extracted = extracted.clone();
+            App::new().get("/a/:x/b/:y", move |params: PathParams| {
+                *extracted.lock().unwrap() = Some(params);
+            })
+        };
+
+        app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/a/first/b/second").unwrap(),
+            Body::empty(),
+        ));
+
+        let params = extracted.lock().unwrap().take().unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get("x").map(|x| x.as_str()), Some("first"));
+        assert_eq!(params.get("y").map(|x| x.as_str()), Some("second"));
+
+        let keys = params.keys().collect::<Vec<_>>();
+        assert_eq!(keys, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn should_extract_wildcard_and_reject_path_traversal() {
+        let app = App::new().get("/static/*", |wildcard: Wildcard| wildcard.into_inner());
+
+        let get = |path: &str| {
+            let res = app.handle(Request::new(
+                Method::GET,
+                Uri::from_str(path).unwrap(),
+                Body::empty(),
+            ));
+
+            let status = res.status();
+            let body = res.into_body().read_all_bytes().unwrap();
+            (status, String::from_utf8(body).unwrap())
+        };
+
+        assert_eq!(
+            get("/static/css/site main.css"),
+            (StatusCode::OK, String::from("css/site main.css"))
+        );
+        assert_eq!(get("/static"), (StatusCode::OK, String::new()));
+
+        let (status, _) = get("/static/../etc/passwd");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = get("/static/css/../../etc/passwd");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = get("/static//etc/passwd");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
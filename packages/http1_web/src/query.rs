@@ -274,6 +274,19 @@ mod tests {
         assert_eq!(query.get("bool").unwrap(), "true");
     }
 
+    #[test]
+    fn should_decode_query_values() {
+        let query = deserialize_query::<HashMap<String, String>>(
+            "/path?q=hello%20world&path=a%2Fb&and=x%26y%3Dz&plus=1+2&bad=%ZZ",
+        );
+
+        assert_eq!(query.get("q").unwrap(), "hello world");
+        assert_eq!(query.get("path").unwrap(), "a/b");
+        assert_eq!(query.get("and").unwrap(), "x&y=z");
+        assert_eq!(query.get("plus").unwrap(), "1 2");
+        assert_eq!(query.get("bad").unwrap(), "%ZZ");
+    }
+
     #[test]
     fn should_parse_optional_field() {
         #[derive(Debug, Clone)]
@@ -299,4 +312,74 @@ mod tests {
         assert_eq!(v2.text, Some(String::from("tiger")));
         assert_eq!(v2.bool, None);
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Filter {
+        tag: Vec<String>,
+        page: u32,
+    }
+
+    impl_serde_struct!(Filter => {
+        tag: Vec<String>,
+        page: u32,
+    });
+
+    #[test]
+    fn should_parse_repeated_keys_to_vec() {
+        let filter = deserialize_query::<Filter>("/path?tag=rust&page=2&tag=http");
+        assert_eq!(
+            filter,
+            Filter {
+                tag: vec![String::from("rust"), String::from("http")],
+                page: 2
+            }
+        );
+
+        let filter = deserialize_query::<Filter>("/path?tag=rust&page=1");
+        assert_eq!(filter.tag, vec![String::from("rust")]);
+    }
+
+    #[test]
+    fn should_parse_bracket_keys_to_vec() {
+        let filter = deserialize_query::<Filter>("/path?tag[]=rust&tag[]=http&page=3");
+        assert_eq!(
+            filter,
+            Filter {
+                tag: vec![String::from("rust"), String::from("http")],
+                page: 3
+            }
+        );
+
+        let filter = deserialize_query::<Filter>("/path?tag[]=rust&tag=http&page=3");
+        assert_eq!(filter.tag, vec![String::from("rust"), String::from("http")]);
+
+        let filter = deserialize_query::<Filter>("/path?tag[]=rust&page=3");
+        assert_eq!(filter.tag, vec![String::from("rust")]);
+    }
+
+    #[test]
+    fn should_collect_unknown_keys_into_flatten_map() {
+        struct Visit {
+            page: String,
+            user_id: u32,
+            extra: HashMap<String, String>,
+        }
+
+        serde::impl_deserialize_struct!(Visit => {
+            page: String,
+            user_id: u32,
+            #[flatten] extra: HashMap<String, String>,
+        });
+
+        let visit = deserialize_query::<Visit>(
+            "/path?page=home&utm_source=newsletter&user_id=7&utm_medium=email&utm_campaign=launch",
+        );
+
+        assert_eq!(visit.page, "home");
+        assert_eq!(visit.user_id, 7);
+        assert_eq!(visit.extra.len(), 3);
+        assert_eq!(visit.extra.get("utm_source").unwrap(), "newsletter");
+        assert_eq!(visit.extra.get("utm_medium").unwrap(), "email");
+        assert_eq!(visit.extra.get("utm_campaign").unwrap(), "launch");
+    }
 }
@@ -0,0 +1,110 @@
+use std::{convert::Infallible, ops::Deref};
+
+use datetime::DateTime;
+
+use crate::from_request::FromRequest;
+
+/// The time the request was received.
+///
+/// The `App` sets it once when the request starts, use it instead of `DateTime::now_utc()`
+/// so all the timestamps within a request are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RequestTime(pub DateTime);
+
+impl RequestTime {
+    /// Constructs a `RequestTime` with the current time.
+    pub fn now() -> Self {
+        RequestTime(DateTime::now_utc())
+    }
+
+    pub fn into_inner(self) -> DateTime {
+        self.0
+    }
+}
+
+impl Deref for RequestTime {
+    type Target = DateTime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for RequestTime {
+    type Rejection = Infallible;
+
+    fn from_request(
+        req: &http1::request::Request<()>,
+        _payload: &mut http1::payload::Payload,
+    ) -> Result<Self, Self::Rejection> {
+        // Only missing if the handler is called outside the `App`
+        Ok(req
+            .extensions()
+            .get::<RequestTime>()
+            .copied()
+            .unwrap_or_else(RequestTime::now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use http1::{
+        body::Body, handler::RequestHandler, method::Method, request::Request, uri::uri::Uri,
+    };
+
+    use crate::app::App;
+
+    use super::RequestTime;
+
+    #[test]
+    fn should_use_same_time_within_request() {
+        let times = Arc::new(Mutex::new(Vec::new()));
+
+        let app = App::new()
+            .middleware({
+                let times = times.clone();
+                move |req: Request<Body>, next: &crate::handler::BoxedHandler| {
+                    let time = req.extensions().get::<RequestTime>().copied().unwrap();
+                    times.lock().unwrap().push(time);
+
+                    std::thread::sleep(Duration::from_millis(5));
+                    next.call(req)
+                }
+            })
+            .get("/", {
+                let times = times.clone();
+                move |a: RequestTime, b: RequestTime| {
+                    times.lock().unwrap().extend([a, b]);
+                    "Hello World!"
+                }
+            });
+
+        app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/").unwrap(),
+            Body::empty(),
+        ));
+
+        let first = std::mem::take(&mut *times.lock().unwrap());
+        assert_eq!(first.len(), 3);
+        assert!(first.iter().all(|x| *x == first[0]));
+
+        // Each request gets its own time
+        app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/").unwrap(),
+            Body::empty(),
+        ));
+
+        let second = times.lock().unwrap();
+        assert_eq!(second.len(), 3);
+        assert!(second.iter().all(|x| *x == second[0]));
+        assert!(second[0] > first[0]);
+    }
+}
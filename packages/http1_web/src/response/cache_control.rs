@@ -0,0 +1,163 @@
+use std::{convert::Infallible, fmt::Display, time::Duration};
+
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue},
+    response::Response,
+    status::StatusCode,
+};
+
+use super::{IntoResponse, IntoResponseParts, ResponseParts};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+/// A builder for the `Cache-Control` response header.
+///
+/// The directives are always emitted in this order: `public` or `private`, `no-store`, `max-age` and `immutable`.
+///
+/// # Example
+/// ```rust,ignore
+/// app.get("/logo.png", || {
+///     let cache = CacheControl::new().public().max_age(Duration::from_secs(3600)).immutable();
+///     (logo, cache)
+/// });
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CacheControl {
+    visibility: Option<Visibility>,
+    no_store: bool,
+    max_age: Option<Duration>,
+    immutable: bool,
+}
+
+impl CacheControl {
+    /// Constructs an empty `CacheControl`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `max-age` directive, the duration is truncated to seconds.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets the `no-store` directive, the response must not be stored in any cache.
+    pub fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// Sets the `public` directive, replacing `private`.
+    pub fn public(mut self) -> Self {
+        self.visibility = Some(Visibility::Public);
+        self
+    }
+
+    /// Sets the `private` directive, replacing `public`.
+    pub fn private(mut self) -> Self {
+        self.visibility = Some(Visibility::Private);
+        self
+    }
+
+    /// Sets the `immutable` directive, the response will not change while it's fresh.
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Adds the `Cache-Control` header to the given response, replacing the existing one.
+    pub fn wrap(self, response: impl IntoResponse) -> Response<Body> {
+        let mut response = response.into_response();
+        response.headers_mut().insert(
+            headers::CACHE_CONTROL,
+            HeaderValue::from_string(self.to_string()),
+        );
+        response
+    }
+}
+
+impl Display for CacheControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut directives = Vec::new();
+
+        match self.visibility {
+            Some(Visibility::Public) => directives.push("public".to_owned()),
+            Some(Visibility::Private) => directives.push("private".to_owned()),
+            None => {}
+        }
+
+        if self.no_store {
+            directives.push("no-store".to_owned());
+        }
+
+        if let Some(max_age) = self.max_age {
+            directives.push(format!("max-age={}", max_age.as_secs()));
+        }
+
+        if self.immutable {
+            directives.push("immutable".to_owned());
+        }
+
+        write!(f, "{}", directives.join(", "))
+    }
+}
+
+impl IntoResponseParts for CacheControl {
+    type Err = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Err> {
+        res.headers_mut().insert(
+            headers::CACHE_CONTROL,
+            HeaderValue::from_string(self.to_string()),
+        );
+        Ok(res)
+    }
+}
+
+impl IntoResponse for CacheControl {
+    fn into_response(self) -> Response<Body> {
+        self.wrap(StatusCode::OK)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http1::headers;
+
+    use crate::IntoResponse;
+
+    use super::CacheControl;
+
+    #[test]
+    fn should_emit_directives_in_order() {
+        let cache = CacheControl::new()
+            .immutable()
+            .max_age(Duration::from_secs(3600))
+            .public();
+
+        assert_eq!(cache.to_string(), "public, max-age=3600, immutable");
+
+        let res = ("Hello World!", cache).into_response();
+        assert_eq!(
+            res.headers().get(headers::CACHE_CONTROL).unwrap().as_str(),
+            "public, max-age=3600, immutable"
+        );
+
+        let res = CacheControl::new()
+            .public()
+            .private()
+            .no_store()
+            .wrap("Secret");
+        assert_eq!(
+            res.headers().get(headers::CACHE_CONTROL).unwrap().as_str(),
+            "private, no-store"
+        );
+    }
+}
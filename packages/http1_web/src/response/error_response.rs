@@ -1,7 +1,17 @@
 #![allow(clippy::borrowed_box)]
-use http1::{body::Body, response::Response, status::StatusCode};
+use http1::{
+    body::{http_body::HttpBody, Body},
+    headers::{self, HeaderValue, Headers},
+    response::Response,
+    status::StatusCode,
+};
+use serde::impl_serialize_struct;
 
-use crate::IntoResponse;
+use crate::{
+    header::{Accept, FromHeaders},
+    mime::Mime,
+    IntoResponse,
+};
 
 /// Represents a client or server error code.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -113,9 +123,34 @@ impl IntoResponse for ErrorStatusCode {
     }
 }
 
+/// The detail of an error, this is attached to the extensions of the responses created from an `ErrorResponse`.
+#[derive(Debug, Clone)]
+pub struct ErrorDetail(String);
+
+impl ErrorDetail {
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The body of a `application/problem+json` response: [`https://www.rfc-editor.org/rfc/rfc7807`].
+struct Problem {
+    status: u16,
+    title: String,
+    detail: String,
+}
+
+impl_serialize_struct!(Problem => {
+    status: u16,
+    title: String,
+    detail: String,
+});
+
 enum Inner {
     Response(Box<dyn FnOnce() -> Response<Body>>),
     Error(Box<dyn std::error::Error + Send + Sync + 'static>),
+    Problem { title: String, detail: String },
 }
 
 /// Represents an error response.
@@ -148,6 +183,22 @@ impl ErrorResponse {
         }
     }
 
+    /// Constructs an `ErrorResponse` with a `application/problem+json` body like
+    /// `{"status":400,"title":"Bad Request","detail":"missing name"}`.
+    pub fn json(
+        status: ErrorStatusCode,
+        title: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Self {
+        ErrorResponse {
+            status,
+            inner: Inner::Problem {
+                title: title.into(),
+                detail: detail.into(),
+            },
+        }
+    }
+
     /// Returns the status code for this error.
     pub fn status_code(&self) -> StatusCode {
         self.status.as_status_code()
@@ -161,7 +212,7 @@ impl ErrorResponse {
     /// Returns the error used for this response if any.
     pub fn error(&self) -> Option<&Box<dyn std::error::Error + Send + Sync + 'static>> {
         match &self.inner {
-            Inner::Response(_) => None,
+            Inner::Response(_) | Inner::Problem { .. } => None,
             Inner::Error(error) => Some(error),
         }
     }
@@ -190,12 +241,91 @@ impl IntoResponse for ErrorResponse {
             Inner::Error(error) => {
                 let msg = error.to_string();
                 log::error!("{}", msg);
-                Response::new(status_code, msg.into())
+
+                let mut response = Response::new(status_code, msg.clone().into());
+                response.extensions_mut().insert(ErrorDetail(msg));
+                response
+            }
+            Inner::Problem { title, detail } => {
+                let mut response = Response::new(status_code, Body::empty());
+                set_problem_json(&mut response, &title, &detail);
+                response
             }
         }
     }
 }
 
+/// Returns `true` if the client prefers a JSON response over plain text.
+pub(crate) fn accepts_problem_json(headers: &Headers) -> bool {
+    if !headers.contains_key(headers::ACCEPT) {
+        return false;
+    }
+
+    let offered = [
+        Mime::TEXT_PLAIN,
+        Mime::APPLICATION_JSON,
+        Mime::APPLICATION_PROBLEM_JSON,
+    ];
+
+    Accept::from_headers(headers)
+        .ok()
+        .and_then(|accept| accept.preferred(&offered))
+        .is_some_and(|mime| mime != Mime::TEXT_PLAIN)
+}
+
+/// Returns `true` if the response has a `application/problem+json` body.
+pub(crate) fn is_problem_json(response: &Response<Body>) -> bool {
+    response
+        .headers()
+        .get(headers::CONTENT_TYPE)
+        .is_some_and(|x| x.as_str() == Mime::APPLICATION_PROBLEM_JSON.to_string())
+}
+
+/// Replaces the body of the response with a `application/problem+json` body, keeping the status and headers.
+pub(crate) fn set_problem_json(response: &mut Response<Body>, title: &str, detail: &str) {
+    let problem = Problem {
+        status: response.status().as_u16(),
+        title: title.to_owned(),
+        detail: detail.to_owned(),
+    };
+
+    let json = match serde::json::to_string(&problem) {
+        Ok(json) => json,
+        Err(err) => {
+            log::error!("Failed to serialize problem details: {err}");
+            return;
+        }
+    };
+
+    *response.body_mut() = json.into();
+    response.headers_mut().remove(headers::CONTENT_LENGTH);
+    response.headers_mut().insert(
+        headers::CONTENT_TYPE,
+        HeaderValue::from_string(Mime::APPLICATION_PROBLEM_JSON.to_string()),
+    );
+}
+
+/// Converts a rejection response into a `application/problem+json` response.
+///
+/// Only empty responses or the ones created from an error are converted, so custom bodies are kept.
+pub(crate) fn rejection_into_problem_json(mut response: Response<Body>) -> Response<Body> {
+    let status = response.status();
+
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let title = status.reason_phrase().unwrap_or("Error");
+    let detail = match response.extensions().get::<ErrorDetail>() {
+        Some(detail) => detail.message().to_owned(),
+        None if response.body().size_hint() == Some(0) => title.to_owned(),
+        None => return response,
+    };
+
+    set_problem_json(&mut response, title, &detail);
+    response
+}
+
 impl From<ErrorStatusCode> for ErrorResponse {
     fn from(value: ErrorStatusCode) -> Self {
         ErrorResponse::new(value, ())
@@ -228,3 +358,34 @@ impl<E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>> From<E> for Er
         ErrorResponse::from_error(status, error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http1::{body::http_body::HttpBody, headers, status::StatusCode};
+
+    use crate::IntoResponse;
+
+    use super::{ErrorResponse, ErrorStatusCode};
+
+    #[test]
+    fn should_create_problem_json_response() {
+        let res = ErrorResponse::json(
+            ErrorStatusCode::BadRequest,
+            "Invalid user",
+            "name is required",
+        )
+        .into_response();
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_TYPE).unwrap().as_str(),
+            "application/problem+json"
+        );
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            r#"{"status":400,"title":"Invalid user","detail":"name is required"}"#
+        );
+    }
+}
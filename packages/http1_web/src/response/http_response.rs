@@ -25,11 +25,18 @@ impl<B> HttpResponse<B> {
     }
 }
 
-impl HttpResponse<()> {
-    /// Creates a `HttpResponse` from a value.
-    pub fn from_response<T: IntoResponse>(value: T) -> HttpResponse<Body> {
-        let res = value.into_response();
-        HttpResponse(res)
+impl HttpResponse<Body> {
+    /// Creates a `HttpResponse` from any value that can be converted into a response.
+    ///
+    /// The status, headers and body of the response are preserved, so more headers or cookies can be added to it.
+    pub fn from_response<T: IntoResponse>(value: T) -> Self {
+        HttpResponse(value.into_response())
+    }
+}
+
+impl<B> From<Response<B>> for HttpResponse<B> {
+    fn from(value: Response<B>) -> Self {
+        HttpResponse(value)
     }
 }
 
@@ -201,8 +208,59 @@ where
 /// Helper trait to convert types that implement `IntoResponse` to `HttpResponse`.
 pub trait IntoHttpResponse: IntoResponse + Sized {
     fn into_http_response(self) -> HttpResponse {
-        HttpResponse::from_response(self).finish()
+        HttpResponse::from_response(self)
     }
 }
 
 impl<T> IntoHttpResponse for T where T: IntoResponse {}
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers::{self, HeaderValue},
+        response::Response,
+        status::StatusCode,
+    };
+
+    use crate::{cookies::Cookie, html::Html, IntoResponse};
+
+    use super::{HttpResponse, IntoHttpResponse};
+
+    #[test]
+    fn should_convert_into_http_response_and_add_cookie() {
+        let res = Html("<h1>Welcome!</h1>")
+            .into_http_response()
+            .set_cookie(Cookie::new("session", "abc123"))
+            .into_response();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(headers::CONTENT_TYPE).unwrap().as_str(),
+            "text/html"
+        );
+
+        let cookie = res.headers().get(headers::SET_COOKIE).unwrap().to_string();
+        assert!(cookie.starts_with("session=abc123"));
+
+        let body = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(body, b"<h1>Welcome!</h1>");
+    }
+
+    #[test]
+    fn should_preserve_status_from_response() {
+        let created = Response::builder()
+            .status(StatusCode::CREATED)
+            .body(Body::empty());
+
+        let res = HttpResponse::from_response(created)
+            .insert_header(headers::LOCATION, HeaderValue::from_static("/items/1"))
+            .into_response();
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(
+            res.headers().get(headers::LOCATION).unwrap().as_str(),
+            "/items/1"
+        );
+    }
+}
@@ -1,11 +1,20 @@
+mod cache_control;
 mod error_response;
 mod http_response;
 mod into_response;
+mod negotiate;
+mod responder;
+mod response_headers;
+mod too_many_requests;
+mod validation_errors;
 
 use std::fmt::Display;
 
 use http1::{body::Body, headers::HeaderValue, response::Response, status::StatusCode};
-pub use {error_response::*, http_response::*, into_response::*};
+pub use {
+    cache_control::*, error_response::*, http_response::*, into_response::*, negotiate::*,
+    responder::*, response_headers::*, too_many_requests::*, validation_errors::*,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WWWAuthenticate {
@@ -0,0 +1,204 @@
+use std::convert::Infallible;
+
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue, Headers},
+    payload::Payload,
+    request::Request,
+    response::Response,
+    status::StatusCode,
+};
+use serde::ser::Serialize;
+
+use crate::{
+    from_request::FromRequest,
+    header::{Accept, FromHeaders},
+    html::Html,
+    json::Json,
+    mime::Mime,
+};
+
+use super::IntoResponse;
+
+type Render = Box<dyn FnOnce() -> Response<Body>>;
+
+/// Selects the response representation based on the `Accept` header of the request.
+///
+/// Can be JSON, HTML or plain text, JSON is preferred when the request accepts any of them equally
+/// or does not have an `Accept` header.
+/// If none of the representations is acceptable it responds with `406 Not Acceptable`.
+///
+/// # Example
+/// ```rust,ignore
+/// app.get("/user", |negotiate: Negotiate| {
+///     negotiate.json(user.clone()).html(format!("<h1>{}</h1>", user.name))
+/// });
+/// ```
+pub struct Negotiate {
+    accept: Option<Accept>,
+    json: Option<Render>,
+    html: Option<Render>,
+    text: Option<Render>,
+}
+
+impl Negotiate {
+    /// Constructs a `Negotiate` that accepts any representation.
+    pub fn new() -> Self {
+        Negotiate {
+            accept: None,
+            json: None,
+            html: None,
+            text: None,
+        }
+    }
+
+    /// Constructs a `Negotiate` using the `Accept` values of the given headers.
+    pub fn from_headers(headers: &Headers) -> Self {
+        Negotiate {
+            accept: Accept::from_headers(headers).ok(),
+            ..Self::new()
+        }
+    }
+
+    /// Sets the JSON representation.
+    pub fn json<T: Serialize + 'static>(mut self, value: T) -> Self {
+        self.json = Some(Box::new(move || Json(value).into_response()));
+        self
+    }
+
+    /// Sets the HTML representation.
+    pub fn html(mut self, html: impl Into<String> + 'static) -> Self {
+        self.html = Some(Box::new(move || Html(html).into_response()));
+        self
+    }
+
+    /// Sets the plain text representation.
+    pub fn text(mut self, text: impl Into<String> + 'static) -> Self {
+        self.text = Some(Box::new(move || text.into().into_response()));
+        self
+    }
+}
+
+impl Default for Negotiate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromRequest for Negotiate {
+    type Rejection = Infallible;
+
+    fn from_request(req: &Request<()>, _payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        Ok(Negotiate::from_headers(req.headers()))
+    }
+}
+
+impl IntoResponse for Negotiate {
+    fn into_response(mut self) -> Response<Body> {
+        let mut candidates = [
+            (Mime::APPLICATION_JSON, self.json.take()),
+            (Mime::TEXT_HTML, self.html.take()),
+            (Mime::TEXT_PLAIN, self.text.take()),
+        ]
+        .into_iter()
+        .filter_map(|(mime, responder)| Some((mime, responder?)))
+        .collect::<Vec<_>>();
+
+        let selected = match &self.accept {
+            Some(accept) => {
+                let offered = candidates
+                    .iter()
+                    .map(|(mime, _)| mime.clone())
+                    .collect::<Vec<_>>();
+                accept
+                    .preferred(&offered)
+                    .and_then(|mime| candidates.iter().position(|(x, _)| *x == mime))
+            }
+            None if candidates.is_empty() => None,
+            None => Some(0),
+        };
+
+        let mut response = match selected {
+            Some(idx) => (candidates.swap_remove(idx).1)(),
+            None => StatusCode::NOT_ACCEPTABLE.into_response(),
+        };
+
+        response
+            .headers_mut()
+            .append(headers::VARY, HeaderValue::from_static("Accept"));
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::{http_body::HttpBody, Body},
+        headers,
+        request::Request,
+        response::Response,
+        status::StatusCode,
+    };
+
+    use crate::{from_request::FromRequest, IntoResponse};
+
+    use super::Negotiate;
+
+    fn negotiate(accept: Option<&'static str>) -> Response<Body> {
+        let mut builder = Request::builder();
+
+        if let Some(accept) = accept {
+            builder = builder.insert_header(headers::ACCEPT, accept);
+        }
+
+        let req = builder.body(Body::empty()).unwrap();
+        let negotiate = Negotiate::from_whole_request(req).unwrap();
+
+        negotiate
+            .json(vec![1, 2, 3])
+            .html("<h1>Numbers</h1>")
+            .text("1, 2, 3")
+            .into_response()
+    }
+
+    fn content_type_and_body(res: Response<Body>) -> (String, String) {
+        let content_type = res
+            .headers()
+            .get(headers::CONTENT_TYPE)
+            .unwrap()
+            .to_string();
+        let body = res.into_body().read_all_bytes().unwrap();
+        (content_type, String::from_utf8(body).unwrap())
+    }
+
+    #[test]
+    fn should_select_representation_from_accept() {
+        let (content_type, body) = content_type_and_body(negotiate(Some("text/html")));
+        assert!(content_type.starts_with("text/html"));
+        assert_eq!(body, "<h1>Numbers</h1>");
+
+        let (content_type, body) = content_type_and_body(negotiate(Some("application/json")));
+        assert!(content_type.starts_with("application/json"));
+        assert_eq!(body, "[1,2,3]");
+
+        let (content_type, body) = content_type_and_body(negotiate(Some("*/*")));
+        assert!(content_type.starts_with("application/json"));
+        assert_eq!(body, "[1,2,3]");
+
+        let (content_type, _) = content_type_and_body(negotiate(None));
+        assert!(content_type.starts_with("application/json"));
+    }
+
+    #[test]
+    fn should_respect_quality_values() {
+        let (content_type, _) = content_type_and_body(negotiate(Some(
+            "application/json;q=0.5, text/*;q=0.8, text/html;q=0",
+        )));
+        assert_eq!(content_type, "text/plain");
+
+        let res = negotiate(Some("image/png"));
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(res.headers().get(headers::VARY).unwrap().as_str(), "Accept");
+    }
+}
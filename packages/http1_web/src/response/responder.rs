@@ -0,0 +1,175 @@
+use http1::{
+    body::Body,
+    headers::{self, HeaderName, HeaderValue},
+    response::Response,
+    status::StatusCode,
+};
+
+use crate::cookies::Cookie;
+
+use super::IntoResponse;
+
+/// A fluent builder to compose a response from any body that implements [`IntoResponse`].
+///
+/// The status, headers and cookies are applied over the response of the body,
+/// so the `Content-Type` of a body like `Json` or `Html` is kept unless a header replaces it.
+///
+/// # Example
+/// ```rust,ignore
+/// app.post("/users", |Json(user): Json<User>| {
+///     Responder::ok(Json(user))
+///         .status(StatusCode::CREATED)
+///         .header(headers::LOCATION, "/users/1")
+///         .cookie(Cookie::new("last_user", "1"))
+/// });
+/// ```
+#[derive(Debug)]
+pub struct Responder {
+    status: Option<StatusCode>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    cookies: Vec<Cookie>,
+    body: Response<Body>,
+}
+
+impl Responder {
+    /// Constructs a `Responder` with the given status and body.
+    pub fn new(status: StatusCode, body: impl IntoResponse) -> Self {
+        Responder {
+            status: Some(status),
+            ..Self::from_body(body)
+        }
+    }
+
+    /// Constructs a `Responder` with `200 OK` status and the given body.
+    pub fn ok(body: impl IntoResponse) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+
+    /// Constructs a `Responder` with `201 Created` status and the given body.
+    pub fn created(body: impl IntoResponse) -> Self {
+        Self::new(StatusCode::CREATED, body)
+    }
+
+    /// Constructs a `Responder` with `204 No Content` status.
+    pub fn no_content() -> Self {
+        Self::new(StatusCode::NO_CONTENT, ())
+    }
+
+    /// Constructs a `Responder` that keeps the status of the body response.
+    pub fn from_body(body: impl IntoResponse) -> Self {
+        Responder {
+            status: None,
+            headers: Vec::new(),
+            cookies: Vec::new(),
+            body: body.into_response(),
+        }
+    }
+
+    /// Sets the status of the response.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets a header of the response, replacing the header with the same name.
+    pub fn header(mut self, name: HeaderName, value: impl Into<HeaderValue>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Adds a `Set-Cookie` header to the response.
+    pub fn cookie(mut self, cookie: impl Into<Cookie>) -> Self {
+        self.cookies.push(cookie.into());
+        self
+    }
+
+    /// Replaces the body of the response, the headers of the previous body are discarded.
+    pub fn body(mut self, body: impl IntoResponse) -> Self {
+        self.body = body.into_response();
+        self
+    }
+}
+
+impl IntoResponse for Responder {
+    fn into_response(self) -> Response<Body> {
+        let Responder {
+            status,
+            headers,
+            cookies,
+            body: mut response,
+        } = self;
+
+        if let Some(status) = status {
+            *response.status_mut() = status;
+        }
+
+        for (name, value) in headers {
+            response.headers_mut().insert(name, value);
+        }
+
+        for cookie in cookies {
+            response.headers_mut().append(
+                headers::SET_COOKIE,
+                HeaderValue::from_string(cookie.to_string()),
+            );
+        }
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{
+        body::http_body::HttpBody,
+        headers::{self, HeaderValue},
+        status::StatusCode,
+    };
+
+    use crate::{cookies::Cookie, json::Json, IntoResponse};
+
+    use super::Responder;
+
+    #[test]
+    fn should_build_response_with_status_header_and_cookie() {
+        let res = Responder::ok(Json(vec![1, 2, 3]))
+            .status(StatusCode::CREATED)
+            .header(headers::LOCATION, HeaderValue::from_static("/numbers/1"))
+            .cookie(Cookie::new("theme", "dark"))
+            .cookie(Cookie::new("lang", "en"))
+            .into_response();
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(
+            res.headers().get(headers::LOCATION).unwrap().as_str(),
+            "/numbers/1"
+        );
+        assert!(res
+            .headers()
+            .get(headers::CONTENT_TYPE)
+            .unwrap()
+            .as_str()
+            .starts_with("application/json"));
+
+        let cookies = res
+            .headers()
+            .get_all(headers::SET_COOKIE)
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(cookies, vec!["theme=dark; Path=/", "lang=en; Path=/"]);
+        assert_eq!(res.into_body().read_all_bytes().unwrap(), b"[1,2,3]");
+    }
+
+    #[test]
+    fn should_keep_body_status_unless_set() {
+        let res = Responder::from_body(StatusCode::NOT_FOUND)
+            .header(headers::CACHE_CONTROL, HeaderValue::from_static("no-store"))
+            .into_response();
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.headers().get(headers::CACHE_CONTROL).unwrap().as_str(),
+            "no-store"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    convert::Infallible,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use http1::{
+    headers::{self, HeaderName, HeaderValue, Headers},
+    payload::Payload,
+    request::Request,
+};
+
+use crate::{cookies::Cookie, from_request::FromRequest};
+
+/// Headers to add to the response of the current request.
+///
+/// Allows extractors to set response headers, like a refreshed token cookie.
+/// The handler places it in the request extensions before extracting its arguments,
+/// and appends the registered headers to the response after the handler runs, even if the extraction failed.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseHeaders(Arc<Mutex<Headers>>);
+
+impl ResponseHeaders {
+    /// Appends a header to the response.
+    pub fn append(&self, name: HeaderName, value: impl Into<HeaderValue>) {
+        let mut headers = self.0.lock().expect("failed to lock response headers");
+        headers.append(name, value.into());
+    }
+
+    /// Inserts a header to the response, replacing the one registered before with the same name.
+    pub fn insert(&self, name: HeaderName, value: impl Into<HeaderValue>) {
+        let mut headers = self.0.lock().expect("failed to lock response headers");
+        headers.insert(name, value.into());
+    }
+
+    /// Appends the `Set-Cookie` header of the cookie, replacing the one registered before for the same cookie.
+    pub(crate) fn set_cookie(&self, cookie: &Cookie) {
+        let mut headers = self.0.lock().expect("failed to lock response headers");
+        let others = headers
+            .get_all(headers::SET_COOKIE)
+            .filter(|value| {
+                Cookie::from_str(value.as_str()).map_or(true, |x| x.name() != cookie.name())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        headers.remove(headers::SET_COOKIE);
+
+        for value in others {
+            headers.append(headers::SET_COOKIE, value);
+        }
+
+        headers.append(
+            headers::SET_COOKIE,
+            HeaderValue::from_string(cookie.to_string()),
+        );
+    }
+
+    /// Moves the registered headers to the given headers.
+    pub(crate) fn drain_into(&self, target: &mut Headers) {
+        let mut headers = self.0.lock().expect("failed to lock response headers");
+        target.extend(std::mem::take(&mut *headers));
+    }
+}
+
+impl FromRequest for ResponseHeaders {
+    type Rejection = Infallible;
+
+    fn from_request(req: &Request<()>, _payload: &mut Payload) -> Result<Self, Self::Rejection> {
+        // Only missing if the extractor is called outside a handler, the headers are discarded
+        Ok(req
+            .extensions()
+            .get::<ResponseHeaders>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, str::FromStr};
+
+    use http1::{
+        body::Body,
+        handler::RequestHandler,
+        headers::{self, HeaderValue},
+        method::Method,
+        payload::Payload,
+        request::Request,
+        uri::uri::Uri,
+    };
+
+    use crate::{app::App, from_request::FromRequest, ErrorStatusCode};
+
+    use super::ResponseHeaders;
+
+    struct RefreshToken;
+
+    impl FromRequest for RefreshToken {
+        type Rejection = Infallible;
+
+        fn from_request(req: &Request<()>, payload: &mut Payload) -> Result<Self, Self::Rejection> {
+            let response_headers = ResponseHeaders::from_request(req, payload)?;
+            response_headers.append(
+                headers::SET_COOKIE,
+                HeaderValue::from_static("token=refreshed; HttpOnly"),
+            );
+
+            Ok(RefreshToken)
+        }
+    }
+
+    struct Reject;
+
+    impl FromRequest for Reject {
+        type Rejection = ErrorStatusCode;
+
+        fn from_request(
+            _req: &Request<()>,
+            _payload: &mut Payload,
+        ) -> Result<Self, Self::Rejection> {
+            Err(ErrorStatusCode::Unauthorized)
+        }
+    }
+
+    #[test]
+    fn should_add_headers_registered_by_extractors() {
+        let app = App::new()
+            .get("/", |_: RefreshToken| "Hello World!")
+            .get("/rejected", |_: (RefreshToken, Reject)| "unreachable");
+
+        let res = app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/").unwrap(),
+            Body::empty(),
+        ));
+
+        assert_eq!(
+            res.headers().get(headers::SET_COOKIE).unwrap().as_str(),
+            "token=refreshed; HttpOnly"
+        );
+
+        // Also added to the rejection response
+        let res = app.handle(Request::new(
+            Method::GET,
+            Uri::from_str("/rejected").unwrap(),
+            Body::empty(),
+        ));
+
+        assert_eq!(res.status(), http1::status::StatusCode::UNAUTHORIZED);
+        assert_eq!(res.headers().get_all(headers::SET_COOKIE).count(), 1);
+    }
+}
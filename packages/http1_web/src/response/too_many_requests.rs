@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use datetime::DateTime;
+use http1::{
+    body::Body,
+    headers::{self, HeaderValue},
+    response::Response,
+    status::StatusCode,
+};
+
+use super::IntoResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryAfter {
+    Delay(Duration),
+    Date(DateTime),
+}
+
+/// A `429 Too Many Requests` response that tells the client when to retry with the `Retry-After` header.
+///
+/// # Example
+/// ```rust,ignore
+/// if !limiter.try_acquire(&client_ip) {
+///     return TooManyRequests::retry_after(Duration::from_secs(30)).into_response();
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyRequests {
+    retry_after: Option<RetryAfter>,
+}
+
+impl TooManyRequests {
+    /// Constructs a `TooManyRequests` response without `Retry-After` header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a `TooManyRequests` response with the delay in seconds to retry, like `Retry-After: 120`.
+    ///
+    /// The delay is rounded up to whole seconds so the client does not retry too early.
+    pub fn retry_after(delay: Duration) -> Self {
+        TooManyRequests {
+            retry_after: Some(RetryAfter::Delay(delay)),
+        }
+    }
+
+    /// Constructs a `TooManyRequests` response with the date to retry, like `Retry-After: Wed, 21 Oct 2015 07:28:00 GMT`.
+    pub fn retry_at(date: DateTime) -> Self {
+        TooManyRequests {
+            retry_after: Some(RetryAfter::Date(date)),
+        }
+    }
+}
+
+impl IntoResponse for TooManyRequests {
+    fn into_response(self) -> Response<Body> {
+        let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+
+        let value = match self.retry_after {
+            Some(RetryAfter::Delay(delay)) => {
+                let secs = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+                secs.to_string()
+            }
+            Some(RetryAfter::Date(date)) => date.to_rfc_1123_string(),
+            None => return response,
+        };
+
+        response
+            .headers_mut()
+            .insert(headers::RETRY_AFTER, HeaderValue::from_string(value));
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use datetime::{DateTime, Month};
+    use http1::{headers, status::StatusCode};
+
+    use crate::IntoResponse;
+
+    use super::TooManyRequests;
+
+    #[test]
+    fn should_set_retry_after_in_seconds() {
+        let res = TooManyRequests::retry_after(Duration::from_secs(120)).into_response();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            res.headers().get(headers::RETRY_AFTER).unwrap().as_str(),
+            "120"
+        );
+
+        let res = TooManyRequests::retry_after(Duration::from_millis(1500)).into_response();
+        assert_eq!(
+            res.headers().get(headers::RETRY_AFTER).unwrap().as_str(),
+            "2"
+        );
+
+        let res = TooManyRequests::new().into_response();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(res.headers().get(headers::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn should_set_retry_after_as_http_date() {
+        let date = DateTime::builder()
+            .year(2015)
+            .month(Month::October)
+            .day(21)
+            .hours(7)
+            .minutes(28)
+            .build();
+
+        let res = TooManyRequests::retry_at(date).into_response();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            res.headers().get(headers::RETRY_AFTER).unwrap().as_str(),
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use http1::{body::Body, response::Response, status::StatusCode};
+use orderedmap::OrderedMap;
+use serde::impl_serialize_struct;
+
+use crate::json::Json;
+
+use super::IntoResponse;
+
+/// A collection of validation errors for the fields of a form or JSON.
+///
+/// It converts into a `422 Unprocessable Content` response with the body `{ "errors": { field: [messages] } }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    errors: OrderedMap<String, Vec<String>>,
+}
+
+impl_serialize_struct!(ValidationErrors => {
+    errors: OrderedMap<String, Vec<String>>,
+});
+
+impl ValidationErrors {
+    /// Constructs an empty collection of validation errors.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an error message for the given field.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        let field = field.into();
+        let message = message.into();
+
+        match self.errors.get_mut(&field) {
+            Some(messages) => messages.push(message),
+            None => self.errors.insert(field, vec![message]),
+        }
+    }
+
+    /// Adds an error message for the given field and returns this instance.
+    pub fn with(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.add(field, message);
+        self
+    }
+
+    /// Returns the error messages for the given field.
+    pub fn get(&self, field: impl AsRef<str>) -> Option<&[String]> {
+        self.errors.get(field.as_ref()).map(|x| x.as_slice())
+    }
+
+    /// Returns `true` if there is no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of fields with errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response<Body> {
+        let mut res = Json(self).into_response();
+        *res.status_mut() = StatusCode::UNPROCESSABLE_CONTENT;
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http1::{body::http_body::HttpBody, headers, status::StatusCode};
+
+    use crate::IntoResponse;
+
+    use super::ValidationErrors;
+
+    #[test]
+    fn should_convert_validation_errors_into_response() {
+        let mut errors = ValidationErrors::new();
+        errors.add("name", "name is required");
+        errors.add("age", "age must be a number");
+        errors.add("age", "age must be greater than 0");
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.get("name").unwrap(), &["name is required"]);
+
+        let res = errors.into_response();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_CONTENT);
+        assert!(res
+            .headers()
+            .get(headers::CONTENT_TYPE)
+            .is_some_and(|x| x.as_str().starts_with("application/json")));
+
+        let bytes = res.into_body().read_all_bytes().unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            r#"{"errors":{"name":["name is required"],"age":["age must be a number","age must be greater than 0"]}}"#
+        );
+    }
+}
@@ -4,11 +4,12 @@ use std::{
     ops::Deref,
 };
 
-use http1::status::StatusCode;
-
-use crate::{from_request::FromRequest, IntoResponse};
+use crate::{from_request::FromRequest, ErrorResponse, ErrorStatusCode, IntoResponse};
 
 /// A state to share within requests.
+///
+/// If the state was not added to the app the request fails with `500 Internal Server Error`,
+/// use `Option<State<T>>` for states that may be missing.
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct State<T>(pub T);
 
@@ -40,7 +41,7 @@ impl<T> Display for AppStateError<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Failed to extract app state for: {}",
+            "Failed to extract app state for: {}, was it added with `App::state`?",
             std::any::type_name::<T>()
         )
     }
@@ -48,8 +49,9 @@ impl<T> Display for AppStateError<T> {
 
 impl<T> IntoResponse for AppStateError<T> {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
-        log::error!("{self}");
-        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        // The detail is only included in the body if the app shows the error details
+        ErrorResponse::from_error(ErrorStatusCode::InternalServerError, self.to_string())
+            .into_response()
     }
 }
 
@@ -1,6 +1,10 @@
 use std::{
     fmt::{Debug, Display},
     io::{Read, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
@@ -13,11 +17,18 @@ use super::{
 
 const BUFFER_SIZE: usize = 4 * 1024;
 const MAX_PAYLOAD_LENGTH: usize = 64 * 1024; // 64 kb
+const MAX_MISSED_PONGS: usize = 3;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WebSocketConfig {
     pub buffer_size: usize,
     pub max_payload_length: Option<usize>,
+
+    /// Max number of pings without a pong before the connection is closed, `None` for no limit.
+    pub max_missed_pongs: Option<usize>,
+
+    /// Max payload length of the frames sent, larger messages are split in multiple frames.
+    pub max_frame_size: Option<usize>,
 }
 
 impl Default for WebSocketConfig {
@@ -25,8 +36,59 @@ impl Default for WebSocketConfig {
         Self {
             buffer_size: BUFFER_SIZE,
             max_payload_length: Some(MAX_PAYLOAD_LENGTH),
+            max_missed_pongs: Some(MAX_MISSED_PONGS),
+            max_frame_size: None,
+        }
+    }
+}
+
+/// Limits the number of concurrent websocket connections, add it to the app state to enable it.
+///
+/// The handshakes received while the limit is reached are rejected with `503 Service Unavailable`.
+#[derive(Debug, Clone)]
+pub struct WebSocketLimit {
+    max_connections: usize,
+    active: Arc<AtomicUsize>,
+}
+
+impl WebSocketLimit {
+    /// Constructs a limit that allows at most `max_connections` websocket connections at the same time.
+    pub fn new(max_connections: usize) -> Self {
+        WebSocketLimit {
+            max_connections,
+            active: Arc::new(AtomicUsize::new(0)),
         }
     }
+
+    /// Returns the max number of concurrent connections.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Returns the number of open connections, including the ones waiting for the upgrade.
+    pub fn active_connections(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Reserves a connection, returns `None` if the limit was reached.
+    pub(crate) fn try_acquire(&self) -> Option<ConnectionPermit> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < self.max_connections).then_some(active + 1)
+            })
+            .ok()
+            .map(|_| ConnectionPermit(self.active.clone()))
+    }
+}
+
+/// A reserved websocket connection, released when dropped.
+#[derive(Debug)]
+pub(crate) struct ConnectionPermit(Arc<AtomicUsize>);
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 #[derive(Debug)]
@@ -76,6 +138,10 @@ pub struct WebSocketSplitError;
 pub struct WebSocket {
     upgrade: Upgrade,
     max_payload_length: Option<usize>,
+    max_missed_pongs: Option<usize>,
+    max_frame_size: Option<usize>,
+    missed_pongs: Arc<AtomicUsize>,
+    permit: Option<Arc<ConnectionPermit>>,
     buf: Box<[u8]>,
 }
 
@@ -90,9 +156,15 @@ impl WebSocket {
         let WebSocketConfig {
             buffer_size,
             max_payload_length,
+            max_missed_pongs,
+            max_frame_size,
         } = config;
 
         assert!(buffer_size > 0, "websocket buffer size must be non-zero");
+        assert!(
+            max_frame_size != Some(0),
+            "websocket max frame size must be non-zero"
+        );
 
         let buf = vec![0; buffer_size].into_boxed_slice();
 
@@ -100,9 +172,19 @@ impl WebSocket {
             upgrade,
             buf,
             max_payload_length,
+            max_missed_pongs,
+            max_frame_size,
+            missed_pongs: Arc::new(AtomicUsize::new(0)),
+            permit: None,
         }
     }
 
+    /// Holds the connection permit until the websocket and its halves are dropped.
+    pub(crate) fn with_permit(mut self, permit: Option<ConnectionPermit>) -> Self {
+        self.permit = permit.map(Arc::new);
+        self
+    }
+
     /// Reads a message.
     #[track_caller]
     pub fn recv(&mut self) -> Result<Message, WebSocketError> {
@@ -137,15 +219,56 @@ impl WebSocket {
                 ..
             } = frame;
 
+            match op_code {
+                OpCode::Ping => {
+                    let pong = Frame::builder(OpCode::Pong).data(payload.clone());
+                    self.send_frame(pong)?;
+                }
+                OpCode::Pong => {
+                    self.missed_pongs.store(0, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+
+            match (msg_op_code, op_code) {
+                // Control frames can be received between the frames of a fragmented message
+                (Some(_), OpCode::Ping | OpCode::Pong) => continue,
+                (Some(_), OpCode::Close) => return Self::into_message(OpCode::Close, payload),
+                (Some(_), OpCode::Text | OpCode::Binary) => {
+                    return Err(WebSocketError::Other(
+                        String::from("expected a continuation frame").into(),
+                    ));
+                }
+                (None, OpCode::Continuation) => {
+                    return Err(WebSocketError::Other(
+                        String::from("unexpected continuation frame").into(),
+                    ));
+                }
+                _ => {}
+            }
+
             msg_data.extend(payload);
             msg_op_code.get_or_insert(op_code);
 
+            if let Some(max) = self.max_payload_length {
+                if msg_data.len() > max {
+                    return Err(WebSocketError::PayloadTooBig {
+                        min: max,
+                        actual: msg_data.len(),
+                    });
+                }
+            }
+
             if fin {
                 break;
             }
         }
 
         let op_code = msg_op_code.expect("message op_code was empty");
+        Self::into_message(op_code, msg_data)
+    }
+
+    fn into_message(op_code: OpCode, msg_data: Vec<u8>) -> Result<Message, WebSocketError> {
         match op_code {
             OpCode::Binary => Ok(Message::Binary(msg_data)),
             OpCode::Text => {
@@ -170,13 +293,53 @@ impl WebSocket {
         }
     }
 
+    /// Sends a text message.
+    pub fn send_text(&mut self, text: impl Into<String>) -> Result<(), WebSocketError> {
+        self.send_message(Message::Text(text.into()))
+    }
+
+    /// Sends a binary message.
+    pub fn send_binary(&mut self, bytes: impl Into<Vec<u8>>) -> Result<(), WebSocketError> {
+        self.send_message(Message::Binary(bytes.into()))
+    }
+
     /// Sends a message.
+    #[deprecated(
+        note = "the message type depends on the conversion, use `send_text`, `send_binary` or `send_message`"
+    )]
     pub fn send(&mut self, message: impl Into<Message>) -> Result<(), WebSocketError> {
-        let message = message.into();
+        self.send_message(message.into())
+    }
+
+    /// Sends a message, messages larger than the `max_frame_size` are split in fragments.
+    pub fn send_message(&mut self, message: Message) -> Result<(), WebSocketError> {
         let op_code = OpCode::from_message(&message);
-        let frame = Frame::builder(op_code).fin(true).data(message.into_bytes());
+        let bytes = message.into_bytes();
+
+        // Control frames cannot be fragmented
+        let is_control = matches!(op_code, OpCode::Ping | OpCode::Pong | OpCode::Close);
+        let frame_size = match self.max_frame_size {
+            Some(max) if !is_control && bytes.len() > max => max,
+            _ => {
+                let frame = Frame::builder(op_code).fin(true).data(bytes);
+                return self.send_frame(frame);
+            }
+        };
+
+        let mut chunks = bytes.chunks(frame_size).peekable();
+        let mut frame_op_code = op_code;
 
-        self.send_frame(frame)
+        while let Some(chunk) = chunks.next() {
+            let is_last = chunks.peek().is_none();
+            let frame = Frame::builder(frame_op_code)
+                .fin(is_last)
+                .data(chunk.to_vec());
+
+            self.send_frame(frame)?;
+            frame_op_code = OpCode::Continuation;
+        }
+
+        Ok(())
     }
 
     /// Send a ping to the client to check if still connected.
@@ -187,13 +350,32 @@ impl WebSocket {
         self.ping_timeout(None)
     }
 
+    /// Sends a ping with the given payload without waiting for the pong.
+    ///
+    /// The pongs are received when reading messages, if the client missed more pongs than the
+    /// configured `max_missed_pongs` the connection is closed.
+    ///
+    /// # Returns
+    /// An error if the connection was closed.
+    pub fn send_ping(&mut self, payload: impl Into<Vec<u8>>) -> Result<(), WebSocketError> {
+        let missed_pongs = self.missed_pongs.fetch_add(1, Ordering::Relaxed);
+
+        if self.max_missed_pongs.is_some_and(|max| missed_pongs >= max) {
+            let close = CloseFrame::new(CloseStatusCode::GoingAway, "missed pongs");
+            self.send_message(Message::Close(Some(close)))?;
+            return Err(WebSocketError::Closed);
+        }
+
+        self.send_message(Message::Ping(payload.into()))
+    }
+
     /// Sends a ping to check if the client still connected within the given timeout.
     ///
     /// # Returns
     /// An error if the client does not respond.
     pub fn ping_timeout(&mut self, timeout: Option<Duration>) -> Result<(), WebSocketError> {
         let id = Uuid::new_v4().to_simple_string().into_bytes();
-        self.send(Message::Ping(id.clone()))?;
+        self.send_message(Message::Ping(id.clone()))?;
 
         match self.read(timeout)? {
             Message::Pong(bytes) => {
@@ -211,7 +393,7 @@ impl WebSocket {
 
     /// Sends a close signal to the client.
     pub fn close(mut self) -> Result<(), WebSocketError> {
-        self.send(Message::Close(None))?;
+        self.send_message(Message::Close(None))?;
         Ok(())
     }
 
@@ -222,7 +404,7 @@ impl WebSocket {
         reason: impl Into<String>,
     ) -> Result<(), WebSocketError> {
         let close = CloseFrame::new(code, reason.into());
-        self.send(Message::Close(Some(close)))?;
+        self.send_message(Message::Close(Some(close)))?;
         Ok(())
     }
 
@@ -230,13 +412,22 @@ impl WebSocket {
         let upgrade = self.upgrade.try_clone()?;
         let buffer_size = self.buf.len();
         let max_payload_length = self.max_payload_length;
-        Some(WebSocket::with_config(
+        let max_missed_pongs = self.max_missed_pongs;
+        let max_frame_size = self.max_frame_size;
+        let mut ws = WebSocket::with_config(
             upgrade,
             WebSocketConfig {
                 buffer_size,
                 max_payload_length,
+                max_missed_pongs,
+                max_frame_size,
             },
-        ))
+        );
+
+        // Both halves track the same pings
+        ws.missed_pongs = self.missed_pongs.clone();
+        ws.permit = self.permit.clone();
+        Some(ws)
     }
 
     /// Split the websocket into a read and write part.
@@ -279,7 +470,7 @@ impl WebSocket {
         let mask = (b1 & 0b1000_0000) != 0;
         let len_indicator = (b1 & 0b0111_1111) as u64;
 
-        let len = if len_indicator < 125 {
+        let len = if len_indicator <= 125 {
             len_indicator
         } else if len_indicator == 126 {
             let b2 = self.read_next_byte()?;
@@ -326,16 +517,19 @@ impl WebSocket {
         let masking_key_bytes = masking_key.to_be_bytes();
 
         while len > 0 {
-            let bytes_read = self.upgrade.read(&mut self.buf)?;
+            // Don't read past this frame
+            let max = self.buf.len().min(len as usize);
+            let bytes_read = self.upgrade.read(&mut self.buf[..max])?;
             match bytes_read {
                 0 => break,
                 n => {
                     len -= n as u64;
+                    let offset = data.len();
                     let bytes = &mut self.buf[..n];
 
                     if masking_key != 0 {
                         bytes.iter_mut().enumerate().for_each(|(idx, b)| {
-                            let mask_byte = masking_key_bytes[idx % 4];
+                            let mask_byte = masking_key_bytes[(offset + idx) % 4];
                             *b ^= mask_byte;
                         });
                     }
@@ -434,9 +628,27 @@ impl Iterator for WebSocketReader {
 
 pub struct WebSocketWriter(WebSocket);
 impl WebSocketWriter {
+    /// Sends a text message.
+    pub fn send_text(&mut self, text: impl Into<String>) -> Result<(), WebSocketError> {
+        self.0.send_text(text)
+    }
+
+    /// Sends a binary message.
+    pub fn send_binary(&mut self, bytes: impl Into<Vec<u8>>) -> Result<(), WebSocketError> {
+        self.0.send_binary(bytes)
+    }
+
     /// Sends a message.
+    #[deprecated(
+        note = "the message type depends on the conversion, use `send_text`, `send_binary` or `send_message`"
+    )]
     pub fn send(&mut self, message: impl Into<Message>) -> Result<(), WebSocketError> {
-        self.0.send(message)
+        self.0.send_message(message.into())
+    }
+
+    /// Sends a message, messages larger than the `max_frame_size` are split in fragments.
+    pub fn send_message(&mut self, message: Message) -> Result<(), WebSocketError> {
+        self.0.send_message(message)
     }
 
     /// Send a ping to the client to check if still connected.
@@ -447,6 +659,14 @@ impl WebSocketWriter {
         self.0.ping()
     }
 
+    /// Sends a ping with the given payload without waiting for the pong.
+    ///
+    /// # Returns
+    /// An error if the connection was closed.
+    pub fn send_ping(&mut self, payload: impl Into<Vec<u8>>) -> Result<(), WebSocketError> {
+        self.0.send_ping(payload)
+    }
+
     /// Sends a ping to check if the client still connected within the given timeout.
     ///
     /// # Returns
@@ -455,3 +675,138 @@ impl WebSocketWriter {
         self.0.ping_timeout(timeout)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use http1::protocol::{connection::Connection, upgrade::Upgrade};
+
+    use super::{Message, WebSocket, WebSocketConfig, WebSocketError};
+
+    struct Pipe {
+        input: std::io::Cursor<Vec<u8>>,
+        output: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Read for Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn client_frame(fin: bool, op_code: u8, payload: &[u8]) -> Vec<u8> {
+        let masking_key = [1, 2, 3, 4];
+        let mut bytes = vec![((fin as u8) << 7) | op_code, 0x80 | payload.len() as u8];
+        bytes.extend_from_slice(&masking_key);
+        bytes.extend(
+            payload
+                .iter()
+                .enumerate()
+                .map(|(i, b)| b ^ masking_key[i % 4]),
+        );
+        bytes
+    }
+
+    fn websocket(input: Vec<u8>, config: WebSocketConfig) -> (WebSocket, Arc<Mutex<Vec<u8>>>) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let pipe = Pipe {
+            input: std::io::Cursor::new(input),
+            output: output.clone(),
+        };
+
+        let upgrade = Upgrade::from_connection(Connection::from_io(pipe));
+        (WebSocket::with_config(upgrade, config), output)
+    }
+
+    #[test]
+    fn should_reply_ping_with_pong() {
+        let mut input = client_frame(true, 0x9, b"are you there?");
+        input.extend(client_frame(true, 0x1, b"Hello"));
+
+        let (mut ws, output) = websocket(input, Default::default());
+        assert_eq!(
+            ws.recv().unwrap(),
+            Message::Ping(b"are you there?".to_vec())
+        );
+        assert_eq!(ws.recv().unwrap(), Message::Text(String::from("Hello")));
+
+        let mut expected = vec![0x8A, 14];
+        expected.extend_from_slice(b"are you there?");
+        assert_eq!(*output.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn should_reassemble_fragmented_message() {
+        let mut input = client_frame(false, 0x1, b"Hello ");
+        input.extend(client_frame(false, 0x0, b"from "));
+        input.extend(client_frame(true, 0x0, b"fragments"));
+
+        let (mut ws, _) = websocket(input, Default::default());
+        assert_eq!(
+            ws.recv().unwrap(),
+            Message::Text(String::from("Hello from fragments"))
+        );
+    }
+
+    #[test]
+    fn should_echo_binary_message_split_in_frames() {
+        let input = client_frame(true, 0x2, &[1, 2, 3, 4, 5]);
+        let config = WebSocketConfig {
+            max_frame_size: Some(2),
+            ..Default::default()
+        };
+
+        let (mut ws, output) = websocket(input, config);
+        let message = ws.recv().unwrap();
+        assert_eq!(message, Message::Binary(vec![1, 2, 3, 4, 5]));
+
+        ws.send_message(message).unwrap();
+        assert_eq!(
+            *output.lock().unwrap(),
+            vec![0x02, 2, 1, 2, 0x00, 2, 3, 4, 0x80, 1, 5]
+        );
+    }
+
+    #[test]
+    fn should_send_text_and_binary_with_their_op_code() {
+        let (mut ws, output) = websocket(Vec::new(), Default::default());
+        ws.send_text("hi").unwrap();
+        assert_eq!(*output.lock().unwrap(), vec![0x81, 2, b'h', b'i']);
+
+        let (mut ws, output) = websocket(Vec::new(), Default::default());
+        ws.send_binary(b"hi".as_slice()).unwrap();
+        assert_eq!(*output.lock().unwrap(), vec![0x82, 2, b'h', b'i']);
+    }
+
+    #[test]
+    fn should_close_after_missed_pongs() {
+        let config = WebSocketConfig {
+            max_missed_pongs: Some(1),
+            ..Default::default()
+        };
+
+        let (mut ws, output) = websocket(Vec::new(), config);
+        ws.send_ping(b"1".as_slice()).unwrap();
+        assert_eq!(*output.lock().unwrap(), vec![0x89, 1, b'1']);
+
+        assert!(matches!(
+            ws.send_ping(b"2".as_slice()),
+            Err(WebSocketError::Closed)
+        ));
+        assert_eq!(output.lock().unwrap()[3], 0x88);
+    }
+}
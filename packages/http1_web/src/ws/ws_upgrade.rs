@@ -12,7 +12,7 @@ use http1::{
 
 use crate::{from_request::FromRequest, state::State, IntoResponse};
 
-use super::{WebSocket, WebSocketConfig};
+use super::{ws::ConnectionPermit, WebSocket, WebSocketConfig, WebSocketLimit};
 
 const WEB_SOCKET_VERSION: &str = "13";
 const WEB_SOCKET_UUID_STR: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
@@ -21,20 +21,25 @@ const WEB_SOCKET_UUID_STR: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
 pub struct PendingWebSocketUpgrade {
     pending: PendingUpgrade,
     config: Option<WebSocketConfig>,
+    permit: Option<ConnectionPermit>,
 }
 
 impl PendingWebSocketUpgrade {
     /// Waits for the websocket connection to be ready.
     /// This blocks the current thread so must be send after send the response or in another thread.
     pub fn wait(self) -> Result<WebSocket, PendingUpgradeError> {
-        let PendingWebSocketUpgrade { pending, config } = self;
+        let PendingWebSocketUpgrade {
+            pending,
+            config,
+            permit,
+        } = self;
         let config = config.unwrap_or_default();
 
         log::debug!("Websocket connection ready with config: {config:?}");
 
         pending
             .wait()
-            .map(|upgrade| WebSocket::with_config(upgrade, config))
+            .map(|upgrade| WebSocket::with_config(upgrade, config).with_permit(permit))
     }
 }
 
@@ -43,6 +48,7 @@ pub struct WebSocketUpgrade {
     key: String,
     pending: PendingUpgrade,
     config: Option<WebSocketConfig>,
+    permit: Option<ConnectionPermit>,
 }
 
 impl WebSocketUpgrade {
@@ -53,6 +59,7 @@ impl WebSocketUpgrade {
             key,
             pending,
             config,
+            permit,
         } = self;
         let hash_bytes = http1::common::sha1::hash(format!("{key}{WEB_SOCKET_UUID_STR}"));
         let accept_key = http1::common::base64::encode_to_string(&hash_bytes);
@@ -67,7 +74,11 @@ impl WebSocketUpgrade {
             )
             .body(Body::empty());
 
-        let pending = PendingWebSocketUpgrade { pending, config };
+        let pending = PendingWebSocketUpgrade {
+            pending,
+            config,
+            permit,
+        };
         (pending, response)
     }
 }
@@ -83,6 +94,7 @@ pub enum WebSocketUpgradeError {
     NoProtocolsSupported(String),
     InvalidVersion(String),
     InvalidKey(String),
+    TooManyConnections(usize),
     Other(BoxError),
 }
 
@@ -112,6 +124,9 @@ impl Display for WebSocketUpgradeError {
                 "Invalid version expected `{WEB_SOCKET_VERSION}` but was `{version}`"
             ),
             WebSocketUpgradeError::InvalidKey(key) => write!(f, "Invalid key: `{key}`"),
+            WebSocketUpgradeError::TooManyConnections(max) => {
+                write!(f, "Too many websocket connections, max is {max}")
+            }
             WebSocketUpgradeError::Other(error) => write!(f, "{error}"),
             WebSocketUpgradeError::NoProtocolsSupported(protocols) => write!(
                 f,
@@ -124,7 +139,13 @@ impl Display for WebSocketUpgradeError {
 impl IntoResponse for WebSocketUpgradeError {
     fn into_response(self) -> http1::response::Response<http1::body::Body> {
         log::error!("Failed to upgrade websocket connection: {self}");
-        StatusCode::BAD_REQUEST.into_response()
+
+        match self {
+            WebSocketUpgradeError::TooManyConnections(_) => {
+                StatusCode::SERVICE_UNAVAILABLE.into_response()
+            }
+            _ => StatusCode::BAD_REQUEST.into_response(),
+        }
     }
 }
 
@@ -209,10 +230,93 @@ impl FromRequest for WebSocketUpgrade {
             .cloned()
             .map(|x| x.into_inner());
 
+        let permit = match req.extensions().get::<State<WebSocketLimit>>() {
+            Some(State(limit)) => Some(limit.try_acquire().ok_or(
+                WebSocketUpgradeError::TooManyConnections(limit.max_connections()),
+            )?),
+            None => None,
+        };
+
         Ok(WebSocketUpgrade {
             key,
             pending,
             config,
+            permit,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{SocketAddr, TcpStream},
+        time::{Duration, Instant},
+    };
+
+    use http1::server::Server;
+
+    use crate::{app::App, ws::WebSocketLimit};
+
+    use super::WebSocketUpgrade;
+
+    fn handshake(addr: SocketAddr) -> (TcpStream, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(
+                b"GET /ws HTTP/1.1\r\n\
+                Host: localhost\r\n\
+                Upgrade: websocket\r\n\
+                Connection: Upgrade\r\n\
+                Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                Sec-WebSocket-Version: 13\r\n\r\n",
+            )
+            .unwrap();
+
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line).unwrap();
+        (stream, status_line)
+    }
+
+    #[test]
+    fn should_reject_websocket_upgrade_over_the_limit() {
+        let limit = WebSocketLimit::new(1);
+        let app = App::new()
+            .state(limit.clone())
+            .get("/ws", |upgrade: WebSocketUpgrade| {
+                let (pending, response) = upgrade.upgrade();
+                std::thread::spawn(move || {
+                    let mut ws = pending.wait().unwrap();
+                    while ws.recv().is_ok() {}
+                });
+
+                response
+            });
+
+        Server::new()
+            .run_scoped(app, |addr| {
+                let (first, status_line) = handshake(addr);
+                assert!(status_line.starts_with("HTTP/1.1 101"), "{status_line}");
+
+                let (second, status_line) = handshake(addr);
+                assert!(status_line.starts_with("HTTP/1.1 503"), "{status_line}");
+                drop(second);
+
+                // The connection is released when the websocket is closed
+                drop(first);
+                let deadline = Instant::now() + Duration::from_secs(5);
+                while limit.active_connections() > 0 {
+                    assert!(
+                        Instant::now() < deadline,
+                        "the websocket connection was not released"
+                    );
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+
+                let (_third, status_line) = handshake(addr);
+                assert!(status_line.starts_with("HTTP/1.1 101"), "{status_line}");
+            })
+            .unwrap();
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
     fmt::{Debug, Display},
     marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     rc::Rc,
     sync::{
         atomic::{
@@ -102,6 +103,31 @@ pub fn missing_field<T: Deserialize>(field_name: &'static str) -> Result<T, Erro
     T::deserialize(MissingFieldDeserializer::<T>(field_name, PhantomData))
 }
 
+/// A map that collects the unknown fields of a struct, used by the `#[flatten]` field of [`crate::impl_deserialize_struct`].
+pub trait FlattenMap: Default {
+    /// The type of the values of the unknown fields.
+    type Value: Deserialize;
+
+    /// Adds an unknown field.
+    fn insert_field(&mut self, key: String, value: Self::Value);
+}
+
+impl<V: Deserialize> FlattenMap for HashMap<String, V> {
+    type Value = V;
+
+    fn insert_field(&mut self, key: String, value: Self::Value) {
+        self.insert(key, value);
+    }
+}
+
+impl<V: Deserialize> FlattenMap for BTreeMap<String, V> {
+    type Value = V;
+
+    fn insert_field(&mut self, key: String, value: Self::Value) {
+        self.insert(key, value);
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Unexpected {
     Bool(bool),
@@ -936,3 +962,37 @@ impl<T: Deserialize> Deserialize for RwLock<T> {
         Ok(RwLock::new(value))
     }
 }
+
+macro_rules! impl_deserialize_from_str {
+    ($($visitor:ident => $T:ty),* $(,)?) => {
+        $(
+            struct $visitor;
+            impl Visitor for $visitor {
+                type Value = $T;
+
+                fn expected(&self) -> &'static str {
+                    stringify!($T)
+                }
+
+                fn visit_string(self, value: String) -> Result<Self::Value, Error> {
+                    value.parse::<$T>().map_err(|err| {
+                        Error::other(format!("invalid {} `{value}`: {err}", stringify!($T)))
+                    })
+                }
+            }
+
+            impl Deserialize for $T {
+                fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, Error> {
+                    deserializer.deserialize_string($visitor)
+                }
+            }
+        )*
+    };
+}
+
+impl_deserialize_from_str!(
+    IpAddrVisitor => IpAddr,
+    Ipv4AddrVisitor => Ipv4Addr,
+    Ipv6AddrVisitor => Ipv6Addr,
+    SocketAddrVisitor => SocketAddr,
+);
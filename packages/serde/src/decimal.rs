@@ -0,0 +1,239 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    ops::Add,
+    str::FromStr,
+};
+
+use crate::{
+    de::{Deserialize, Deserializer, Error},
+    ser::{Serialize, Serializer},
+};
+
+/// A fixed-point decimal number represented as `mantissa * 10^-scale`.
+///
+/// Serializes as a string like `"10.25"` to avoid the precision loss of `f64`.
+/// Decimals with the same value are equal regardless of the scale, so `10.5` is equal to `10.50`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// A decimal with value `0`.
+    pub const ZERO: Decimal = Decimal::new(0, 0);
+
+    /// Constructs a decimal with the value `mantissa * 10^-scale`.
+    pub const fn new(mantissa: i128, scale: u32) -> Self {
+        Decimal { mantissa, scale }
+    }
+
+    /// Returns the integer mantissa.
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Returns the number of digits after the decimal point.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Adds two decimals, returns `None` on overflow.
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let a = self.rescale(scale)?;
+        let b = other.rescale(scale)?;
+        Some(Decimal::new(a.checked_add(b)?, scale))
+    }
+
+    /// Returns the mantissa for the given scale, which must be greater or equal to the current scale.
+    fn rescale(&self, scale: u32) -> Option<i128> {
+        10i128
+            .checked_pow(scale - self.scale)
+            .and_then(|factor| self.mantissa.checked_mul(factor))
+    }
+
+    /// Returns the same value with the min scale.
+    fn normalize(&self) -> Decimal {
+        let mut decimal = *self;
+
+        while decimal.scale > 0 && decimal.mantissa % 10 == 0 {
+            decimal.mantissa /= 10;
+            decimal.scale -= 1;
+        }
+
+        decimal
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("decimal addition overflow")
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            // The value that overflows has a greater magnitude than the other
+            (None, _) => self.mantissa.cmp(&0),
+            (_, None) => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Decimal { mantissa, scale } = self.normalize();
+        mantissa.hash(state);
+        scale.hash(state);
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+
+        // Pad with zeros so there is at least one digit before the point
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{int}.{frac}")
+    }
+}
+
+/// An error when parsing a decimal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalParseError {
+    /// The string is not a decimal number.
+    Invalid,
+
+    /// The number does not fit in the mantissa.
+    Overflow,
+}
+
+impl Display for DecimalParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecimalParseError::Invalid => write!(f, "invalid decimal number"),
+            DecimalParseError::Overflow => write!(f, "decimal number is too large"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalParseError {}
+
+impl FromStr for Decimal {
+    type Err = DecimalParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |x: &str| x.bytes().all(|b| b.is_ascii_digit());
+
+        if (int.is_empty() && frac.is_empty()) || !is_digits(int) || !is_digits(frac) {
+            return Err(DecimalParseError::Invalid);
+        }
+
+        let mut mantissa = 0i128;
+
+        for b in int.bytes().chain(frac.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|x| x.checked_add((b - b'0') as i128))
+                .ok_or(DecimalParseError::Overflow)?;
+        }
+
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        Ok(Decimal::new(mantissa, frac.len() as u32))
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Err> {
+        serializer.serialize_string(self.to_string())
+    }
+}
+
+impl Deserialize for Decimal {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, Error> {
+        let s = String::deserialize(deserializer)?;
+        Decimal::from_str(s.trim()).map_err(Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::json;
+
+    use super::{Decimal, DecimalParseError};
+
+    #[test]
+    fn should_roundtrip_json_string_without_precision_loss() {
+        let decimal = json::from_str::<Decimal>("\"10.25\"").unwrap();
+        assert_eq!(decimal.mantissa(), 1025);
+        assert_eq!(decimal.scale(), 2);
+        assert_eq!(json::to_string(&decimal).unwrap(), "\"10.25\"");
+
+        // Would be `0.30000000000000004` with `f64`
+        let sum = Decimal::from_str("0.1").unwrap() + Decimal::from_str("0.2").unwrap();
+        assert_eq!(sum.to_string(), "0.3");
+
+        let total = json::from_str::<Vec<Decimal>>(r#"["10.25", "-0.05", "3"]"#)
+            .unwrap()
+            .into_iter()
+            .fold(Decimal::ZERO, |acc, x| acc + x);
+        assert_eq!(json::to_string(&total).unwrap(), "\"13.20\"");
+
+        assert!(json::from_str::<Decimal>("\"1.2.3\"").is_err());
+        assert_eq!(Decimal::from_str("."), Err(DecimalParseError::Invalid));
+    }
+
+    #[test]
+    fn should_compare_decimals() {
+        let a = Decimal::from_str("10.5").unwrap();
+        let b = Decimal::from_str("10.50").unwrap();
+        let c = Decimal::from_str("-3.25").unwrap();
+
+        assert_eq!(a, b);
+        assert!(c < a);
+        assert!(Decimal::from_str("10.51").unwrap() > b);
+        assert_eq!([a, c].into_iter().max(), Some(b));
+        assert!(Decimal::new(i128::MAX, 0) > Decimal::new(1, 30));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Write;
+
+use super::{number::Number, ser::JsonSerializationError, value::JsonValue};
+
+/// Writes the value in the canonical form of the JSON Canonicalization Scheme (RFC 8785).
+///
+/// - The object keys are sorted by their UTF-16 code units.
+/// - There is no whitespace between tokens.
+/// - Numbers are formatted as ECMAScript doubles, integers outside the `f64` precision are rounded.
+/// - Strings only escape the characters that must be escaped.
+pub(crate) fn write_canonical(
+    out: &mut String,
+    value: &JsonValue,
+) -> Result<(), JsonSerializationError> {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Number(n) => write_number(out, n)?,
+        JsonValue::String(s) => write_string(out, s),
+        JsonValue::Array(array) => {
+            out.push('[');
+            for (idx, item) in array.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_canonical(out, item)?;
+            }
+            out.push(']');
+        }
+        JsonValue::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (idx, (key, value)) in entries.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_canonical(out, value)?;
+            }
+            out.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\x08' => out.push_str("\\b"),
+            '\x0c' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < '\x20' => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+fn write_number(out: &mut String, number: &Number) -> Result<(), JsonSerializationError> {
+    let value = match number {
+        Number::Float(f) => *f,
+        Number::UInteger(u) => *u as f64,
+        Number::Integer(i) => *i as f64,
+    };
+
+    if !value.is_finite() {
+        return Err(JsonSerializationError::Other(format!(
+            "`{value}` cannot be represented in canonical JSON"
+        )));
+    }
+
+    // Also handles `-0`
+    if value == 0.0 {
+        out.push('0');
+        return Ok(());
+    }
+
+    if value < 0.0 {
+        out.push('-');
+    }
+
+    // The shortest digits that roundtrip, like `1.2345e-7`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exp) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let exp = exp.parse::<i32>().unwrap();
+
+    // Number::prototype::toString, where the value is `0.digits * 10^n`
+    let k = digits.len() as i32;
+    let n = exp + 1;
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        write!(out, "{int}.{frac}").unwrap();
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', -n as usize));
+        out.push_str(&digits);
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+
+        if !rest.is_empty() {
+            write!(out, ".{rest}").unwrap();
+        }
+
+        write!(out, "e{sign}{}", (n - 1).abs()).unwrap();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use orderedmap::OrderedMap;
+
+    use crate::json::{self, number::Number, value::JsonValue};
+
+    fn canonical_number(value: f64) -> String {
+        json::to_canonical_string(&JsonValue::Number(Number::Float(value))).unwrap()
+    }
+
+    #[test]
+    fn should_produce_same_output_for_different_key_orders() {
+        let a = json::from_str::<JsonValue>(
+            r#"{ "name": "Ai", "age": 16, "tags": [ "idol", { "z": null, "a": true } ] }"#,
+        )
+        .unwrap();
+        let b = json::from_str::<JsonValue>(
+            r#"{"tags":[ "idol", {"a": true, "z": null}], "age": 16.0,
+            "name": "Ai"}"#,
+        )
+        .unwrap();
+
+        let expected = r#"{"age":16,"name":"Ai","tags":["idol",{"a":true,"z":null}]}"#;
+        assert_eq!(json::to_canonical_string(&a).unwrap(), expected);
+        assert_eq!(json::to_canonical_string(&b).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_sort_keys_by_utf16_code_units() {
+        let mut map = OrderedMap::new();
+        for (idx, key) in [
+            "\u{20ac}",
+            "\r",
+            "\u{fb33}",
+            "1",
+            "\u{1f600}",
+            "\u{f6}",
+            "b",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            map.insert(
+                key.to_owned(),
+                JsonValue::Number(Number::UInteger(idx as u128)),
+            );
+        }
+
+        let value = JsonValue::Object(map);
+
+        assert_eq!(
+            json::to_canonical_string(&value).unwrap(),
+            "{\"\\r\":1,\"1\":3,\"b\":6,\"\u{f6}\":5,\"\u{20ac}\":0,\"\u{1f600}\":4,\"\u{fb33}\":2}"
+        );
+    }
+
+    #[test]
+    fn should_format_numbers_as_ecmascript() {
+        assert_eq!(canonical_number(-0.0), "0");
+        assert_eq!(canonical_number(1.5), "1.5");
+        assert_eq!(canonical_number(-42.0), "-42");
+        assert_eq!(canonical_number(1e21), "1e+21");
+        assert_eq!(canonical_number(1e20), "100000000000000000000");
+        assert_eq!(canonical_number(0.000001), "0.000001");
+        assert_eq!(canonical_number(1.2345e-7), "1.2345e-7");
+        assert_eq!(canonical_number(333333333.3333333), "333333333.3333333");
+        assert_eq!(canonical_number(f64::MAX), "1.7976931348623157e+308");
+        assert!(json::to_canonical_string(&JsonValue::Number(Number::Float(f64::NAN))).is_err());
+    }
+}
@@ -1,8 +1,14 @@
-use std::io::{BufReader, Read};
+use std::{
+    io::{BufReader, Read},
+    marker::PhantomData,
+};
 
 use orderedmap::OrderedMap;
 
-use crate::de::{Deserializer, Error, Unexpected};
+use crate::{
+    de::{Deserialize, Deserializer, Error, Unexpected},
+    visitor::{MapAccess, SeqAccess, Visitor},
+};
 
 use super::{
     number::Number,
@@ -13,6 +19,7 @@ pub struct JsonDeserializer<R> {
     reader: BufReader<R>,
     next: Option<u8>,
     depth: usize,
+    lenient: bool,
 }
 
 impl JsonDeserializer<()> {
@@ -24,11 +31,20 @@ impl JsonDeserializer<()> {
             reader: BufReader::new(reader),
             next: None,
             depth: 0,
+            lenient: false,
         }
     }
 }
 
 impl<R: Read> JsonDeserializer<R> {
+    /// Whether to coerce mismatched scalars, a number is accepted for a string and a string for a number.
+    ///
+    /// Defaults to `false`.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
     fn peek(&mut self) -> Option<u8> {
         match self.next {
             Some(b) => Some(b),
@@ -403,6 +419,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_u8(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_u8() {
@@ -418,6 +438,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_u16(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_u16() {
@@ -433,6 +457,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_u32(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_u32() {
@@ -448,6 +476,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_u64(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_u64() {
@@ -463,6 +495,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_u128(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_u128() {
@@ -478,6 +514,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_i8(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_i8() {
@@ -490,6 +530,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_i16(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_i16() {
@@ -502,6 +546,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_i32(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_i32() {
@@ -514,6 +562,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_i64(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_i64() {
@@ -526,6 +578,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_i128(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_i128() {
@@ -538,6 +594,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_f32(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_f32() {
@@ -550,6 +610,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_f64(visitor);
+        }
+
         let value = self.parse_number()?;
 
         match value.as_f64() {
@@ -582,6 +646,10 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     where
         V: crate::visitor::Visitor,
     {
+        if self.lenient {
+            return LenientJsonValue(self.parse_json()?).deserialize_string(visitor);
+        }
+
         let string = self.parse_string()?;
         visitor.visit_string(string)
     }
@@ -591,6 +659,11 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
         V: crate::visitor::Visitor,
     {
         let seq = self.parse_array()?;
+
+        if self.lenient {
+            return visitor.visit_seq(LenientSeqAccess(seq.into_iter()));
+        }
+
         visitor.visit_seq(JsonSeqAccess(seq.into_iter()))
     }
 
@@ -599,6 +672,14 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
         V: crate::visitor::Visitor,
     {
         let map = self.parse_object()?;
+
+        if self.lenient {
+            return visitor.visit_map(LenientObjectAccess {
+                iter: map.into_iter(),
+                value: None,
+            });
+        }
+
         visitor.visit_map(JsonObjectAccess::new(map.into_iter()))
     }
 
@@ -607,6 +688,11 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
         V: crate::visitor::Visitor,
     {
         let json_value = self.parse_json()?;
+
+        if self.lenient {
+            return LenientJsonValue(json_value).deserialize_any(visitor);
+        }
+
         json_value.deserialize_any(visitor)
     }
 
@@ -615,6 +701,11 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
         V: crate::visitor::Visitor,
     {
         let json_value = self.parse_json()?;
+
+        if self.lenient {
+            return LenientJsonValue(json_value).deserialize_option(visitor);
+        }
+
         json_value.deserialize_option(visitor)
     }
 
@@ -647,11 +738,326 @@ impl<R: Read> Deserializer for JsonDeserializer<R> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamState {
+    Start,
+    Array,
+    Values,
+    Done,
+}
+
+/// Deserializes a stream of JSON values element by element from a reader,
+/// this allow to read large inputs without holding all the values in memory.
+///
+/// The input can be a JSON array like `[1, 2, 3]` or whitespace separated values like newline delimited JSON.
+/// After an error the iterator is exhausted.
+pub struct StreamDeserializer<R, T> {
+    deserializer: JsonDeserializer<R>,
+    state: StreamState,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<R: Read, T> StreamDeserializer<R, T> {
+    /// Constructs a new `StreamDeserializer` that reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        let mut deserializer = JsonDeserializer::new(reader);
+
+        // Elements must not consume the rest of the input
+        deserializer.depth = 1;
+
+        StreamDeserializer {
+            deserializer,
+            state: StreamState::Start,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Whether to coerce mismatched scalars, a number is accepted for a string and a string for a number.
+    ///
+    /// Defaults to `false`.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.deserializer.lenient = lenient;
+        self
+    }
+
+    /// Moves to the next element, returns `false` if there are no more elements.
+    fn advance(&mut self) -> Result<bool, Error> {
+        let de = &mut self.deserializer;
+
+        match self.state {
+            StreamState::Start => match de.read_until_next_non_whitespace() {
+                None => Ok(false),
+                Some(b'[') => {
+                    de.read_byte();
+
+                    if de.read_until_next_non_whitespace() == Some(b']') {
+                        de.read_byte();
+                        return self.end();
+                    }
+
+                    self.state = StreamState::Array;
+                    Ok(true)
+                }
+                Some(_) => {
+                    self.state = StreamState::Values;
+                    Ok(true)
+                }
+            },
+            StreamState::Array => match de.read_until_next_non_whitespace() {
+                Some(b',') => {
+                    de.read_byte();
+                    Ok(true)
+                }
+                Some(b']') => {
+                    de.read_byte();
+                    self.end()
+                }
+                Some(b) => Err(Error::other(format!(
+                    "expected `,` or `]` but was `{}`",
+                    b as char
+                ))),
+                None => Err(Error::other("expected `]` but was empty")),
+            },
+            StreamState::Values => Ok(de.read_until_next_non_whitespace().is_some()),
+            StreamState::Done => Ok(false),
+        }
+    }
+
+    /// Ensures only whitespace remains after the end of the array.
+    fn end(&mut self) -> Result<bool, Error> {
+        self.state = StreamState::Done;
+        self.deserializer.depth = 0;
+        self.deserializer.consume_rest()?;
+        Ok(false)
+    }
+
+    fn next_element(&mut self) -> Result<Option<T>, Error>
+    where
+        T: Deserialize,
+    {
+        if !self.advance()? {
+            self.state = StreamState::Done;
+            return Ok(None);
+        }
+
+        let value = self.deserializer.parse_json()?;
+
+        if self.deserializer.lenient {
+            return T::deserialize(LenientJsonValue(value)).map(Some);
+        }
+
+        T::deserialize(value).map(Some)
+    }
+}
+
+impl<R: Read, T: Deserialize> Iterator for StreamDeserializer<R, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == StreamState::Done {
+            return None;
+        }
+
+        match self.next_element() {
+            Ok(value) => value.map(Ok),
+            Err(err) => {
+                self.state = StreamState::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// A `JsonValue` deserializer that coerces numbers to strings and strings to numbers.
+struct LenientJsonValue(JsonValue);
+
+impl LenientJsonValue {
+    fn into_number(self) -> JsonValue {
+        match self.0 {
+            JsonValue::String(s) => match parse_number_str(&s) {
+                Some(n) => JsonValue::Number(n),
+                None => JsonValue::String(s),
+            },
+            value => value,
+        }
+    }
+}
+
+fn parse_number_str(s: &str) -> Option<Number> {
+    if let Ok(u) = s.parse::<u128>() {
+        return Some(Number::from(u));
+    }
+
+    if let Ok(i) = s.parse::<i128>() {
+        return Some(Number::from(i));
+    }
+
+    s.parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite())
+        .map(Number::from)
+}
+
+macro_rules! lenient_deserialize_number {
+    ($($method:ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor,
+            {
+                self.into_number().$method(visitor)
+            }
+        )*
+    };
+}
+
+impl Deserializer for LenientJsonValue {
+    lenient_deserialize_number!(
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_f32,
+        deserialize_f64
+    );
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        self.0.deserialize_unit(visitor)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        self.0.deserialize_bool(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        self.0.deserialize_char(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        match self.0 {
+            JsonValue::Number(n) => visitor.visit_string(n.to_string()),
+            value => value.deserialize_string(visitor),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        match self.0 {
+            JsonValue::Array(value) => visitor.visit_seq(LenientSeqAccess(value.into_iter())),
+            value => value.deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        match self.0 {
+            JsonValue::Object(value) => visitor.visit_map(LenientObjectAccess {
+                iter: value.into_iter(),
+                value: None,
+            }),
+            value => value.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        match self.0 {
+            JsonValue::Array(_) => self.deserialize_seq(visitor),
+            JsonValue::Object(_) => self.deserialize_map(visitor),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        match self.0 {
+            JsonValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(LenientJsonValue(value)),
+        }
+    }
+
+    fn deserialize_bytes_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        self.0.deserialize_bytes_buf(visitor)
+    }
+
+    fn deserialize_bytes_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor,
+    {
+        self.0.deserialize_bytes_seq(visitor)
+    }
+}
+
+struct LenientSeqAccess(std::vec::IntoIter<JsonValue>);
+
+impl SeqAccess for LenientSeqAccess {
+    fn next_element<T: Deserialize>(&mut self) -> Result<Option<T>, Error> {
+        match self.0.next() {
+            Some(x) => T::deserialize(LenientJsonValue(x)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct LenientObjectAccess<I> {
+    iter: I,
+    value: Option<JsonValue>,
+}
+
+impl<I: Iterator<Item = (String, JsonValue)>> MapAccess for LenientObjectAccess<I> {
+    fn next_key<K: Deserialize>(&mut self) -> Result<Option<K>, Error> {
+        match self.iter.next() {
+            Some((k, v)) => {
+                self.value = Some(v);
+                K::deserialize(JsonValue::String(k)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value<V: Deserialize>(&mut self) -> Result<Option<V>, Error> {
+        match self.value.take() {
+            Some(v) => V::deserialize(LenientJsonValue(v)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        impl_deserialize_struct, impl_serde_struct,
-        json::{from_str, value::JsonValue},
+        impl_deserialize_enum_tagged, impl_deserialize_enum_untagged, impl_deserialize_struct,
+        impl_serde_struct,
+        json::{from_str, from_str_lenient, from_value, value::JsonValue, StreamDeserializer},
     };
 
     #[test]
@@ -777,6 +1183,28 @@ mod tests {
         assert_eq!(from_str::<f64>("-9.87e-6").unwrap(), -9.87e-6); // -0.00000987
     }
 
+    #[test]
+    fn should_deserialize_numbers_without_losing_precision() {
+        // Integer into float
+        assert_eq!(from_str::<f64>("3").unwrap(), 3.0);
+        assert_eq!(from_str::<f32>("-3").unwrap(), -3.0);
+
+        // Float with no fractional part into integer
+        assert_eq!(from_str::<u64>("3.0").unwrap(), 3);
+        assert_eq!(from_str::<i32>("-3.0").unwrap(), -3);
+        assert_eq!(from_str::<u8>("2.55e2").unwrap(), 255);
+
+        // Truncation or overflow
+        assert!(from_str::<u64>("3.5").is_err());
+        assert!(from_str::<i64>("-0.1").is_err());
+        assert!(from_str::<u64>("-3.0").is_err());
+        assert!(from_str::<u8>("256.0").is_err());
+
+        // From a `JsonValue`
+        assert_eq!(from_value::<u64>(JsonValue::from(3.0)).unwrap(), 3);
+        assert!(from_value::<u64>(JsonValue::from(3.5)).is_err());
+    }
+
     #[test]
     fn should_deserialize_string() {
         assert_eq!(
@@ -1208,4 +1636,361 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn should_deserialize_internally_tagged_enum() {
+        #[derive(Debug, PartialEq)]
+        struct Circle {
+            radius: u32,
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Square {
+            side: u32,
+        }
+
+        #[derive(Debug, PartialEq)]
+        enum Shape {
+            Circle(Circle),
+            Square(Square),
+        }
+
+        impl_deserialize_struct!(Circle => { radius: u32 });
+        impl_deserialize_struct!(Square, deny_unknown = true => { side: u32 });
+        impl_deserialize_enum_tagged!(Shape, "type" => {
+            Circle = "circle",
+            Square
+        });
+
+        assert_eq!(
+            from_str::<Shape>(r#"{ "type": "circle", "radius": 1 }"#).unwrap(),
+            Shape::Circle(Circle { radius: 1 })
+        );
+
+        // The tag is not passed to the variant
+        assert_eq!(
+            from_str::<Shape>(r#"{ "side": 2, "type": "Square" }"#).unwrap(),
+            Shape::Square(Square { side: 2 })
+        );
+
+        assert!(from_str::<Shape>(r#"{ "type": "triangle", "side": 2 }"#).is_err());
+        assert!(from_str::<Shape>(r#"{ "radius": 1 }"#).is_err());
+        assert!(from_str::<Shape>(r#"{ "type": 1, "radius": 1 }"#).is_err());
+    }
+
+    #[test]
+    fn should_deserialize_untagged_enum() {
+        #[derive(Debug, PartialEq)]
+        struct Circle {
+            radius: u32,
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Square {
+            side: u32,
+        }
+
+        #[derive(Debug, PartialEq)]
+        enum Shape {
+            Circle(Circle),
+            Square(Square),
+            Size(u32),
+        }
+
+        impl_deserialize_struct!(Circle, deny_unknown = true => { radius: u32 });
+        impl_deserialize_struct!(Square, deny_unknown = true => { side: u32 });
+        impl_deserialize_enum_untagged!(Shape => { Circle, Square, Size });
+
+        assert_eq!(
+            from_str::<Shape>(r#"{ "side": 2 }"#).unwrap(),
+            Shape::Square(Square { side: 2 })
+        );
+        assert_eq!(from_str::<Shape>("3").unwrap(), Shape::Size(3));
+
+        let err = from_str::<Shape>(r#""triangle""#).err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("data did not match any variant of untagged enum Shape"));
+    }
+
+    #[test]
+    fn should_deserialize_with_unknown_fields_allowed() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Value {
+            name: String,
+            age: u32,
+        }
+
+        impl_deserialize_struct!(Value, deny_unknown = false => {
+            name: String,
+            age: u32,
+        });
+
+        assert_eq!(
+            from_str::<Value>(r#"{ "name": "Kaguya", "age": 17, "debug": true }"#).unwrap(),
+            Value {
+                name: String::from("Kaguya"),
+                age: 17
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_to_deserialize_with_unknown_fields_denied() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Value {
+            name: String,
+            age: u32,
+        }
+
+        impl_deserialize_struct!(Value, deny_unknown = true => {
+            name: String,
+            age: u32,
+        });
+
+        assert!(from_str::<Value>(r#"{ "name": "Kaguya", "age": 17 }"#).is_ok());
+        assert!(from_str::<Value>(r#"{ "name": "Kaguya", "age": 17, "debug": true }"#).is_err());
+    }
+
+    #[test]
+    fn should_deserialize_with_default_values() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Config {
+            port: u16,
+            host: String,
+            timeout: Option<u32>,
+            retries: Option<u32>,
+        }
+
+        impl_deserialize_struct!(Config => {
+            port: u16 = 8080,
+            host: String,
+            timeout: Option<u32> = Some(30),
+            retries: Option<u32>,
+        });
+
+        // Absent fields
+        assert_eq!(
+            from_str::<Config>(r#"{ "host": "localhost" }"#).unwrap(),
+            Config {
+                port: 8080,
+                host: String::from("localhost"),
+                timeout: Some(30),
+                retries: None
+            }
+        );
+
+        // Present but null fields
+        assert_eq!(
+            from_str::<Config>(
+                r#"{ "port": 3000, "host": "localhost", "timeout": null, "retries": null }"#
+            )
+            .unwrap(),
+            Config {
+                port: 3000,
+                host: String::from("localhost"),
+                timeout: None,
+                retries: None
+            }
+        );
+
+        // Fields without default are still required
+        assert!(from_str::<Config>(r#"{ "port": 3000 }"#).is_err());
+    }
+
+    #[test]
+    fn should_serialize_and_deserialize_ip_and_socket_addresses() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        use crate::json::to_string;
+
+        let ipv4 = from_str::<IpAddr>(r#""127.0.0.1""#).unwrap();
+        assert_eq!(ipv4, IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(to_string(&ipv4).unwrap(), r#""127.0.0.1""#);
+
+        let ipv6 = from_str::<Ipv6Addr>(r#""2001:db8::1""#).unwrap();
+        assert_eq!(ipv6, Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(to_string(&ipv6).unwrap(), r#""2001:db8::1""#);
+
+        let socket_addr = from_str::<SocketAddr>(r#""192.168.0.10:8080""#).unwrap();
+        assert_eq!(socket_addr, SocketAddr::from(([192, 168, 0, 10], 8080)));
+        assert_eq!(to_string(&socket_addr).unwrap(), r#""192.168.0.10:8080""#);
+
+        let socket_addr = from_str::<SocketAddr>(r#""[::1]:3000""#).unwrap();
+        assert_eq!(to_string(&socket_addr).unwrap(), r#""[::1]:3000""#);
+
+        assert!(from_str::<Ipv4Addr>(r#""::1""#).is_err());
+        assert!(from_str::<IpAddr>(r#""localhost""#).is_err());
+        assert!(from_str::<SocketAddr>(r#""127.0.0.1""#).is_err());
+    }
+
+    #[test]
+    fn should_deserialize_absent_optional_nested_struct_as_none() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Address {
+            city: String,
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct User {
+            name: String,
+            address: Option<Address>,
+        }
+
+        impl_deserialize_struct!(Address => { city: String });
+        impl_deserialize_struct!(User => {
+            name: String,
+            address: Option<Address>,
+        });
+
+        assert_eq!(
+            from_str::<User>(r#"{ "name": "Aqua" }"#).unwrap(),
+            User {
+                name: String::from("Aqua"),
+                address: None
+            }
+        );
+
+        assert_eq!(
+            from_str::<User>(r#"{ "name": "Aqua", "address": null }"#).unwrap(),
+            User {
+                name: String::from("Aqua"),
+                address: None
+            }
+        );
+
+        assert_eq!(
+            from_str::<User>(r#"{ "name": "Aqua", "address": { "city": "Tokyo" } }"#).unwrap(),
+            User {
+                name: String::from("Aqua"),
+                address: Some(Address {
+                    city: String::from("Tokyo")
+                })
+            }
+        );
+
+        // The nested struct fields are still required when present
+        assert!(from_str::<User>(r#"{ "name": "Aqua", "address": {} }"#).is_err());
+    }
+
+    #[test]
+    fn should_coerce_numbers_and_strings_when_lenient() {
+        #[derive(Debug, PartialEq)]
+        struct Product {
+            sku: String,
+            price: f64,
+            stock: Option<u32>,
+            tags: Vec<String>,
+        }
+
+        impl_deserialize_struct!(Product => {
+            sku: String,
+            price: f64,
+            stock: Option<u32>,
+            tags: Vec<String>,
+        });
+
+        let json = r#"{ "sku": 1024, "price": "9.5", "stock": "12", "tags": [1, "new"] }"#;
+
+        assert_eq!(
+            from_str_lenient::<Product>(json).unwrap(),
+            Product {
+                sku: String::from("1024"),
+                price: 9.5,
+                stock: Some(12),
+                tags: vec![String::from("1"), String::from("new")]
+            }
+        );
+
+        assert_eq!(from_str_lenient::<String>("-3").unwrap(), "-3");
+        assert_eq!(from_str_lenient::<i32>(r#""-3""#).unwrap(), -3);
+        assert_eq!(from_str_lenient::<u8>("200").unwrap(), 200);
+        assert!(from_str_lenient::<u8>(r#""300""#).is_err());
+        assert!(from_str_lenient::<u32>(r#""twelve""#).is_err());
+    }
+
+    #[test]
+    fn should_reject_mismatched_scalars_when_strict() {
+        assert!(from_str::<String>("1024").is_err());
+        assert!(from_str::<u32>(r#""12""#).is_err());
+        assert!(from_str::<Vec<String>>("[1, 2]").is_err());
+
+        #[derive(Debug, PartialEq)]
+        struct Product {
+            sku: String,
+        }
+
+        impl_deserialize_struct!(Product => { sku: String });
+
+        assert!(from_str::<Product>(r#"{ "sku": 1024 }"#).is_err());
+        assert!(from_str_lenient::<Product>(r#"{ "sku": 1024 }"#).is_ok());
+    }
+
+    #[test]
+    fn should_stream_array_elements() {
+        #[derive(Debug, PartialEq)]
+        struct Item {
+            id: u32,
+            name: String,
+        }
+
+        impl_deserialize_struct!(Item => { id: u32, name: String });
+
+        let input = r#" [ {"id": 1, "name": "Ai"}, {"id": 2, "name": "Ruby"} ] "#;
+        let items = StreamDeserializer::<_, Item>::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                Item {
+                    id: 1,
+                    name: "Ai".into()
+                },
+                Item {
+                    id: 2,
+                    name: "Ruby".into()
+                }
+            ]
+        );
+
+        let empty = StreamDeserializer::<_, u32>::new("[ ]".as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn should_stream_newline_delimited_values() {
+        let input = "1\n2\n\n[3, 4]\n";
+        let values = StreamDeserializer::<_, JsonValue>::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(from_value::<u32>(values[1].clone()).unwrap(), 2);
+        assert_eq!(
+            from_value::<Vec<u32>>(values[2].clone()).unwrap(),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn should_stop_streaming_after_error() {
+        let mut iter = StreamDeserializer::<_, u32>::new("[1, 2 3]".as_bytes());
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert_eq!(iter.next().unwrap().unwrap(), 2);
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+
+        let mut iter = StreamDeserializer::<_, u32>::new("[1] 2".as_bytes());
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert!(iter.next().unwrap().is_err());
+
+        let mut iter = StreamDeserializer::<_, u32>::new("[1, 2".as_bytes());
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert_eq!(iter.next().unwrap().unwrap(), 2);
+        assert!(iter.next().unwrap().is_err());
+    }
 }
@@ -100,28 +100,34 @@ impl<W: Write> Formatter<W> for CompactFormatter {
     }
 }
 
-pub struct PrettyFormatter {
+pub struct PrettyFormatter<'a> {
     level: usize,
-    indent: &'static [u8],
+    indent: &'a [u8],
 }
 
-impl PrettyFormatter {
+impl PrettyFormatter<'static> {
     pub fn new() -> Self {
-        Self::with_indent(b"  ")
+        PrettyFormatter::with_indent("  ")
     }
+}
 
-    pub fn with_indent(indent: &'static [u8]) -> Self {
-        PrettyFormatter { level: 0, indent }
+impl<'a> PrettyFormatter<'a> {
+    /// Constructs a formatter that uses the given string for each indentation level.
+    pub fn with_indent(indent: &'a str) -> Self {
+        PrettyFormatter {
+            level: 0,
+            indent: indent.as_bytes(),
+        }
     }
 }
 
-impl Default for PrettyFormatter {
+impl Default for PrettyFormatter<'static> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl PrettyFormatter {
+impl PrettyFormatter<'_> {
     fn write_indented<W: Write>(&mut self, w: &mut W, value: &[u8]) -> std::io::Result<()> {
         for _ in 0..self.level {
             w.write_all(self.indent)?;
@@ -197,7 +203,7 @@ where
     }
 }
 
-impl<W> Formatter<W> for PrettyFormatter
+impl<W> Formatter<W> for PrettyFormatter<'_>
 where
     W: Write,
 {
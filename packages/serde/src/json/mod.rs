@@ -10,12 +10,17 @@ use super::{
     ser::Serialize,
 };
 
+mod canonical;
+
 pub mod de;
 pub mod formatter;
 pub mod number;
 pub mod ser;
 pub mod value;
 
+pub use de::StreamDeserializer;
+pub use ser::StreamArraySerializer;
+
 // Serialize
 
 /// Serialize the value of type `T` to a writer.
@@ -30,10 +35,19 @@ pub fn to_writer<W: Write, T: Serialize>(
 
 /// Serialize the value of type `T` to a writer formatted.
 pub fn to_pretty_writer<W: Write, T: Serialize>(
+    writer: W,
+    value: &T,
+) -> Result<(), JsonSerializationError> {
+    to_pretty_writer_with(writer, value, "  ")
+}
+
+/// Serialize the value of type `T` to a writer formatted using the given indentation.
+pub fn to_pretty_writer_with<W: Write, T: Serialize>(
     mut writer: W,
     value: &T,
+    indent: &str,
 ) -> Result<(), JsonSerializationError> {
-    let mut serializer = JsonSerializer::new(&mut writer, PrettyFormatter::new());
+    let mut serializer = JsonSerializer::new(&mut writer, PrettyFormatter::with_indent(indent));
     value.serialize(&mut serializer)?;
     Ok(())
 }
@@ -64,6 +78,27 @@ pub fn to_pretty_string<T: Serialize>(value: &T) -> Result<String, JsonSerializa
     String::from_utf8(bytes).map_err(|err| JsonSerializationError::Other(err.to_string()))
 }
 
+/// Serialize a value of type `T` to a formatted JSON string using the given indentation.
+pub fn to_pretty_string_with<T: Serialize>(
+    value: &T,
+    indent: &str,
+) -> Result<String, JsonSerializationError> {
+    let mut buf = Vec::<u8>::new();
+    to_pretty_writer_with(&mut buf, value, indent)?;
+    String::from_utf8(buf).map_err(|err| JsonSerializationError::Other(err.to_string()))
+}
+
+/// Serialize a value of type `T` to a canonical JSON string as defined by the JSON Canonicalization Scheme (RFC 8785).
+///
+/// The object keys are sorted and there is no whitespace, so the same value always produces the same output,
+/// which is useful to sign JSON payloads.
+pub fn to_canonical_string<T: Serialize>(value: &T) -> Result<String, JsonSerializationError> {
+    let value = to_value(value)?;
+    let mut buf = String::new();
+    canonical::write_canonical(&mut buf, &value)?;
+    Ok(buf)
+}
+
 /// Serialize a value of type `T` to a `JsonValue`.
 pub fn to_value<T>(value: &T) -> Result<JsonValue, JsonSerializationError>
 where
@@ -83,6 +118,15 @@ where
     T::deserialize(JsonDeserializer::new(reader))
 }
 
+/// Deserialize a reader to a value of type `T`, coercing numbers to strings and strings to numbers.
+pub fn from_reader_lenient<T, R>(reader: R) -> Result<T, Error>
+where
+    T: Deserialize,
+    R: Read,
+{
+    T::deserialize(JsonDeserializer::new(reader).lenient(true))
+}
+
 /// Deserialize bytes to a value of type `T`.
 pub fn from_bytes<T>(bytes: impl AsRef<[u8]>) -> Result<T, Error>
 where
@@ -99,6 +143,14 @@ where
     from_reader(str.as_ref().as_bytes())
 }
 
+/// Deserialize a string to a value of type `T`, coercing numbers to strings and strings to numbers.
+pub fn from_str_lenient<T>(str: impl AsRef<str>) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    from_reader_lenient(str.as_ref().as_bytes())
+}
+
 /// Deserialize a `JsonValue` to a value of type `T`.
 pub fn from_value<T>(value: JsonValue) -> Result<T, Error>
 where
@@ -44,7 +44,7 @@ impl Number {
         match self {
             Number::Integer(i) => (*i).try_into().ok(),
             Number::UInteger(u) => (*u).try_into().ok(),
-            Number::Float(f) => Some(*f as i8),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -52,7 +52,7 @@ impl Number {
         match self {
             Number::Integer(i) => (*i).try_into().ok(),
             Number::UInteger(u) => (*u).try_into().ok(),
-            Number::Float(f) => Some(*f as i16),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -60,7 +60,7 @@ impl Number {
         match self {
             Number::Integer(i) => (*i).try_into().ok(),
             Number::UInteger(u) => (*u).try_into().ok(),
-            Number::Float(f) => Some(*f as i32),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -68,7 +68,7 @@ impl Number {
         match self {
             Number::Integer(i) => (*i).try_into().ok(),
             Number::UInteger(u) => (*u).try_into().ok(),
-            Number::Float(f) => Some(*f as i64),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -76,7 +76,7 @@ impl Number {
         match self {
             Number::Integer(i) => Some(*i),
             Number::UInteger(u) => (*u).try_into().ok(),
-            Number::Float(f) => Some(*f as i128),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -85,7 +85,7 @@ impl Number {
         match self {
             Number::UInteger(u) => (*u).try_into().ok(),
             Number::Integer(i) => (*i).try_into().ok(),
-            Number::Float(f) => Some(*f as u8),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -93,7 +93,7 @@ impl Number {
         match self {
             Number::UInteger(u) => (*u).try_into().ok(),
             Number::Integer(i) => (*i).try_into().ok(),
-            Number::Float(f) => Some(*f as u16),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -101,7 +101,7 @@ impl Number {
         match self {
             Number::UInteger(u) => (*u).try_into().ok(),
             Number::Integer(i) => (*i).try_into().ok(),
-            Number::Float(f) => Some(*f as u32),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -109,7 +109,7 @@ impl Number {
         match self {
             Number::UInteger(u) => (*u).try_into().ok(),
             Number::Integer(i) => (*i).try_into().ok(),
-            Number::Float(f) => Some(*f as u64),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 
@@ -117,11 +117,21 @@ impl Number {
         match self {
             Number::UInteger(u) => Some(*u),
             Number::Integer(i) => (*i).try_into().ok(),
-            Number::Float(f) => Some(*f as u128),
+            Number::Float(f) => float_to_int(*f),
         }
     }
 }
 
+/// Converts a float to an integer only if it has no fractional part and fits in the target type.
+fn float_to_int<T: TryFrom<i128>>(value: f64) -> Option<T> {
+    // `i128::MAX as f64` rounds up to 2^127, which is already out of range
+    if value.fract() != 0.0 || value < i128::MIN as f64 || value >= i128::MAX as f64 {
+        return None;
+    }
+
+    (value as i128).try_into().ok()
+}
+
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
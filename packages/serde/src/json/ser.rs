@@ -5,7 +5,10 @@ use crate::{
     ser::{BytesSerializer, MapSerializer, SequenceSerializer, Serialize, Serializer},
 };
 
-use super::{formatter::Formatter, value::JsonValue};
+use super::{
+    formatter::{CompactFormatter, Formatter},
+    value::JsonValue,
+};
 
 #[derive(Debug)]
 pub enum JsonSerializationError {
@@ -307,6 +310,65 @@ impl<'a, W: Write, F: Formatter<W>> Serializer for KeySerializer<'a, W, F> {
     }
 }
 
+/// Serializes a JSON array element by element to a writer,
+/// this allow to write large arrays without holding all the elements in memory.
+pub struct StreamArraySerializer<W> {
+    serializer: JsonSerializer<W, CompactFormatter>,
+    count: usize,
+}
+
+impl<W: Write> StreamArraySerializer<W> {
+    /// Constructs a new `StreamArraySerializer` that writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        StreamArraySerializer {
+            serializer: JsonSerializer::new(writer, CompactFormatter),
+            count: 0,
+        }
+    }
+
+    fn write_start(&mut self) -> Result<(), JsonSerializationError> {
+        if self.count == 0 {
+            self.serializer
+                .formatter
+                .write_array_start(&mut self.serializer.writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Serializes and writes the next element of the array.
+    pub fn serialize_element<T: Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), JsonSerializationError> {
+        self.write_start()?;
+
+        self.serializer
+            .formatter
+            .write_array_element_begin(&mut self.serializer.writer, self.count == 0)?;
+
+        value.serialize(&mut self.serializer)?;
+
+        self.serializer
+            .formatter
+            .write_array_element_end(&mut self.serializer.writer)?;
+
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Closes the array and returns the underlying writer.
+    pub fn end(mut self) -> Result<W, JsonSerializationError> {
+        self.write_start()?;
+
+        self.serializer
+            .formatter
+            .write_array_end(&mut self.serializer.writer)?;
+
+        Ok(self.serializer.writer)
+    }
+}
+
 fn map_key_error() -> JsonSerializationError {
     JsonSerializationError::Other("Keys can only be serialized to string".into())
 }
@@ -316,10 +378,14 @@ mod tests {
     use orderedmap::OrderedMap;
 
     use crate::{
-        impl_serde_struct,
-        json::{number::Number, to_pretty_string, to_string, value::JsonValue},
+        impl_serde_struct, impl_serialize_struct,
+        json::{
+            number::Number, to_pretty_string, to_pretty_string_with, to_string, value::JsonValue,
+        },
     };
 
+    use super::StreamArraySerializer;
+
     #[test]
     fn should_serialize_number() {
         let f = JsonValue::Number(Number::Float(0.5));
@@ -475,12 +541,84 @@ mod tests {
         assert_eq!(to_pretty_string(&object).unwrap(), expected_pretty);
     }
 
+    #[test]
+    fn should_serialize_with_custom_indent() {
+        let mut map = OrderedMap::new();
+        map.insert(String::from("number"), JsonValue::from(1));
+        map.insert(String::from("boolean"), JsonValue::from(true));
+
+        let object = JsonValue::Object(map);
+        let tabs = to_pretty_string_with(&object, "\t").unwrap();
+
+        assert_ne!(tabs, to_pretty_string(&object).unwrap());
+        assert_eq!(tabs, "{\n\t\"number\": 1,\n\t\"boolean\": true\n}");
+        assert_eq!(
+            to_pretty_string_with(&object, "  ").unwrap(),
+            to_pretty_string(&object).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_serialize_stream_array() {
+        let values = vec![
+            JsonValue::from("Hoshino Ai"),
+            JsonValue::from(16),
+            JsonValue::Array(vec![JsonValue::from(true), JsonValue::Null]),
+        ];
+
+        let mut stream = StreamArraySerializer::new(Vec::new());
+        for value in values.iter() {
+            stream.serialize_element(value).unwrap();
+        }
+
+        let bytes = stream.end().unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            to_string(&values).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_serialize_empty_stream_array() {
+        let stream = StreamArraySerializer::new(Vec::new());
+        let bytes = stream.end().unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "[]");
+    }
+
     #[test]
     fn should_serialize_null() {
         let null = JsonValue::Null;
         assert_eq!(to_string(&null).unwrap(), "null");
     }
 
+    #[test]
+    fn should_skip_none_fields() {
+        struct Value {
+            num: Option<u32>,
+            text: Option<String>,
+            count: u32,
+            items: Vec<u32>,
+        }
+
+        impl_serialize_struct!(Value, skip_none => {
+            num: Option<u32>,
+            text: Option<String>,
+            count: u32,
+            items: Vec<u32>,
+        });
+
+        assert_eq!(
+            to_string(&Value {
+                num: None,
+                text: Some(String::from("hello")),
+                count: 0,
+                items: vec![],
+            })
+            .unwrap(),
+            "{\"text\":\"hello\",\"count\":0,\"items\":[]}"
+        );
+    }
+
     #[test]
     fn should_serialize_object_with_optional_fields() {
         #[derive(Debug, PartialEq, Eq)]
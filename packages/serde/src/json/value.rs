@@ -936,9 +936,8 @@ impl SequenceSerializer for JsonArraySerializer {
     type Err = JsonSerializationError;
 
     fn serialize_element<T: Serialize>(&mut self, value: &T) -> Result<(), Self::Err> {
-        let mut arr = vec![];
         let json_value = value.serialize(JsonValueSerializer)?;
-        arr.push(json_value);
+        self.0.push(json_value);
         Ok(())
     }
 
@@ -1510,4 +1509,26 @@ mod tests {
         );
         assert_eq!(jjk.select("students.1.age").unwrap(), &JsonValue::from(16));
     }
+
+    #[test]
+    fn should_serialize_vec_to_json_array() {
+        let value = crate::json::to_value(&vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            value,
+            JsonValue::Array(vec![
+                JsonValue::from(1),
+                JsonValue::from(2),
+                JsonValue::from(3)
+            ])
+        );
+
+        let nested = crate::json::to_value(&vec![vec!["a"], vec![]]).unwrap();
+        assert_eq!(
+            nested,
+            JsonValue::Array(vec![
+                JsonValue::Array(vec![JsonValue::from("a")]),
+                JsonValue::Array(vec![])
+            ])
+        );
+    }
 }
@@ -1,6 +1,9 @@
 /// Byte stream deserialization utilities.
 pub mod bytes;
 
+/// Fixed-point decimal numbers.
+pub mod decimal;
+
 /// Utilities to display expected errors.
 pub mod expected;
 
@@ -41,9 +44,71 @@ macro_rules! forward_to_deserialize_any {
 }
 
 /// Implement `Deserialize` for a struct.
+///
+/// Unknown fields are ignored by default, use `deny_unknown = true` to reject them:
+///
+/// ```
+/// struct Person {
+///     name: String,
+/// }
+///
+/// serde::impl_deserialize_struct!(Person, deny_unknown = true => {
+///     name: String
+/// });
+///
+/// assert!(serde::json::from_str::<Person>(r#"{ "name": "Ai", "age": 16 }"#).is_err());
+/// ```
+///
+/// Fields of type `Option<T>` are `None` when missing, a default value can be declared for other fields that may be missing:
+///
+/// ```
+/// struct Config {
+///     port: u16,
+///     host: String,
+/// }
+///
+/// serde::impl_deserialize_struct!(Config => {
+///     port: u16 = 8080,
+///     host: String
+/// });
+///
+/// let config = serde::json::from_str::<Config>(r#"{ "host": "localhost" }"#).unwrap();
+/// assert_eq!(config.port, 8080);
+/// ```
+///
+/// A last field marked with `#[flatten]` collects the unknown fields, it can be any [`de::FlattenMap`]:
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// struct Event {
+///     name: String,
+///     extra: HashMap<String, String>,
+/// }
+///
+/// serde::impl_deserialize_struct!(Event => {
+///     name: String,
+///     #[flatten] extra: HashMap<String, String>
+/// });
+///
+/// let event = serde::json::from_str::<Event>(r#"{ "name": "click", "source": "ad" }"#).unwrap();
+/// assert_eq!(event.extra.get("source").unwrap(), "ad");
+/// ```
 #[macro_export]
 macro_rules! impl_deserialize_struct {
-    ($struct:ident => { $($field:ident : $value:ty),* $(,)? }) => {
+    ($struct:ident => { $($field:ident : $value:ty $(= $default:expr)? ,)* #[flatten] $extra:ident : $extra_ty:ty $(,)? }) => {
+        $crate::impl_deserialize_struct!(@impl $struct, deny_unknown = false, flatten = [$extra: $extra_ty] => { $($field: $value $(= $default)?),* });
+    };
+
+    ($struct:ident => { $($field:ident : $value:ty $(= $default:expr)?),* $(,)? }) => {
+        $crate::impl_deserialize_struct!($struct, deny_unknown = false => { $($field: $value $(= $default)?),* });
+    };
+
+    ($struct:ident, deny_unknown = $deny_unknown:literal => { $($field:ident : $value:ty $(= $default:expr)?),* $(,)? }) => {
+        $crate::impl_deserialize_struct!(@impl $struct, deny_unknown = $deny_unknown, flatten = [] => { $($field: $value $(= $default)?),* });
+    };
+
+    (@impl $struct:ident, deny_unknown = $deny_unknown:literal, flatten = [$($extra:ident : $extra_ty:ty)?] => { $($field:ident : $value:ty $(= $default:expr)?),* $(,)? }) => {
         impl $crate::de::Deserialize for $struct {
             fn deserialize<D: $crate::de::Deserializer>(
                 deserializer: D,
@@ -65,6 +130,10 @@ macro_rules! impl_deserialize_struct {
                             let mut $field: Option<$value> = None;
                         )*
 
+                        $(
+                            let mut $extra: $extra_ty = Default::default();
+                        )?
+
                         while let Some(k) = map.next_key::<String>()?  {
                             match k.as_str() {
                                 $(
@@ -83,7 +152,7 @@ macro_rules! impl_deserialize_struct {
                                 )*
 
                                 _ => {
-                                   _ = map.next_value::<$crate::ignore::Ignore>()?;
+                                    $crate::impl_deserialize_struct!(@unknown map, k, $deny_unknown $(, $extra: $extra_ty)?);
                                 }
                             }
                         }
@@ -91,14 +160,17 @@ macro_rules! impl_deserialize_struct {
                         $(
                             let $field = match $field {
                                 Some(x) => x,
-                                None => $crate::de::missing_field(stringify!($field))?
+                                None => $crate::impl_deserialize_struct!(@missing $field $(= $default)?)
                             };
                         )*
 
                         Ok($struct {
                             $(
-                                $field
-                            ),*
+                                $field,
+                            )*
+                            $(
+                                $extra
+                            )?
                         })
                     }
                 }
@@ -107,12 +179,59 @@ macro_rules! impl_deserialize_struct {
             }
         }
     };
+
+    (@unknown $map:ident, $key:ident, $deny_unknown:literal) => {
+        if $deny_unknown {
+            return Err($crate::de::Error::other(format!("unknown field `{}`", $key)));
+        }
+
+        _ = $map.next_value::<$crate::ignore::Ignore>()?;
+    };
+
+    (@unknown $map:ident, $key:ident, $deny_unknown:literal, $extra:ident : $extra_ty:ty) => {
+        if let Some(value) = $map.next_value::<<$extra_ty as $crate::de::FlattenMap>::Value>()? {
+            $crate::de::FlattenMap::insert_field(&mut $extra, $key, value);
+        }
+    };
+
+    (@missing $field:ident) => {
+        $crate::de::missing_field(stringify!($field))?
+    };
+
+    (@missing $field:ident = $default:expr) => {
+        $default
+    };
 }
 
 /// Implement `Serialize` for a struct.
+///
+/// Use `skip_none` to omit the fields that are `None` instead of serializing them as `null`:
+///
+/// ```
+/// struct User {
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// serde::impl_serialize_struct!(User, skip_none => {
+///     name: String,
+///     email: Option<String>
+/// });
+///
+/// let user = User { name: "Ai".into(), email: None };
+/// assert_eq!(serde::json::to_string(&user).unwrap(), r#"{"name":"Ai"}"#);
+/// ```
 #[macro_export]
 macro_rules! impl_serialize_struct {
     ($struct:ident => { $($field:ident : $value:ty),* $(,)? }) => {
+        $crate::impl_serialize_struct!(@impl $struct, skip_none = false => { $($field: $value),* });
+    };
+
+    ($struct:ident, skip_none => { $($field:ident : $value:ty),* $(,)? }) => {
+        $crate::impl_serialize_struct!(@impl $struct, skip_none = true => { $($field: $value),* });
+    };
+
+    (@impl $struct:ident, skip_none = $skip_none:literal => { $($field:ident : $value:ty),* $(,)? }) => {
         impl $crate::ser::Serialize for $struct {
             fn serialize<S: $crate::ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Err> {
                 use $crate::ser::MapSerializer;
@@ -120,7 +239,9 @@ macro_rules! impl_serialize_struct {
                 let mut map = serializer.serialize_map()?;
 
                $(
-                    map.serialize_entry(&stringify!($field), &self.$field)?;
+                    if !($skip_none && $crate::ser::is_none(&self.$field)) {
+                        map.serialize_entry(&stringify!($field), &self.$field)?;
+                    }
                )*
 
                 map.end()
@@ -132,8 +253,8 @@ macro_rules! impl_serialize_struct {
 /// Helper for implementing both `Serialize` and `Deserialize` for a struct.
 #[macro_export]
 macro_rules! impl_serde_struct {
-    ($struct:ident => { $($field:ident : $value:ty),* $(,)? }) => {
-        $crate::impl_deserialize_struct!($struct => { $($field: $value),* });
+    ($struct:ident => { $($field:ident : $value:ty $(= $default:expr)?),* $(,)? }) => {
+        $crate::impl_deserialize_struct!($struct => { $($field: $value $(= $default)?),* });
         $crate::impl_serialize_struct!($struct => { $($field: $value),* });
     };
 }
@@ -199,6 +320,136 @@ macro_rules! impl_serde_enum_str {
     };
 }
 
+/// Implement `Deserialize` for an internally tagged enum with newtype variants.
+///
+/// The tag field selects the variant and the remaining fields are deserialized into the variant value,
+/// by default the tag is the variant name but a different one can be set with `Variant = "name"`.
+///
+/// ```
+/// struct Circle {
+///     radius: u32,
+/// }
+///
+/// serde::impl_deserialize_struct!(Circle => {
+///     radius: u32
+/// });
+///
+/// enum Shape {
+///     Circle(Circle),
+/// }
+///
+/// serde::impl_deserialize_enum_tagged!(Shape, "type" => {
+///     Circle = "circle"
+/// });
+///
+/// let Shape::Circle(circle) = serde::json::from_str::<Shape>(r#"{ "type": "circle", "radius": 1 }"#).unwrap();
+/// assert_eq!(circle.radius, 1);
+/// ```
+#[macro_export]
+macro_rules! impl_deserialize_enum_tagged {
+    ($enum:ident, $tag:literal => { $($variant:ident $(= $name:literal)?),* $(,)? }) => {
+        impl $crate::de::Deserialize for $enum {
+            fn deserialize<D: $crate::de::Deserializer>(
+                deserializer: D,
+            ) -> Result<Self, $crate::de::Error> {
+                use $crate::json::value::JsonValue;
+
+                static KNOWN_VARIANTS: &[&str] = &[
+                    $(
+                        $crate::impl_deserialize_enum_tagged!(@name $variant $(= $name)?)
+                    ),*
+                ];
+
+                let mut map = match <JsonValue as $crate::de::Deserialize>::deserialize(deserializer)? {
+                    JsonValue::Object(map) => map,
+                    _ => {
+                        return Err($crate::de::Error::other(concat!("expected object for enum ", stringify!($enum))));
+                    }
+                };
+
+                let variant = match map.remove($tag) {
+                    Some(JsonValue::String(variant)) => variant,
+                    Some(_) => {
+                        return Err($crate::de::Error::other(concat!("expected string for tag field '", $tag, "'")));
+                    }
+                    None => {
+                        return Err($crate::de::Error::other(concat!("missing tag field '", $tag, "'")));
+                    }
+                };
+
+                let rest = JsonValue::Object(map);
+
+                match variant.as_str() {
+                    $(
+                        v if v == $crate::impl_deserialize_enum_tagged!(@name $variant $(= $name)?) => {
+                            $crate::de::Deserialize::deserialize(rest).map($enum :: $variant)
+                        }
+                    )*
+                    v => {
+                        Err($crate::de::Error::other(format!(
+                            "Unknown enum variant `{v}`, valid variants: {KNOWN_VARIANTS:?}"
+                        )))
+                    },
+                }
+            }
+        }
+    };
+
+    (@name $variant:ident) => {
+        stringify!($variant)
+    };
+
+    (@name $variant:ident = $name:literal) => {
+        $name
+    };
+}
+
+/// Implement `Deserialize` for an untagged enum with newtype variants.
+///
+/// The input is buffered as a `JsonValue` and each variant is tried in order, the first that deserializes is returned.
+///
+/// ```
+/// enum Id {
+///     Number(u64),
+///     Text(String),
+/// }
+///
+/// serde::impl_deserialize_enum_untagged!(Id => { Number, Text });
+///
+/// let Id::Text(id) = serde::json::from_str::<Id>(r#""abc""#).unwrap() else {
+///     panic!("expected text id");
+/// };
+/// assert_eq!(id, "abc");
+/// ```
+#[macro_export]
+macro_rules! impl_deserialize_enum_untagged {
+    ($enum:ident => { $($variant:ident),* $(,)? }) => {
+        impl $crate::de::Deserialize for $enum {
+            fn deserialize<D: $crate::de::Deserializer>(
+                deserializer: D,
+            ) -> Result<Self, $crate::de::Error> {
+                use $crate::json::value::JsonValue;
+
+                let value = <JsonValue as $crate::de::Deserialize>::deserialize(deserializer)?;
+                let mut errors = Vec::new();
+
+                $(
+                    match $crate::de::Deserialize::deserialize(value.clone()) {
+                        Ok(x) => return Ok($enum :: $variant(x)),
+                        Err(err) => errors.push(format!("{}: {err}", stringify!($variant))),
+                    }
+                )*
+
+                Err($crate::de::Error::other(format!(
+                    "data did not match any variant of untagged enum {}: {}",
+                    stringify!($enum),
+                    errors.join("; ")
+                )))
+            }
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
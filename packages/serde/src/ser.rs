@@ -1,6 +1,7 @@
 use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     rc::Rc,
     sync::{
         atomic::{
@@ -13,6 +14,8 @@ use std::{
 
 use orderedmap::OrderedMap;
 
+use crate::impossible::Impossible;
+
 /// Serialize a sequence of items like a `Vec<T>` or array,
 pub trait SequenceSerializer {
     type Ok;
@@ -157,6 +160,70 @@ pub trait Serializer: Sized {
     fn serialize_map(self) -> Result<Self::Map, Self::Err>;
 }
 
+/// Returns `true` if the value is serialized as none, like `Option::None`.
+pub fn is_none<T: Serialize + ?Sized>(value: &T) -> bool {
+    struct NoneSerializer;
+
+    impl Serializer for NoneSerializer {
+        type Ok = bool;
+        type Err = std::fmt::Error;
+        type Bytes = Impossible<Self::Ok, Self::Err>;
+        type Seq = Impossible<Self::Ok, Self::Err>;
+        type Map = Impossible<Self::Ok, Self::Err>;
+
+        fn serialize_unit(self) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_i128(self, _value: i128) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_u128(self, _value: u128) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_f32(self, _value: f32) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_f64(self, _value: f64) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_bool(self, _value: bool) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_str(self, _value: &str) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        fn serialize_none(self) -> Result<Self::Ok, Self::Err> {
+            Ok(true)
+        }
+
+        fn serialize_slice<T: Serialize>(self, _value: &[T]) -> Result<Self::Ok, Self::Err> {
+            Ok(false)
+        }
+
+        // Collections are never none, we fail to avoid serializing them
+        fn serialize_byte_seq(self) -> Result<Self::Bytes, Self::Err> {
+            Err(std::fmt::Error)
+        }
+
+        fn serialize_sequence(self) -> Result<Self::Seq, Self::Err> {
+            Err(std::fmt::Error)
+        }
+
+        fn serialize_map(self) -> Result<Self::Map, Self::Err> {
+            Err(std::fmt::Error)
+        }
+    }
+
+    matches!(value.serialize(NoneSerializer), Ok(true))
+}
+
 /// Allow a type to be serialize to other.
 pub trait Serialize {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Err>;
@@ -428,6 +495,20 @@ impl<T: Serialize> Serialize for RwLock<T> {
     }
 }
 
+macro_rules! impl_serialize_display {
+    ($($T:ty),*) => {
+        $(
+            impl Serialize for $T {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Err> {
+                    serializer.serialize_str(&self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize_display!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr);
+
 macro_rules! impl_serialize_tuple {
     ($($T:ident),*) => {
         #[allow(non_snake_case)]
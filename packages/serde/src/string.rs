@@ -275,9 +275,8 @@ impl Deserializer for DeserializeFromStr {
         V: Visitor,
     {
         match self {
-            DeserializeFromStr::Str(_) => {
-                Err(crate::de::Error::other("cannot deserialize str to `map`"))
-            }
+            // A single value is a sequence of one element
+            DeserializeFromStr::Str(s) => visitor.visit_seq(FromStrSeqAccess(vec![s].into_iter())),
             DeserializeFromStr::List(vec) => visitor.visit_seq(FromStrSeqAccess(vec.into_iter())),
         }
     }